@@ -14,6 +14,7 @@ pub fn parse(str: &str) -> Result<JsValue, JsError> {
         Expr::String(v) => v.into(),
         Expr::Bool(v) => v.into(),
         Expr::Array(v) => array_to_string(Expr::Array(v)).into(),
+        Expr::Error(v) => v.to_string().into(),
         Expr::Null => JsValue::null(),
     };
 
@@ -35,6 +36,7 @@ fn array_to_string(arr: Expr) -> String {
         Expr::Time(v) => format!("\"{v}\""),
         Expr::Number(v) => v.to_string(),
         Expr::Bool(v) => v.to_string(),
+        Expr::Error(v) => format!("\"{v}\""),
         Expr::Null => "null".to_string(),
     }
 }
@@ -0,0 +1,294 @@
+use crate::{
+    context::{Cell, Reference, MAX_COLUMNS, MAX_ROWS},
+    error::Error,
+    parsers::operator::pratt_parser,
+    ErrorKind, Result, Rule,
+};
+use pest::iterators::Pair;
+
+/// `Node` is a node of the abstract syntax tree of a formula.
+///
+/// The tree is owned, so it doesn't depend on the formula string, and it can be cloned,
+/// stored and sent across threads. Operators, like `1+2`, are stored as their `F.` functions, like `F.ADD(1, 2)`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Node {
+    Number(f64),
+    String(String),
+    Bool(bool),
+    /// An error value, e.g. `#N/A`
+    Error(ErrorKind),
+    /// An array as a list of rows, e.g. `{1, 2; 3, 4}`
+    Array(Vec<Vec<Node>>),
+    /// A reference to a cell or a range, e.g. `A1:C10`
+    Reference(Reference),
+    /// A name, like a variable or a named range, e.g. `tax_rate`
+    Name(String),
+    /// A function or an operator with its arguments, e.g. `SUM(1, 2)`
+    Function {
+        rule: Rule,
+        args: Vec<Node>,
+    },
+}
+
+impl Node {
+    /// Return whether it's an omitted argument of a function, like the match mode of `XMATCH(2, A1:A3, , -1)`
+    pub(crate) fn is_empty(&self) -> bool {
+        matches!(self, Node::Function { rule: Rule::empty, .. })
+    }
+
+    pub(crate) fn from_pair(pair: Pair<Rule>) -> Result<Self> {
+        let node = match pair.as_rule() {
+            Rule::expr => Self::from_expr(pair)?,
+            Rule::num => Self::from_num(pair)?,
+            Rule::string => Self::from_string(pair),
+            Rule::bool_true => Node::Bool(true),
+            Rule::bool_false => Node::Bool(false),
+            Rule::error => {
+                Node::Error(ErrorKind::from_literal(pair.as_str()).ok_or_else(|| Error::Parser("error".to_owned()))?)
+            }
+            Rule::array => Self::from_array(pair)?,
+            Rule::cell => Node::Reference(Reference::Cell(Self::to_cell(pair)?)),
+            Rule::cell_range => Self::from_cell_range(pair)?,
+            Rule::column_range | Rule::row_range => Self::from_line_range(pair)?,
+            Rule::name => Node::Name(pair.as_str().to_string()),
+            rule => Node::Function {
+                rule,
+                args: pair.into_inner().map(Self::from_pair).collect::<Result<Vec<_>>>()?,
+            },
+        };
+        Ok(node)
+    }
+
+    fn from_expr(pair: Pair<Rule>) -> Result<Self> {
+        let mut pairs = pair.into_inner();
+        // A single operand doesn't need the pratt parser
+        if pairs.len() == 1 {
+            return Self::from_pair(pairs.next().ok_or_else(|| Error::Parser("expr".to_owned()))?);
+        }
+
+        pratt_parser()
+            .map_primary(Self::from_pair)
+            .map_prefix(|op, operand| match op.as_rule() {
+                Rule::neg_op => Ok(Node::Function {
+                    rule: Rule::negate,
+                    args: vec![operand?],
+                }),
+                Rule::pos_op => operand,
+                rule => unreachable!("{rule:?} is not a prefix operator"),
+            })
+            .map_postfix(|operand, op| match op.as_rule() {
+                Rule::percent_op => Ok(Node::Function {
+                    rule: Rule::percent,
+                    args: vec![operand?],
+                }),
+                rule => unreachable!("{rule:?} is not a postfix operator"),
+            })
+            .map_infix(|operand1, op, operand2| {
+                let rule = match op.as_rule() {
+                    Rule::add_op => Rule::add,
+                    Rule::sub_op => Rule::sub,
+                    Rule::mul_op => Rule::mul,
+                    Rule::div_op => Rule::div,
+                    Rule::pow_op => Rule::pow,
+                    Rule::concat_op => Rule::concat,
+                    Rule::eq_op => Rule::eq,
+                    Rule::ne_op => Rule::ne,
+                    Rule::lt_op => Rule::lt,
+                    Rule::gt_op => Rule::gt,
+                    Rule::lte_op => Rule::lte,
+                    Rule::gte_op => Rule::gte,
+                    rule => unreachable!("{rule:?} is not an infix operator"),
+                };
+                Ok(Node::Function {
+                    rule,
+                    args: vec![operand1?, operand2?],
+                })
+            })
+            .parse(pairs)
+    }
+
+    fn from_num(pair: Pair<Rule>) -> Result<Self> {
+        let number = pair
+            .as_str()
+            .trim()
+            .parse()
+            .map_err(|_| Error::Parser("num".to_owned()))?;
+        Ok(Node::Number(number))
+    }
+
+    fn from_string(pair: Pair<Rule>) -> Self {
+        let string = pair.into_inner().as_str().to_string();
+        let string = string
+            .replace("\\\'", "\'")
+            .replace("\\\"", "\"")
+            .replace("\\\\", "\"")
+            .replace("\\b", "\u{0008}")
+            .replace("\\f", "\u{000C}")
+            .replace("\\\n", "\n")
+            .replace("\\\r", "\r")
+            .replace("\\\t", "\t");
+        Node::String(string)
+    }
+
+    fn from_array(pair: Pair<Rule>) -> Result<Self> {
+        let mut table = Vec::new();
+        let mut row = Vec::new();
+
+        for ipair in pair.into_inner() {
+            match ipair.as_rule() {
+                Rule::array_col_sep => {}
+                Rule::array_row_sep => {
+                    table.push(row);
+                    row = Vec::new();
+                }
+                _ => row.push(Self::from_pair(ipair)?),
+            }
+        }
+
+        if !row.is_empty() || !table.is_empty() {
+            table.push(row);
+        }
+        Ok(Node::Array(table))
+    }
+
+    fn from_cell_range(pair: Pair<Rule>) -> Result<Self> {
+        let mut cells = pair.into_inner().map(Self::to_cell);
+        let error = || Error::Parser("cell_range".to_owned());
+        let start = cells.next().ok_or_else(error)??;
+        let end = cells.next().ok_or_else(error)??;
+
+        let (top, bottom) = if start.row <= end.row {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        let (left, right) = if start.column <= end.column {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        let start = Cell {
+            column: left.column,
+            absolute_column: left.absolute_column,
+            row: top.row,
+            absolute_row: top.absolute_row,
+        };
+        let end = Cell {
+            column: right.column,
+            absolute_column: right.absolute_column,
+            row: bottom.row,
+            absolute_row: bottom.absolute_row,
+        };
+        Ok(Node::Reference(Reference::Range(start, end)))
+    }
+
+    fn from_line_range(pair: Pair<Rule>) -> Result<Self> {
+        let rule = pair.as_rule();
+        let lines = pair
+            .into_inner()
+            .map(|line| match line.as_rule() {
+                Rule::column => Self::to_column(line.as_str()),
+                _ => Self::to_row(line.as_str()),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let (start, end) = match lines[..] {
+            [start, end] => (start.min(end), start.max(end)),
+            _ => return Err(Error::Parser(format!("{rule:?}"))),
+        };
+
+        let reference = if rule == Rule::column_range {
+            Reference::Columns(start, end)
+        } else {
+            Reference::Rows(start, end)
+        };
+        Ok(Node::Reference(reference))
+    }
+
+    fn to_cell(pair: Pair<Rule>) -> Result<Cell> {
+        let mut inner = pair.into_inner();
+        let error = || Error::Parser("cell".to_owned());
+        let column = inner.next().ok_or_else(error)?.as_str();
+        let row = inner.next().ok_or_else(error)?.as_str();
+
+        Ok(Cell {
+            column: Self::to_column(column)?,
+            row: Self::to_row(row)?,
+            absolute_column: column.starts_with('$'),
+            absolute_row: row.starts_with('$'),
+        })
+    }
+
+    fn to_column(column: &str) -> Result<u32> {
+        let column = column.trim_start_matches('$').chars().fold(0, |column, c| {
+            column * 26 + (u32::from(c.to_ascii_uppercase()) - u32::from('A') + 1)
+        });
+        if column > MAX_COLUMNS {
+            return Err(Error::Parser("column".to_owned()));
+        }
+        Ok(column)
+    }
+
+    fn to_row(row: &str) -> Result<u32> {
+        match row.trim_start_matches('$').parse() {
+            Ok(row) if row <= MAX_ROWS => Ok(row),
+            _ => Err(Error::Parser("row".to_owned())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Expr, Formula};
+    use std::thread;
+
+    #[test]
+    fn test_ast() {
+        let formula = Formula::new("=-1+SUM(2, 3)*4%").unwrap();
+        assert_eq!(
+            formula.ast(),
+            &Node::Function {
+                rule: Rule::add,
+                args: vec![
+                    Node::Function {
+                        rule: Rule::negate,
+                        args: vec![Node::Number(1.0)]
+                    },
+                    Node::Function {
+                        rule: Rule::mul,
+                        args: vec![
+                            Node::Function {
+                                rule: Rule::sum,
+                                args: vec![Node::Number(2.0), Node::Number(3.0)]
+                            },
+                            Node::Function {
+                                rule: Rule::percent,
+                                args: vec![Node::Number(4.0)]
+                            },
+                        ]
+                    },
+                ]
+            }
+        );
+
+        let formula = Formula::new("={'a', TRUE; 1, 2}").unwrap();
+        assert_eq!(
+            formula.ast(),
+            &Node::Array(vec![
+                vec![Node::String("a".to_string()), Node::Bool(true)],
+                vec![Node::Number(1.0), Node::Number(2.0)],
+            ])
+        );
+
+        // A compiled formula can be evaluated many times, and on other threads
+        let formula = Formula::new("=UPPER('a')&LEN('abc')").unwrap();
+        let cloned = formula.clone();
+        let handle = thread::spawn(move || cloned.parse().unwrap());
+        assert_eq!(handle.join().unwrap(), Expr::String("A3".to_string()));
+        assert_eq!(formula.parse().unwrap(), Expr::String("A3".to_string()));
+        assert_eq!(formula.parse().unwrap(), Expr::String("A3".to_string()));
+
+        let formula = Formula::from(formula.ast().clone());
+        assert_eq!(formula.parse().unwrap(), Expr::String("A3".to_string()));
+    }
+}
@@ -0,0 +1,292 @@
+use crate::Expr;
+use std::{collections::HashMap, fmt};
+
+/// The maximum number of columns of a sheet, like Excel, which is `XFD`
+pub const MAX_COLUMNS: u32 = 16_384;
+/// The maximum number of rows of a sheet, like Excel
+pub const MAX_ROWS: u32 = 1_048_576;
+
+/// `Cell` is a cell of a sheet, like `A1` or `$B$2`.
+///
+/// Columns and rows start from 1, so `B3` is the column 2 and the row 3.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Cell {
+    pub column: u32,
+    pub row: u32,
+    pub absolute_column: bool,
+    pub absolute_row: bool,
+}
+
+/// `Reference` is a reference to the cells of a sheet, which is resolved by an [`EvalContext`].
+///
+/// Ranges are normalized, so `C10:A1` is the same as `A1:C10`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Reference {
+    /// A single cell, like `A1`
+    Cell(Cell),
+    /// A range of cells, from the top left to the bottom right cell, like `A1:C10`
+    Range(Cell, Cell),
+    /// A range of whole columns, like `A:A` or `A:C`
+    Columns(u32, u32),
+    /// A range of whole rows, like `1:1` or `1:3`
+    Rows(u32, u32),
+}
+
+/// `EvalContext` resolves the references of a formula, so formulas can use the data of your own storage.
+///
+/// A single cell should be resolved to its value and a range to an `Expr::Array` of rows,
+/// the same as an array in a formula, like `{1, 2; 3, 4}`. You can use [`Expr::from_rows`] for it.
+/// Empty cells should be resolved to `Expr::Null`.
+///
+/// Names, like `tax_rate`, are resolved by [`EvalContext::resolve_name`]. A named range can be resolved
+/// to the value of its reference, like `Some(self.resolve(&reference))`.
+/// It's implemented for `HashMap<String, Expr>`, so you can use a map of variables as a context.
+///
+/// It's implemented for closures, so you can use a closure as a context:
+///
+/// ```rust
+/// use formula::{context::Reference, Expr, Formula, Result};
+///
+/// fn main() -> Result<()> {
+///     let context = |reference: &Reference| match reference {
+///         Reference::Cell(cell) => Expr::Number(f64::from(cell.row)),
+///         _ => Expr::from_rows(vec![vec![Expr::Number(1.0)], vec![Expr::Number(2.0)]]),
+///     };
+///     let formula = Formula::new("=SUM(A1:A2, B3)")?;
+///     let value = formula.parse_with_context(&context)?;
+///     assert_eq!(value, Expr::Number(6.0));
+///     Ok(())
+/// }
+/// ```
+pub trait EvalContext {
+    fn resolve(&self, reference: &Reference) -> Expr;
+
+    /// Resolves a name, or returns `None` if the name is not defined
+    fn resolve_name(&self, _name: &str) -> Option<Expr> {
+        None
+    }
+}
+
+impl<F: Fn(&Reference) -> Expr> EvalContext for F {
+    fn resolve(&self, reference: &Reference) -> Expr {
+        self(reference)
+    }
+}
+
+/// Names are case-insensitive, like Excel, and cells are resolved as empty cells
+impl EvalContext for HashMap<String, Expr> {
+    fn resolve(&self, _reference: &Reference) -> Expr {
+        Expr::Null
+    }
+
+    fn resolve_name(&self, name: &str) -> Option<Expr> {
+        self.get(name)
+            .or_else(|| {
+                self.iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                    .map(|(_, value)| value)
+            })
+            .cloned()
+    }
+}
+
+/// The context of the formulas without a context, which resolves all cells as empty cells
+pub(crate) struct NoContext;
+
+impl EvalContext for NoContext {
+    fn resolve(&self, _reference: &Reference) -> Expr {
+        Expr::Null
+    }
+}
+
+impl Cell {
+    pub(crate) fn column_name(column: u32) -> String {
+        let mut name = Vec::new();
+        let mut column = column;
+        while column > 0 {
+            let rem = (column - 1) % 26;
+            name.push(char::from(b'A' + rem as u8));
+            column = (column - 1) / 26;
+        }
+        name.iter().rev().collect()
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dollar = |absolute| if absolute { "$" } else { "" };
+        write!(
+            f,
+            "{}{}{}{}",
+            dollar(self.absolute_column),
+            Self::column_name(self.column),
+            dollar(self.absolute_row),
+            self.row
+        )
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reference::Cell(cell) => write!(f, "{cell}"),
+            Reference::Range(start, end) => write!(f, "{start}:{end}"),
+            Reference::Columns(start, end) => write!(f, "{}:{}", Cell::column_name(*start), Cell::column_name(*end)),
+            Reference::Rows(start, end) => write!(f, "{start}:{end}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ast::Node, Formula};
+
+    #[test]
+    fn test_references() {
+        let formula = Formula::new("=$B$2").unwrap();
+        let cell = Cell {
+            column: 2,
+            row: 2,
+            absolute_column: true,
+            absolute_row: true,
+        };
+        assert_eq!(formula.ast(), &Node::Reference(Reference::Cell(cell)));
+
+        let formula = Formula::new("=xfd1048576").unwrap();
+        let cell = Cell {
+            column: MAX_COLUMNS,
+            row: MAX_ROWS,
+            absolute_column: false,
+            absolute_row: false,
+        };
+        assert_eq!(formula.ast(), &Node::Reference(Reference::Cell(cell)));
+
+        assert!(Formula::new("=XFE1").is_err());
+        assert!(Formula::new("=A0").is_err());
+
+        let references = [
+            ("=A1", "A1"),
+            ("=$AA$10", "$AA$10"),
+            ("=A1:C10", "A1:C10"),
+            ("=C10:A1", "A1:C10"),
+            ("=A:A", "A:A"),
+            ("=$Z:AB", "Z:AB"),
+            ("=1:1", "1:1"),
+            ("=3:$2", "2:3"),
+        ];
+        for (formula, reference) in references {
+            match Formula::new(formula).unwrap().ast() {
+                Node::Reference(r) => assert_eq!(r.to_string(), reference),
+                node => panic!("{node:?} is not a reference"),
+            }
+        }
+
+        let context = |reference: &Reference| match reference {
+            Reference::Cell(cell) => Expr::Number(f64::from(cell.row * 10 + cell.column)),
+            Reference::Range(start, end) => Expr::from_rows(
+                (start.row..=end.row)
+                    .map(|row| {
+                        (start.column..=end.column)
+                            .map(|column| Expr::Number(f64::from(row * 10 + column)))
+                            .collect()
+                    })
+                    .collect(),
+            ),
+            Reference::Columns(..) => Expr::Array(vec![Expr::Number(1.0), Expr::String("a".to_string())]),
+            Reference::Rows(..) => Expr::Null,
+        };
+
+        let formula = Formula::new("=A1+B2*2").unwrap();
+        let value = formula.parse_with_context(&context).unwrap();
+        assert_eq!(value, Expr::Number(55.0));
+
+        let formula = Formula::new("=A1:B2").unwrap();
+        let value = formula.parse_with_context(&context).unwrap();
+        assert_eq!(
+            value,
+            Expr::Array(vec![
+                Expr::Array(vec![Expr::Number(11.0), Expr::Number(12.0)]),
+                Expr::Array(vec![Expr::Number(21.0), Expr::Number(22.0)]),
+            ])
+        );
+
+        let formula = Formula::new("=SUM(A1:A10)").unwrap();
+        let value = formula.parse_with_context(&context).unwrap();
+        assert_eq!(value, Expr::Number(560.0));
+
+        let formula = Formula::new("=SUM(A:A, 1:1, 2)").unwrap();
+        let value = formula.parse_with_context(&context).unwrap();
+        assert_eq!(value, Expr::Number(3.0));
+
+        // Without a context, all cells are empty
+        let formula = Formula::new("=A1").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Null);
+
+        // Names
+        let formula = Formula::new("=tax_rate * price").unwrap();
+        assert_eq!(
+            formula.ast(),
+            &Node::Function {
+                rule: crate::Rule::mul,
+                args: vec![Node::Name("tax_rate".to_string()), Node::Name("price".to_string())]
+            }
+        );
+
+        let variables = HashMap::from([
+            ("tax_rate".to_string(), Expr::Number(0.2)),
+            ("Price".to_string(), Expr::Number(50.0)),
+            ("T".to_string(), Expr::String("t".to_string())),
+            ("PI".to_string(), Expr::Number(3.0)),
+            ("true_value".to_string(), Expr::Bool(true)),
+            (
+                "values".to_string(),
+                Expr::Array(vec![Expr::Number(1.0), Expr::Number(2.0)]),
+            ),
+        ]);
+        let names = [
+            ("=tax_rate * price", Expr::Number(10.0)),
+            ("=TAX_RATE*PRICE", Expr::Number(10.0)),
+            ("=T", Expr::String("t".to_string())),
+            ("=T(T)", Expr::String("t".to_string())),
+            ("=PI", Expr::Number(3.0)),
+            ("=PI()", Expr::Number(std::f64::consts::PI)),
+            ("=true_value", Expr::Bool(true)),
+            ("=TRUE", Expr::Bool(true)),
+            ("=SUM(values, 3)", Expr::Number(6.0)),
+        ];
+        for (formula, value) in names {
+            let formula = Formula::new(formula).unwrap();
+            assert_eq!(formula.parse_with_variables(&variables).unwrap(), value);
+        }
+
+        let formula = Formula::new("=tax_rate * unknown").unwrap();
+        let error = formula.parse_with_variables(&variables).unwrap_err();
+        assert!(matches!(error, crate::error::Error::UnknownName(name) if name == "unknown"));
+        assert!(Formula::new("=price").unwrap().parse().is_err());
+
+        // A named range is resolved by its reference
+        struct Sheet;
+        impl EvalContext for Sheet {
+            fn resolve(&self, reference: &Reference) -> Expr {
+                match reference {
+                    Reference::Cell(cell) => Expr::Number(f64::from(cell.row)),
+                    _ => Expr::Null,
+                }
+            }
+
+            fn resolve_name(&self, name: &str) -> Option<Expr> {
+                let cell = Cell {
+                    column: 1,
+                    row: 2,
+                    absolute_column: true,
+                    absolute_row: true,
+                };
+                (name == "first").then(|| self.resolve(&Reference::Cell(cell)))
+            }
+        }
+        let formula = Formula::new("=first + A3").unwrap();
+        assert_eq!(formula.parse_with_context(&Sheet).unwrap(), Expr::Number(5.0));
+    }
+}
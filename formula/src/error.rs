@@ -1,13 +1,21 @@
+use crate::ErrorKind;
 use thiserror::Error;
 
 /// Error type for the crate
 ///
 /// `Error::Parser` means the formula is not valid
 /// `Error::NotImplemented` means there is a function that is not implemented yet
+/// `Error::UnknownName` means there is a name in the formula that the context cannot resolve
+/// `Error::Excel` means an argument of a function is an error value, like `#N/A`.
+/// It's propagated as an `Expr::Error` by the functions, so it's not returned by `Formula::parse`
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Parser Error: Invalid `{0}` expression")]
     Parser(String),
     #[error("Not Implemented Yet: `{0}` function is not implemented yet")]
     NotImplemented(String),
+    #[error("Unknown Name: `{0}` is not defined")]
+    UnknownName(String),
+    #[error("Excel Error: `{0}`")]
+    Excel(ErrorKind),
 }
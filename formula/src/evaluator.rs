@@ -0,0 +1,531 @@
+use crate::{ast::Node, context::EvalContext, error::Error, ErrorKind, Expr, Result, Rule};
+
+/// `Evaluator` walks the abstract syntax tree of a formula and evaluates it.
+/// The references of the formula are resolved by its context.
+pub(crate) struct Evaluator<'a> {
+    pub(crate) context: &'a dyn EvalContext,
+}
+
+impl Evaluator<'_> {
+    pub(crate) fn parse_node(&self, node: &Node) -> Result<Expr> {
+        match node {
+            Node::Number(number) => Ok(Expr::Number(*number)),
+            Node::String(string) => Ok(Expr::String(string.clone())),
+            Node::Bool(bool) => Ok(Expr::Bool(*bool)),
+            Node::Error(kind) => Ok(Expr::Error(*kind)),
+            Node::Array(rows) => self.parse_array(rows),
+            Node::Reference(reference) => Ok(self.context.resolve(reference)),
+            Node::Name(name) => self
+                .context
+                .resolve_name(name)
+                .ok_or_else(|| Error::UnknownName(name.clone())),
+            // The error values of the arguments are propagated, and the invalid numbers are `#NUM!`, like Excel
+            Node::Function { rule, args } => match self.parse_function(*rule, args) {
+                Ok(Expr::Number(number)) if !number.is_finite() => Ok(Expr::Error(ErrorKind::Num)),
+                Err(Error::Excel(kind)) => Ok(Expr::Error(kind)),
+                value => value,
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn parse_function(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        #[allow(clippy::match_same_arms)]
+        let res = match rule {
+            // Operators
+            Rule::add => self.parse_add(rule, args)?,
+            Rule::sub => self.parse_sub(rule, args)?,
+            Rule::mul => self.parse_mul(rule, args)?,
+            Rule::div => self.parse_div(rule, args)?,
+            Rule::pow => self.parse_pow(rule, args)?,
+            Rule::eq => self.parse_eq(rule, args)?,
+            Rule::ne => self.parse_ne(rule, args)?,
+            Rule::gt => self.parse_gt(rule, args)?,
+            Rule::lt => self.parse_lt(rule, args)?,
+            Rule::gte => self.parse_gte(rule, args)?,
+            Rule::lte => self.parse_lte(rule, args)?,
+            Rule::percent => self.parse_percent(rule, args)?,
+            Rule::negate => self.parse_negate(rule, args)?,
+
+            // Date and time functions
+            Rule::date => self.parse_date(rule, args)?,
+            Rule::time => self.parse_time(rule, args)?,
+            Rule::year => self.parse_year(rule, args)?,
+            Rule::month => self.parse_month(rule, args)?,
+            Rule::day => self.parse_day(rule, args)?,
+            Rule::days => self.parse_days(rule, args)?,
+            Rule::edate => self.parse_edate(rule, args)?,
+            Rule::eomonth => self.parse_eomonth(rule, args)?,
+            Rule::datevalue => self.parse_datevalue(rule, args)?,
+            Rule::timevalue => self.parse_timevalue(rule, args)?,
+            Rule::hour => self.parse_hour(rule, args)?,
+            Rule::minute => self.parse_minute(rule, args)?,
+            Rule::second => self.parse_second(rule, args)?,
+            Rule::isoweeknum => self.parse_isoweeknum(rule, args)?,
+            Rule::weeknum => self.parse_weeknum(rule, args)?,
+            Rule::weekday => self.parse_weekday(rule, args)?,
+            Rule::now => self.parse_now()?,
+            Rule::today => self.parse_today()?,
+            // TODO remaining date and time functions
+            Rule::networkdays
+            | Rule::networkdaysintl
+            | Rule::workdays
+            | Rule::workdaysintl
+            | Rule::yearfrac
+            | Rule::days360
+            | Rule::datediff => return Err(Error::NotImplemented(format!("{rule:?}"))),
+
+            // Text functions
+            Rule::left => self.parse_left(rule, args)?,
+            Rule::leftb => self.parse_leftb(rule, args)?,
+            Rule::right => self.parse_right(rule, args)?,
+            Rule::rightb => self.parse_rightb(rule, args)?,
+            Rule::mid => self.parse_mid(rule, args)?,
+            Rule::midb => self.parse_midb(rule, args)?,
+            Rule::char | Rule::unichar => self.parse_char(rule, args)?,
+            Rule::code | Rule::unicode => self.parse_code(rule, args)?,
+            Rule::concat | Rule::concatenate => self.parse_concat(rule, args)?,
+            Rule::exact => self.parse_exact(rule, args)?,
+            Rule::find => self.parse_find(rule, args)?,
+            Rule::findb => self.parse_findb(rule, args)?,
+            Rule::search => self.parse_search(rule, args)?,
+            Rule::searchb => self.parse_searchb(rule, args)?,
+            Rule::fixed => self.parse_fixed(rule, args)?,
+            Rule::len => self.parse_len(rule, args)?,
+            Rule::lenb => self.parse_lenb(rule, args)?,
+            Rule::lower => self.parse_lower(rule, args)?,
+            Rule::upper => self.parse_upper(rule, args)?,
+            Rule::rept => self.parse_rept(rule, args)?,
+            Rule::replace => self.parse_replace(rule, args)?,
+            Rule::replaceb => self.parse_replaceb(rule, args)?,
+            Rule::textjoin => self.parse_textjoin(rule, args)?,
+            Rule::trim => self.parse_trim(rule, args)?,
+            Rule::t => self.parse_t(rule, args)?,
+            Rule::proper => self.parse_proper(rule, args)?,
+            // TODO remaining text functions
+            Rule::arraytotext
+            | Rule::asc
+            | Rule::clean
+            | Rule::dbcs
+            | Rule::dollar
+            | Rule::jis
+            | Rule::text
+            | Rule::numbervalue
+            | Rule::phonetic
+            | Rule::substitute
+            | Rule::textafter
+            | Rule::textbefore
+            | Rule::textsplit
+            | Rule::value
+            | Rule::valuetotext
+            | Rule::bahttext => return Err(Error::NotImplemented(format!("{rule:?}"))),
+            // Engineering functions
+            Rule::bin2dec => self.parse_bin2dec(rule, args)?,
+            Rule::bin2hex => self.parse_bin2hex(rule, args)?,
+            Rule::bin2oct => self.parse_bin2oct(rule, args)?,
+            Rule::dec2bin => self.parse_dec2bin(rule, args)?,
+            Rule::dec2hex => self.parse_dec2hex(rule, args)?,
+            Rule::dec2oct => self.parse_dec2oct(rule, args)?,
+            Rule::hex2bin => self.parse_hex2bin(rule, args)?,
+            Rule::hex2dec => self.parse_hex2dec(rule, args)?,
+            Rule::hex2oct => self.parse_hex2oct(rule, args)?,
+            Rule::oct2bin => self.parse_oct2bin(rule, args)?,
+            Rule::oct2dec => self.parse_oct2dec(rule, args)?,
+            Rule::oct2hex => self.parse_oct2hex(rule, args)?,
+            Rule::bitand => self.parse_bitand(rule, args)?,
+            Rule::bitor => self.parse_bitor(rule, args)?,
+            Rule::bitxor => self.parse_bitxor(rule, args)?,
+            Rule::bitlshift => self.parse_bitlshift(rule, args)?,
+            Rule::bitrshift => self.parse_bitrshift(rule, args)?,
+            Rule::complex => self.parse_complex(rule, args)?,
+            Rule::imabs => self.parse_imabs(rule, args)?,
+            Rule::imaginary => self.parse_imaginary(rule, args)?,
+            Rule::imargument => self.parse_imargument(rule, args)?,
+            Rule::imconjugate => self.parse_imconjugate(rule, args)?,
+            Rule::imcos => self.parse_imcos(rule, args)?,
+            Rule::imcosh => self.parse_imcosh(rule, args)?,
+            Rule::imcot => self.parse_imcot(rule, args)?,
+            Rule::imcsc => self.parse_imcsc(rule, args)?,
+            Rule::imcsch => self.parse_imcsch(rule, args)?,
+            Rule::imdiv => self.parse_imdiv(rule, args)?,
+            Rule::imexp => self.parse_imexp(rule, args)?,
+            Rule::imln => self.parse_imln(rule, args)?,
+            Rule::imlog10 => self.parse_imlog10(rule, args)?,
+            Rule::imlog2 => self.parse_imlog2(rule, args)?,
+            Rule::impower => self.parse_impower(rule, args)?,
+            Rule::improduct => self.parse_improduct(rule, args)?,
+            Rule::imreal => self.parse_imreal(rule, args)?,
+            Rule::imsec => self.parse_imsec(rule, args)?,
+            Rule::imsech => self.parse_imsech(rule, args)?,
+            Rule::imsin => self.parse_imsin(rule, args)?,
+            Rule::imsinh => self.parse_imsinh(rule, args)?,
+            Rule::imsqrt => self.parse_imsqrt(rule, args)?,
+            Rule::imsub => self.parse_imsub(rule, args)?,
+            Rule::imsum => self.parse_imsum(rule, args)?,
+            Rule::imtan => self.parse_imtan(rule, args)?,
+            Rule::besseli => self.parse_besseli(rule, args)?,
+            Rule::besselj => self.parse_besselj(rule, args)?,
+            Rule::besselk => self.parse_besselk(rule, args)?,
+            Rule::bessely => self.parse_bessely(rule, args)?,
+            Rule::erf => self.parse_erf(rule, args)?,
+            Rule::erf_precise => self.parse_erf_precise(rule, args)?,
+            Rule::erfc => self.parse_erfc(rule, args)?,
+            Rule::erfc_precise => self.parse_erfc_precise(rule, args)?,
+            Rule::delta => self.parse_delta(rule, args)?,
+            Rule::gestep => self.parse_gestep(rule, args)?,
+            Rule::convert => self.parse_convert(rule, args)?,
+            // Financial functions
+            Rule::cumipmt => self.parse_cumipmt(rule, args)?,
+            Rule::cumprinc => self.parse_cumprinc(rule, args)?,
+            Rule::effect => self.parse_effect(rule, args)?,
+            Rule::fv => self.parse_fv(rule, args)?,
+            Rule::fvschedule => self.parse_fvschedule(rule, args)?,
+            Rule::ipmt => self.parse_ipmt(rule, args)?,
+            Rule::nominal => self.parse_nominal(rule, args)?,
+            Rule::nper => self.parse_nper(rule, args)?,
+            Rule::pduration => self.parse_pduration(rule, args)?,
+            Rule::pmt => self.parse_pmt(rule, args)?,
+            Rule::ppmt => self.parse_ppmt(rule, args)?,
+            Rule::pv => self.parse_pv(rule, args)?,
+            Rule::rate => self.parse_rate(rule, args)?,
+            Rule::rri => self.parse_rri(rule, args)?,
+            Rule::irr => self.parse_irr(rule, args)?,
+            Rule::mirr => self.parse_mirr(rule, args)?,
+            Rule::npv => self.parse_npv(rule, args)?,
+            Rule::xirr => self.parse_xirr(rule, args)?,
+            Rule::xnpv => self.parse_xnpv(rule, args)?,
+            Rule::amordegrc => self.parse_amordegrc(rule, args)?,
+            Rule::amorlinc => self.parse_amorlinc(rule, args)?,
+            Rule::db => self.parse_db(rule, args)?,
+            Rule::ddb => self.parse_ddb(rule, args)?,
+            Rule::sln => self.parse_sln(rule, args)?,
+            Rule::syd => self.parse_syd(rule, args)?,
+            Rule::vdb => self.parse_vdb(rule, args)?,
+            Rule::accrint => self.parse_accrint(rule, args)?,
+            Rule::accrintm => self.parse_accrintm(rule, args)?,
+            Rule::coupdaybs => self.parse_coupdaybs(rule, args)?,
+            Rule::coupdays => self.parse_coupdays(rule, args)?,
+            Rule::coupdaysnc => self.parse_coupdaysnc(rule, args)?,
+            Rule::coupncd => self.parse_coupncd(rule, args)?,
+            Rule::coupnum => self.parse_coupnum(rule, args)?,
+            Rule::couppcd => self.parse_couppcd(rule, args)?,
+            Rule::disc => self.parse_disc(rule, args)?,
+            Rule::dollarde => self.parse_dollarde(rule, args)?,
+            Rule::dollarfr => self.parse_dollarfr(rule, args)?,
+            Rule::duration => self.parse_duration(rule, args)?,
+            Rule::intrate => self.parse_intrate(rule, args)?,
+            Rule::mduration => self.parse_mduration(rule, args)?,
+            Rule::oddfprice => self.parse_oddfprice(rule, args)?,
+            Rule::oddfyield => self.parse_oddfyield(rule, args)?,
+            Rule::oddlprice => self.parse_oddlprice(rule, args)?,
+            Rule::oddlyield => self.parse_oddlyield(rule, args)?,
+            Rule::price => self.parse_price(rule, args)?,
+            Rule::pricedisc => self.parse_pricedisc(rule, args)?,
+            Rule::pricemat => self.parse_pricemat(rule, args)?,
+            Rule::received => self.parse_received(rule, args)?,
+            Rule::tbilleq => self.parse_tbilleq(rule, args)?,
+            Rule::tbillprice => self.parse_tbillprice(rule, args)?,
+            Rule::tbillyield => self.parse_tbillyield(rule, args)?,
+            Rule::yield_ => self.parse_yield_(rule, args)?,
+            Rule::yielddisc => self.parse_yielddisc(rule, args)?,
+            Rule::yieldmat => self.parse_yieldmat(rule, args)?,
+            // Information functions
+            Rule::error_type => self.parse_error_type(rule, args)?,
+            Rule::iserror => self.parse_iserror(rule, args)?,
+            Rule::isna => self.parse_isna(rule, args)?,
+            // Logical functions
+            Rule::and => self.parse_and(rule, args)?,
+            Rule::or => self.parse_or(rule, args)?,
+            Rule::xor => self.parse_xor(rule, args)?,
+            Rule::not => self.parse_not(rule, args)?,
+            Rule::if_ => self.parse_if(rule, args)?,
+            Rule::ifna => self.parse_ifna(rule, args)?,
+            Rule::iferror => self.parse_iferror(rule, args)?,
+            // TODO remaining text functions
+            Rule::let_
+            | Rule::bycol
+            | Rule::byrow
+            | Rule::makearray
+            | Rule::reduce
+            | Rule::scan
+            | Rule::map
+            | Rule::lambda
+            | Rule::switch
+            | Rule::ifs => return Err(Error::NotImplemented(format!("{rule:?}"))),
+            // Lookup and reference functions
+            Rule::vlookup => self.parse_vlookup(rule, args)?,
+            Rule::hlookup => self.parse_hlookup(rule, args)?,
+            Rule::lookup => self.parse_lookup(rule, args)?,
+            Rule::index => self.parse_index(rule, args)?,
+            Rule::match_ => self.parse_match(rule, args)?,
+            Rule::xmatch => self.parse_xmatch(rule, args)?,
+            Rule::xlookup => self.parse_xlookup(rule, args)?,
+            Rule::choose => self.parse_choose(rule, args)?,
+            Rule::rows => self.parse_rows(rule, args)?,
+            Rule::columns => self.parse_columns(rule, args)?,
+            Rule::transpose => self.parse_transpose(rule, args)?,
+
+            // Math functions
+            Rule::abs => self.parse_abs(rule, args)?,
+            Rule::acos => self.parse_acos(rule, args)?,
+            Rule::acosh => self.parse_acosh(rule, args)?,
+            Rule::asin => self.parse_asin(rule, args)?,
+            Rule::asinh => self.parse_asinh(rule, args)?,
+            Rule::cos => self.parse_cos(rule, args)?,
+            Rule::cosh => self.parse_cosh(rule, args)?,
+            Rule::sin => self.parse_sin(rule, args)?,
+            Rule::sinh => self.parse_sinh(rule, args)?,
+            Rule::tan => self.parse_tan(rule, args)?,
+            Rule::tanh => self.parse_tanh(rule, args)?,
+            Rule::atan => self.parse_atan(rule, args)?,
+            Rule::atan2 => self.parse_atan2(rule, args)?,
+            Rule::atanh => self.parse_atanh(rule, args)?,
+            Rule::pi => self.parse_pi()?,
+            Rule::power => self.parse_pow(rule, args)?,
+            Rule::mod_ => self.parse_mod(rule, args)?,
+            Rule::log => self.parse_log(rule, args)?,
+            Rule::log10 => self.parse_log10(rule, args)?,
+            Rule::ln => self.parse_ln(rule, args)?,
+            Rule::sqrt => self.parse_sqrt(rule, args)?,
+            Rule::sqrtpi => self.parse_sqrtpi(rule, args)?,
+            Rule::rand => self.parse_rand()?,
+            Rule::sign => self.parse_sign(rule, args)?,
+            Rule::exp => self.parse_exp(rule, args)?,
+            Rule::sum => self.parse_sum(rule, args)?,
+            Rule::sumif => self.parse_sumif(rule, args)?,
+            Rule::sumifs => self.parse_sumifs(rule, args)?,
+            Rule::round => self.parse_round(rule, args)?,
+            Rule::roundup => self.parse_roundup(rule, args)?,
+            Rule::rounddown => self.parse_rounddown(rule, args)?,
+            Rule::mround => self.parse_mround(rule, args)?,
+            Rule::trunc => self.parse_trunc(rule, args)?,
+            Rule::int => self.parse_int(rule, args)?,
+            Rule::ceiling => self.parse_ceiling(rule, args)?,
+            Rule::ceiling_math => self.parse_ceiling_math(rule, args)?,
+            Rule::ceiling_precise | Rule::iso_ceiling => self.parse_ceiling_precise(rule, args)?,
+            Rule::floor => self.parse_floor(rule, args)?,
+            Rule::floor_math => self.parse_floor_math(rule, args)?,
+            Rule::floor_precise => self.parse_floor_precise(rule, args)?,
+            Rule::even => self.parse_even(rule, args)?,
+            Rule::odd => self.parse_odd(rule, args)?,
+            Rule::quotient => self.parse_quotient(rule, args)?,
+            Rule::gcd => self.parse_gcd(rule, args)?,
+            Rule::lcm => self.parse_lcm(rule, args)?,
+            Rule::fact => self.parse_fact(rule, args)?,
+            Rule::factdouble => self.parse_factdouble(rule, args)?,
+            Rule::combin => self.parse_combin(rule, args)?,
+            Rule::combina => self.parse_combina(rule, args)?,
+            Rule::multinomial => self.parse_multinomial(rule, args)?,
+            Rule::seriessum => self.parse_seriessum(rule, args)?,
+            Rule::mmult => self.parse_mmult(rule, args)?,
+            Rule::mdeterm => self.parse_mdeterm(rule, args)?,
+            Rule::minverse => self.parse_minverse(rule, args)?,
+            Rule::munit => self.parse_munit(rule, args)?,
+            Rule::sumproduct => self.parse_sumproduct(rule, args)?,
+            Rule::sumsq => self.parse_sumsq(rule, args)?,
+            Rule::sumx2my2 => self.parse_sumx2my2(rule, args)?,
+            Rule::sumx2py2 => self.parse_sumx2py2(rule, args)?,
+            Rule::sumxmy2 => self.parse_sumxmy2(rule, args)?,
+
+            // TODO remaining text functions
+            Rule::acot
+            | Rule::acoth
+            | Rule::aggregate
+            | Rule::arabic
+            | Rule::base
+            | Rule::cot
+            | Rule::coth
+            | Rule::csc
+            | Rule::csch
+            | Rule::decimal
+            | Rule::degrees
+            | Rule::product
+            | Rule::radians
+            | Rule::randarray
+            | Rule::randbetween
+            | Rule::roman
+            | Rule::sec
+            | Rule::sech
+            | Rule::sequence
+            | Rule::subtotal => return Err(Error::NotImplemented(format!("{rule:?}"))),
+
+            // Statistical functions
+            Rule::average => self.parse_average(rule, args)?,
+            Rule::averagea => self.parse_averagea(rule, args)?,
+            Rule::median => self.parse_median(rule, args)?,
+            Rule::mode_sngl => self.parse_mode_sngl(rule, args)?,
+            Rule::mode_mult => self.parse_mode_mult(rule, args)?,
+            Rule::min => self.parse_min(rule, args)?,
+            Rule::mina => self.parse_mina(rule, args)?,
+            Rule::max => self.parse_max(rule, args)?,
+            Rule::maxa => self.parse_maxa(rule, args)?,
+            Rule::count => self.parse_count(rule, args)?,
+            Rule::counta => self.parse_counta(rule, args)?,
+            Rule::countblank => self.parse_countblank(rule, args)?,
+            Rule::stdev_s => self.parse_stdev_s(rule, args)?,
+            Rule::stdev_p => self.parse_stdev_p(rule, args)?,
+            Rule::var_s => self.parse_var_s(rule, args)?,
+            Rule::var_p => self.parse_var_p(rule, args)?,
+            Rule::avedev => self.parse_avedev(rule, args)?,
+            Rule::devsq => self.parse_devsq(rule, args)?,
+            Rule::geomean => self.parse_geomean(rule, args)?,
+            Rule::harmean => self.parse_harmean(rule, args)?,
+            Rule::kurt => self.parse_kurt(rule, args)?,
+            Rule::skew => self.parse_skew(rule, args)?,
+            Rule::trimmean => self.parse_trimmean(rule, args)?,
+            Rule::countif => self.parse_countif(rule, args)?,
+            Rule::countifs => self.parse_countifs(rule, args)?,
+            Rule::averageif => self.parse_averageif(rule, args)?,
+            Rule::averageifs => self.parse_averageifs(rule, args)?,
+            Rule::maxifs => self.parse_maxifs(rule, args)?,
+            Rule::minifs => self.parse_minifs(rule, args)?,
+            Rule::gamma => self.parse_gamma(rule, args)?,
+            Rule::gammaln => self.parse_gammaln(rule, args)?,
+            Rule::gammaln_precise => self.parse_gammaln_precise(rule, args)?,
+            Rule::norm_dist => self.parse_norm_dist(rule, args)?,
+            Rule::norm_inv => self.parse_norm_inv(rule, args)?,
+            Rule::norm_s_dist => self.parse_norm_s_dist(rule, args)?,
+            Rule::norm_s_inv => self.parse_norm_s_inv(rule, args)?,
+            Rule::lognorm_dist => self.parse_lognorm_dist(rule, args)?,
+            Rule::lognorm_inv => self.parse_lognorm_inv(rule, args)?,
+            Rule::phi => self.parse_phi(rule, args)?,
+            Rule::t_dist => self.parse_t_dist(rule, args)?,
+            Rule::t_dist_2t => self.parse_t_dist_2t(rule, args)?,
+            Rule::t_dist_rt => self.parse_t_dist_rt(rule, args)?,
+            Rule::t_inv => self.parse_t_inv(rule, args)?,
+            Rule::t_inv_2t => self.parse_t_inv_2t(rule, args)?,
+            Rule::chisq_dist => self.parse_chisq_dist(rule, args)?,
+            Rule::chisq_dist_rt => self.parse_chisq_dist_rt(rule, args)?,
+            Rule::chisq_inv => self.parse_chisq_inv(rule, args)?,
+            Rule::chisq_inv_rt => self.parse_chisq_inv_rt(rule, args)?,
+            Rule::f_dist => self.parse_f_dist(rule, args)?,
+            Rule::f_dist_rt => self.parse_f_dist_rt(rule, args)?,
+            Rule::f_inv => self.parse_f_inv(rule, args)?,
+            Rule::f_inv_rt => self.parse_f_inv_rt(rule, args)?,
+            Rule::binom_dist => self.parse_binom_dist(rule, args)?,
+            Rule::binom_inv => self.parse_binom_inv(rule, args)?,
+            Rule::binom_dist_range => self.parse_binom_dist_range(rule, args)?,
+            Rule::negbinom_dist => self.parse_negbinom_dist(rule, args)?,
+            Rule::hypgeom_dist => self.parse_hypgeom_dist(rule, args)?,
+            Rule::poisson_dist => self.parse_poisson_dist(rule, args)?,
+            Rule::expon_dist => self.parse_expon_dist(rule, args)?,
+            Rule::gamma_dist => self.parse_gamma_dist(rule, args)?,
+            Rule::gamma_inv => self.parse_gamma_inv(rule, args)?,
+            Rule::beta_dist => self.parse_beta_dist(rule, args)?,
+            Rule::beta_inv => self.parse_beta_inv(rule, args)?,
+            Rule::weibull_dist => self.parse_weibull_dist(rule, args)?,
+            Rule::confidence_norm => self.parse_confidence_norm(rule, args)?,
+            Rule::confidence_t => self.parse_confidence_t(rule, args)?,
+            Rule::slope => self.parse_slope(rule, args)?,
+            Rule::intercept => self.parse_intercept(rule, args)?,
+            Rule::rsq => self.parse_rsq(rule, args)?,
+            Rule::steyx => self.parse_steyx(rule, args)?,
+            Rule::correl => self.parse_correl(rule, args)?,
+            Rule::pearson => self.parse_pearson(rule, args)?,
+            Rule::covariance_s => self.parse_covariance_s(rule, args)?,
+            Rule::covariance_p => self.parse_covariance_p(rule, args)?,
+            Rule::forecast_linear => self.parse_forecast_linear(rule, args)?,
+            Rule::trend => self.parse_trend(rule, args)?,
+            Rule::growth => self.parse_growth(rule, args)?,
+            Rule::linest => self.parse_linest(rule, args)?,
+            Rule::logest => self.parse_logest(rule, args)?,
+            Rule::t_test => self.parse_t_test(rule, args)?,
+            Rule::z_test => self.parse_z_test(rule, args)?,
+            Rule::chisq_test => self.parse_chisq_test(rule, args)?,
+            Rule::f_test => self.parse_f_test(rule, args)?,
+            Rule::large => self.parse_large(rule, args)?,
+            Rule::small => self.parse_small(rule, args)?,
+            Rule::rank_eq => self.parse_rank_eq(rule, args)?,
+            Rule::rank_avg => self.parse_rank_avg(rule, args)?,
+            Rule::percentile_inc => self.parse_percentile_inc(rule, args)?,
+            Rule::percentile_exc => self.parse_percentile_exc(rule, args)?,
+            Rule::percentrank_inc => self.parse_percentrank_inc(rule, args)?,
+            Rule::percentrank_exc => self.parse_percentrank_exc(rule, args)?,
+            Rule::quartile_inc => self.parse_quartile_inc(rule, args)?,
+            Rule::quartile_exc => self.parse_quartile_exc(rule, args)?,
+            Rule::frequency => self.parse_frequency(rule, args)?,
+            Rule::prob => self.parse_prob(rule, args)?,
+            Rule::permut => self.parse_permut(rule, args)?,
+            Rule::permutationa => self.parse_permutationa(rule, args)?,
+            Rule::forecast_ets => self.parse_forecast_ets(rule, args)?,
+            Rule::forecast_ets_confint => self.parse_forecast_ets_confint(rule, args)?,
+            Rule::forecast_ets_seasonality => self.parse_forecast_ets_seasonality(rule, args)?,
+            Rule::forecast_ets_stat => self.parse_forecast_ets_stat(rule, args)?,
+            // Web functions
+            Rule::encodeurl => self.parse_encodeurl(rule, args)?,
+            Rule::filterxml => self.parse_filterxml(rule, args)?,
+            Rule::webservice => self.parse_webservice(rule, args)?,
+
+            // An omitted argument is empty, like Excel
+            Rule::empty => Expr::Null,
+
+            // Rules which are not functions
+            Rule::num
+            | Rule::string
+            | Rule::bool_true
+            | Rule::bool_false
+            | Rule::array
+            | Rule::expr
+            | Rule::formula
+            | Rule::column
+            | Rule::row
+            | Rule::cell
+            | Rule::cell_range
+            | Rule::column_range
+            | Rule::row_range
+            | Rule::reference_end
+            | Rule::reference
+            | Rule::name_char
+            | Rule::name
+            | Rule::root
+            | Rule::EOI
+            | Rule::group
+            | Rule::operand
+            | Rule::prefix_op
+            | Rule::postfix_op
+            | Rule::infix_op
+            | Rule::neg_op
+            | Rule::pos_op
+            | Rule::percent_op
+            | Rule::pow_op
+            | Rule::mul_op
+            | Rule::div_op
+            | Rule::add_op
+            | Rule::sub_op
+            | Rule::concat_op
+            | Rule::eq_op
+            | Rule::ne_op
+            | Rule::lte_op
+            | Rule::gte_op
+            | Rule::lt_op
+            | Rule::gt_op
+            | Rule::OP
+            | Rule::F
+            | Rule::CF
+            | Rule::CE
+            | Rule::CP
+            | Rule::C
+            | Rule::Q
+            | Rule::inner
+            | Rule::char_
+            | Rule::array_row_sep
+            | Rule::array_col_sep
+            | Rule::error
+            | Rule::basic_types
+            | Rule::operators
+            | Rule::datetime_functions
+            | Rule::text_functions
+            | Rule::engineering_functions
+            | Rule::information_functions
+            | Rule::logical_functions
+            | Rule::financial_functions
+            | Rule::lookup_functions
+            | Rule::math_functions
+            | Rule::statistical_functions
+            | Rule::web_functions
+            | Rule::WHITESPACE => return Err(Error::Parser(format!("{rule:?}"))),
+        };
+
+        Ok(res)
+    }
+}
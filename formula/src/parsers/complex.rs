@@ -0,0 +1,198 @@
+use crate::parsers::helper::to_decimal;
+use std::ops::{Add, Div, Mul, Sub};
+
+/// The suffixes of the imaginary unit, like `3+4i` or `3+4j`
+pub(crate) const SUFFIXES: [char; 2] = ['i', 'j'];
+
+/// A complex number of the engineering functions, which is a text like `3+4i` in Excel
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct Complex {
+    pub(crate) re: f64,
+    pub(crate) im: f64,
+}
+
+impl Complex {
+    pub(crate) fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    fn from_polar(abs: f64, arg: f64) -> Self {
+        Self::new(abs * arg.cos(), abs * arg.sin())
+    }
+
+    /// Parse a complex number, like `3+4i`, `-2.5j`, `i` or `1E-3-i`, with its suffix, if it has any.
+    /// Return `None` if it's not valid, like a text with spaces or a suffix other than `i` or `j`.
+    pub(crate) fn parse(text: &str) -> Option<(Self, Option<char>)> {
+        let Some(body) = text.strip_suffix(SUFFIXES) else {
+            return Some((Self::new(parse_part(text)?, 0.0), None));
+        };
+        let suffix = text.chars().last();
+
+        // The imaginary part starts at the last sign, which isn't the first character or the sign of an exponent
+        let sign = body
+            .char_indices()
+            .rev()
+            .find(|&(index, c)| index > 0 && (c == '+' || c == '-') && !body[..index].ends_with(['e', 'E']))
+            .map(|(index, _)| index);
+        let (re, im) = match sign {
+            Some(index) => (parse_part(&body[..index])?, &body[index..]),
+            None => (0.0, body),
+        };
+        let im = match im {
+            "" | "+" => 1.0,
+            "-" => -1.0,
+            im => parse_part(im)?,
+        };
+        Some((Self::new(re, im), suffix))
+    }
+
+    /// Format the complex number, like `3+4i`, by 15 significant digits, where the parts which are zero,
+    /// and the coefficients which are one, are omitted, like Excel
+    pub(crate) fn to_text(self, suffix: char) -> String {
+        let (re, im) = (to_decimal(self.re), to_decimal(self.im));
+        if im == 0.0 {
+            return format_part(re);
+        }
+        let coefficient = if im == 1.0 {
+            String::new()
+        } else if im == -1.0 {
+            "-".to_owned()
+        } else {
+            format_part(im)
+        };
+        if re == 0.0 {
+            format!("{coefficient}{suffix}")
+        } else if im > 0.0 {
+            format!("{}+{coefficient}{suffix}", format_part(re))
+        } else {
+            format!("{}{coefficient}{suffix}", format_part(re))
+        }
+    }
+
+    pub(crate) fn is_finite(self) -> bool {
+        self.re.is_finite() && self.im.is_finite()
+    }
+
+    pub(crate) fn abs(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// Return the angle of the complex number, in radians, from `-PI` to `PI`
+    pub(crate) fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    pub(crate) fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    pub(crate) fn exp(self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+
+    /// Return the principal natural logarithm, which is infinite for zero
+    pub(crate) fn ln(self) -> Self {
+        Self::new(self.abs().ln(), self.arg())
+    }
+
+    pub(crate) fn log(self, base: f64) -> Self {
+        let ln = self.ln();
+        let ln_base = base.ln();
+        Self::new(ln.re / ln_base, ln.im / ln_base)
+    }
+
+    /// Raise the complex number to a real power, by the polar form, like Excel
+    pub(crate) fn powf(self, exponent: f64) -> Self {
+        Self::from_polar(self.abs().powf(exponent), self.arg() * exponent)
+    }
+
+    pub(crate) fn sqrt(self) -> Self {
+        Self::from_polar(self.abs().sqrt(), self.arg() / 2.0)
+    }
+
+    pub(crate) fn sin(self) -> Self {
+        Self::new(self.re.sin() * self.im.cosh(), self.re.cos() * self.im.sinh())
+    }
+
+    pub(crate) fn cos(self) -> Self {
+        Self::new(self.re.cos() * self.im.cosh(), -self.re.sin() * self.im.sinh())
+    }
+
+    pub(crate) fn sinh(self) -> Self {
+        Self::new(self.re.sinh() * self.im.cos(), self.re.cosh() * self.im.sin())
+    }
+
+    pub(crate) fn cosh(self) -> Self {
+        Self::new(self.re.cosh() * self.im.cos(), self.re.sinh() * self.im.sin())
+    }
+
+    pub(crate) fn recip(self) -> Self {
+        Self::new(1.0, 0.0) / self
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Self;
+
+    /// The division by zero is not finite
+    fn div(self, other: Self) -> Self {
+        let denominator = other.re * other.re + other.im * other.im;
+        if denominator == 0.0 {
+            return Self::new(f64::NAN, f64::NAN);
+        }
+        Self::new(
+            (self.re * other.re + self.im * other.im) / denominator,
+            (self.im * other.re - self.re * other.im) / denominator,
+        )
+    }
+}
+
+/// Parse a part of a complex number, which is a decimal number, maybe with an exponent, like `-1.5E3`
+fn parse_part(text: &str) -> Option<f64> {
+    if !text
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+    {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Format a part of a complex number, where the very large and the very small numbers have an exponent,
+/// like `1.22464679914735E-16`
+fn format_part(number: f64) -> String {
+    if number == 0.0 {
+        "0".to_owned()
+    } else if (1e-9..1e15).contains(&number.abs()) {
+        number.to_string()
+    } else {
+        format!("{number:E}")
+    }
+}
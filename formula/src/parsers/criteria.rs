@@ -0,0 +1,344 @@
+use crate::{ast::Node, error::Error, evaluator::Evaluator, ErrorKind, Expr, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use std::cmp::Ordering;
+
+/// `Criteria` is a condition of the conditional functions, like `SUMIF`, which is matched against the values of a range.
+///
+/// It's parsed from a value, like `10`, or a text with an optional comparison operator, like `">=10"`,
+/// `"<>apple"`, `"<2024-01-31"` or `"app*"`. Texts are compared case-insensitively and they can have
+/// wildcards, `*` for any characters and `?` for a single character, which can be escaped by `~`, like Excel.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Criteria {
+    comparison: Comparison,
+    operand: Operand,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Lte,
+    Gte,
+}
+
+#[derive(Debug, PartialEq)]
+enum Operand {
+    Number(f64),
+    Date(NaiveDateTime),
+    Bool(bool),
+    /// A lowercase text, which can have wildcards
+    Text(String),
+    Error(ErrorKind),
+    Blank,
+}
+
+impl Criteria {
+    pub(crate) fn new(criteria: Expr) -> Result<Self> {
+        let operand = match criteria {
+            Expr::String(criteria) => return Ok(Self::from_text(&criteria)),
+            Expr::Number(number) => Operand::Number(number),
+            Expr::Bool(bool) => Operand::Bool(bool),
+            Expr::Date(date) => Operand::Date(date.and_hms_opt(0, 0, 0).unwrap_or_default()),
+            Expr::Datetime(datetime) => Operand::Date(datetime.naive_utc()),
+            Expr::Null => Operand::Blank,
+            Expr::Error(kind) => return Err(Error::Excel(kind)),
+            Expr::Time(_) | Expr::Array(_) => return Err(Error::Excel(ErrorKind::Value)),
+        };
+        Ok(Self {
+            comparison: Comparison::Eq,
+            operand,
+        })
+    }
+
+    fn from_text(criteria: &str) -> Self {
+        let (comparison, operand) = [
+            ("<>", Comparison::Ne),
+            (">=", Comparison::Gte),
+            ("<=", Comparison::Lte),
+            ("=", Comparison::Eq),
+            (">", Comparison::Gt),
+            ("<", Comparison::Lt),
+        ]
+        .into_iter()
+        .find_map(|(prefix, comparison)| criteria.strip_prefix(prefix).map(|operand| (comparison, operand)))
+        .unwrap_or((Comparison::Eq, criteria));
+
+        let operand = if operand.is_empty() {
+            Operand::Blank
+        } else if let Ok(number) = operand.trim().parse() {
+            Operand::Number(number)
+        } else if let Some(date) = Self::to_date(operand.trim()) {
+            Operand::Date(date)
+        } else if operand.eq_ignore_ascii_case("true") || operand.eq_ignore_ascii_case("false") {
+            Operand::Bool(operand.eq_ignore_ascii_case("true"))
+        } else if let Some(kind) = ErrorKind::from_literal(operand.trim()) {
+            Operand::Error(kind)
+        } else {
+            Operand::Text(operand.to_lowercase())
+        };
+        Self { comparison, operand }
+    }
+
+    fn to_date(date: &str) -> Option<NaiveDateTime> {
+        ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+            .or_else(|| {
+                ["%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y"]
+                    .iter()
+                    .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
+    }
+
+    /// Return true if the value matches the criteria
+    pub(crate) fn matches(&self, value: &Expr) -> bool {
+        match self.comparison {
+            Comparison::Eq => self.equals(value),
+            Comparison::Ne => !self.equals(value),
+            comparison => self.compare(value).is_some_and(|ordering| match comparison {
+                Comparison::Lt => ordering.is_lt(),
+                Comparison::Gt => ordering.is_gt(),
+                Comparison::Lte => ordering.is_le(),
+                _ => ordering.is_ge(),
+            }),
+        }
+    }
+
+    fn equals(&self, value: &Expr) -> bool {
+        match (&self.operand, value) {
+            (Operand::Number(operand), Expr::Number(value)) => operand == value,
+            (Operand::Number(operand), Expr::String(value)) => value.trim().parse() == Ok(*operand),
+            (Operand::Bool(operand), Expr::Bool(value)) => operand == value,
+            (Operand::Text(operand), Expr::String(value)) => wildcard_match(operand, &value.to_lowercase()),
+            (Operand::Error(operand), Expr::Error(value)) => operand == value,
+            (Operand::Blank, Expr::Null) => true,
+            (Operand::Blank, Expr::String(value)) => value.is_empty(),
+            (Operand::Date(_), _) => self.compare(value) == Some(Ordering::Equal),
+            _ => false,
+        }
+    }
+
+    /// Compare the value with the operand, if they have the same type
+    fn compare(&self, value: &Expr) -> Option<Ordering> {
+        match (&self.operand, value) {
+            (Operand::Number(operand), Expr::Number(value)) => value.partial_cmp(operand),
+            (Operand::Text(operand), Expr::String(value)) => Some(value.to_lowercase().cmp(operand)),
+            (Operand::Bool(operand), Expr::Bool(value)) => Some(value.cmp(operand)),
+            (Operand::Date(operand), Expr::Date(value)) => value.and_hms_opt(0, 0, 0).map(|value| value.cmp(operand)),
+            (Operand::Date(operand), Expr::Datetime(value)) => Some(value.naive_utc().cmp(operand)),
+            _ => None,
+        }
+    }
+}
+
+/// Return true if the text matches the pattern, which can have the `*` and `?` wildcards, escaped by `~`, like Excel
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    // The position of the last `*` in the pattern, and the position of the text it's matched to
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut t) = (0, 0);
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+                continue;
+            }
+            Some('?') => {
+                p += 1;
+                t += 1;
+                continue;
+            }
+            Some('~') if matches!(pattern.get(p + 1), Some('*' | '?' | '~')) && pattern[p + 1] == text[t] => {
+                p += 2;
+                t += 1;
+                continue;
+            }
+            // An escaped wildcard only matches itself, so the `~` is never a literal before it
+            Some('~') if matches!(pattern.get(p + 1), Some('*' | '?' | '~')) => {}
+            Some(c) if *c == text[t] => {
+                p += 1;
+                t += 1;
+                continue;
+            }
+            _ => {}
+        }
+        // Backtrack, so the last `*` matches one more character
+        match star {
+            Some((star_p, star_t)) => {
+                star = Some((star_p, star_t + 1));
+                p = star_p + 1;
+                t = star_t + 1;
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+impl Evaluator<'_> {
+    /// Return the items of a range, which is an array or a single value
+    pub(crate) fn get_range(range: Expr) -> Vec<Expr> {
+        match range {
+            Expr::Array(array) => Self::flatten(array),
+            value => vec![value],
+        }
+    }
+
+    /// Return the values which all their criteria match, like `SUMIFS`.
+    /// All the ranges should have the same size as the values, otherwise it's `#VALUE!`.
+    pub(crate) fn get_matched_values(values: Vec<Expr>, ranges: Vec<(Vec<Expr>, Criteria)>) -> Result<Vec<Expr>> {
+        if ranges.iter().any(|(range, _)| range.len() != values.len()) {
+            return Err(Error::Excel(ErrorKind::Value));
+        }
+
+        Ok(values
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| ranges.iter().all(|(range, criteria)| criteria.matches(&range[*i])))
+            .map(|(_, value)| value)
+            .collect())
+    }
+
+    /// Return the numbers of the matched values, and propagate their errors
+    pub(crate) fn get_matched_numbers(values: Vec<Expr>) -> Result<Vec<f64>> {
+        let mut numbers = Vec::new();
+        for value in values {
+            match value {
+                Expr::Number(number) => numbers.push(number),
+                Expr::Error(kind) => return Err(Error::Excel(kind)),
+                _ => {}
+            }
+        }
+        Ok(numbers)
+    }
+
+    /// Evaluate the pairs of the ranges and their criteria of the arguments, like `range1, criteria1, range2, criteria2`
+    pub(crate) fn get_criteria_ranges(&self, args: &[Node], rule_name: &str) -> Result<Vec<(Vec<Expr>, Criteria)>> {
+        if args.is_empty() || args.len() % 2 != 0 {
+            return Err(Error::Parser(rule_name.to_owned()));
+        }
+        args.chunks(2)
+            .map(|pair| {
+                let range = Self::get_range(self.parse_node(&pair[0])?);
+                let criteria = Criteria::new(self.parse_arg(&pair[1])?)?;
+                Ok((range, criteria))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Formula;
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_criteria() {
+        assert!(wildcard_match("a*c", "abbbc"));
+        assert!(wildcard_match("a*c", "ac"));
+        assert!(wildcard_match("a?c", "abc"));
+        assert!(!wildcard_match("a?c", "ac"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("*b*", "abc"));
+        assert!(!wildcard_match("a*d", "abc"));
+        assert!(wildcard_match("a~*", "a*"));
+        assert!(!wildcard_match("a~*", "ab"));
+        assert!(wildcard_match("what~?", "what?"));
+        assert!(wildcard_match("~~", "~"));
+        assert!(!wildcard_match("~*", "~x"));
+
+        let date = |y, m, d| Expr::Date(NaiveDate::from_ymd_opt(y, m, d).unwrap());
+        let matches = [
+            (Expr::String(">=10".to_string()), Expr::Number(10.0), true),
+            (Expr::String(">=10".to_string()), Expr::Number(9.0), false),
+            (Expr::String(">=10".to_string()), Expr::String("11".to_string()), false),
+            (
+                Expr::String("<>apple".to_string()),
+                Expr::String("Apple".to_string()),
+                false,
+            ),
+            (
+                Expr::String("<>apple".to_string()),
+                Expr::String("pear".to_string()),
+                true,
+            ),
+            (Expr::String("<>apple".to_string()), Expr::Null, true),
+            (
+                Expr::String("app*".to_string()),
+                Expr::String("APPLE".to_string()),
+                true,
+            ),
+            (
+                Expr::String("app*".to_string()),
+                Expr::String("pineapple".to_string()),
+                false,
+            ),
+            (Expr::String("=".to_string()), Expr::Null, true),
+            (Expr::String(String::new()), Expr::String(String::new()), true),
+            (Expr::String("<>".to_string()), Expr::Number(0.0), true),
+            (Expr::String("<>".to_string()), Expr::Null, false),
+            (Expr::String(">b".to_string()), Expr::String("c".to_string()), true),
+            (Expr::String(">b".to_string()), Expr::Number(1.0), false),
+            (Expr::String("<2024-01-31".to_string()), date(2024, 1, 30), true),
+            (Expr::String("<2024-01-31".to_string()), date(2024, 1, 31), false),
+            (Expr::String("1/31/2024".to_string()), date(2024, 1, 31), true),
+            (Expr::String("TRUE".to_string()), Expr::Bool(true), true),
+            (Expr::String("#N/A".to_string()), Expr::Error(ErrorKind::NA), true),
+            (Expr::Number(1.0), Expr::String("1".to_string()), true),
+            (Expr::Bool(false), Expr::Number(0.0), false),
+            (date(2024, 1, 31), date(2024, 1, 31), true),
+        ];
+        for (criteria, value, expected) in matches {
+            let criteria = Criteria::new(criteria).unwrap();
+            assert_eq!(criteria.matches(&value), expected, "{criteria:?} {value:?}");
+        }
+
+        let numbers = [
+            ("=SUMIF({1, 5, 10, 20}, '>=10')", 30.0),
+            ("=SUMIF({'a', 'b', 'a'}, 'a', {1, 2, 3})", 4.0),
+            ("=SUMIF({'apple', 'pear', 'pineapple'}, '*apple', {1, 2, 3})", 4.0),
+            ("=SUMIF({1, 2, 3}, '>5')", 0.0),
+            (
+                "=SUMIFS({1, 2, 3, 4}, {'a', 'b', 'a', 'a'}, 'a', {1, 2, 3, 4}, '>1')",
+                7.0,
+            ),
+            ("=COUNTIF({1, 'a', 2, 'b', 3}, '>1')", 2.0),
+            ("=COUNTIF({'a', 'A', 'b'}, 'a')", 2.0),
+            ("=COUNTIF({1, 'a', TRUE}, '<>a')", 2.0),
+            ("=COUNTIF({'~x', '*', 'a'}, '~*')", 1.0),
+            ("=COUNTIFS({1, 2, 3}, '>1', {'x', 'y', 'y'}, 'y')", 2.0),
+            ("=AVERAGEIF({1, 2, 3, 4}, '>2')", 3.5),
+            ("=AVERAGEIF({'a', 'b', 'a'}, 'a', {1, 2, 4})", 2.5),
+            ("=AVERAGEIFS({1, 2, 3, 4}, {1, 2, 3, 4}, '>1', {1, 2, 3, 4}, '<4')", 2.5),
+            ("=MAXIFS({1, 2, 3, 4}, {'a', 'a', 'b', 'b'}, 'a')", 2.0),
+            ("=MINIFS({1, 2, 3, 4}, {'a', 'a', 'b', 'b'}, 'b')", 3.0),
+            ("=MAXIFS({1, 2}, {'a', 'a'}, 'c')", 0.0),
+        ];
+        for (formula, expected) in numbers {
+            let formula = Formula::new(formula).unwrap();
+            assert_eq!(formula.parse().unwrap(), Expr::Number(expected), "{formula:?}");
+        }
+
+        let errors = [
+            ("=AVERAGEIF({1, 2}, '>5')", ErrorKind::DivZero),
+            ("=SUMIFS({1, 2}, {1, 2, 3}, '>1')", ErrorKind::Value),
+            ("=SUMIF({1, 2}, '>1', {1, #N/A})", ErrorKind::NA),
+            ("=COUNTIF({1, 2}, #REF!)", ErrorKind::Ref),
+        ];
+        for (formula, kind) in errors {
+            let formula = Formula::new(formula).unwrap();
+            assert_eq!(formula.parse().unwrap(), Expr::Error(kind), "{formula:?}");
+        }
+
+        // The errors of the ranges, which are not matched, are ignored
+        let formula = Formula::new("=SUMIF({1, 2}, '>1', {#N/A, 2})").unwrap();
+        assert_eq!(formula.parse().unwrap(), Expr::Number(2.0));
+    }
+}
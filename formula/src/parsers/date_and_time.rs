@@ -1,24 +1,23 @@
-use crate::{error::Error, Expr, Formula, Result, Rule};
+use crate::{ast::Node, error::Error, evaluator::Evaluator, Expr, Result, Rule};
 use chrono::{Datelike, NaiveDate, NaiveTime, Timelike, Utc};
-use pest::iterators::Pair;
 
-impl Formula<'_> {
-    #[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
-    pub(crate) fn parse_now(_pair: Pair<Rule>) -> Result<Expr> {
+impl Evaluator<'_> {
+    #[allow(clippy::unnecessary_wraps)]
+    pub(crate) fn parse_now(&self) -> Result<Expr> {
         Ok(Expr::Datetime(Utc::now()))
     }
 
-    #[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
-    pub(crate) fn parse_today(_pair: Pair<Rule>) -> Result<Expr> {
+    #[allow(clippy::unnecessary_wraps)]
+    pub(crate) fn parse_today(&self) -> Result<Expr> {
         Ok(Expr::Date(Utc::now().naive_utc().date()))
     }
 
-    pub(crate) fn parse_weekday(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let date = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_weekday(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let date = self.get_formula(&mut args, &rule_name)?;
         // TODO support return type, ref: https://support.microsoft.com/en-us/office/weekday-function-60e44483-2ed1-439f-8bd0-e404c190949a
-        // let return_type = Self::get_opt_formula_with_default(&mut args,Expr::Number(1.0))?;
+        // let return_type = self.get_opt_formula_with_default(&mut args,Expr::Number(1.0))?;
 
         let weekday = match date {
             Expr::Date(date) => date.weekday(),
@@ -27,12 +26,12 @@ impl Formula<'_> {
         Ok(Expr::Number(f64::from(weekday as i32)))
     }
 
-    #[allow(clippy::needless_pass_by_value)]
-    pub(crate) fn parse_weeknum(pair: Pair<Rule>) -> Result<Expr> {
-        // let rule_name = format!("{:?}", &pair.as_rule());
-        // let mut args = pair.into_inner();
-        // let date = Self::get_formula(&mut args, &rule_name)?;
-        // let return_type = args.next().map(|v| Self::parse_pair(v)?).unwrap_or(Expr::Number(1.0));
+    #[allow(clippy::unused_self)]
+    pub(crate) fn parse_weeknum(&self, rule: Rule, _args: &[Node]) -> Result<Expr> {
+        // let rule_name = format!("{rule:?}");
+        // let mut args = args.iter();
+        // let date = self.get_formula(&mut args, &rule_name)?;
+        // let return_type = args.next().map(|v| self.parse_node(v)?).unwrap_or(Expr::Number(1.0));
         //
         // let start_week = match return_type {
         //     Expr::Number(16.0) => Weekday::Sat,
@@ -49,13 +48,13 @@ impl Formula<'_> {
         //     _ => return Err(Error::Parser(rule_name).into()),
         // };
         // Ok(Expr::Number(week as i32 as f64))
-        todo!("Not implemented: {:?}", pair.as_rule())
+        todo!("Not implemented: {rule:?}")
     }
 
-    pub(crate) fn parse_isoweeknum(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let arg = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_isoweeknum(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let arg = self.get_formula(&mut args, &rule_name)?;
         let second = match arg {
             Expr::Date(date) => date.iso_week().week(),
             Expr::String(date) => Self::datestring_to_naivedate(&date, &rule_name)?.iso_week().week(),
@@ -64,10 +63,10 @@ impl Formula<'_> {
         Ok(Expr::Number(f64::from(second)))
     }
 
-    pub(crate) fn parse_second(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let arg = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_second(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let arg = self.get_formula(&mut args, &rule_name)?;
         let second = match arg {
             Expr::Time(time) => time.second(),
             Expr::String(time) => Self::timestring_to_naivetime(&time, &rule_name)?.second(),
@@ -76,10 +75,10 @@ impl Formula<'_> {
         Ok(Expr::Number(f64::from(second)))
     }
 
-    pub(crate) fn parse_minute(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let arg = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_minute(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let arg = self.get_formula(&mut args, &rule_name)?;
         let minute = match arg {
             Expr::Time(time) => time.minute(),
             Expr::String(time) => Self::timestring_to_naivetime(&time, &rule_name)?.minute(),
@@ -88,10 +87,10 @@ impl Formula<'_> {
         Ok(Expr::Number(f64::from(minute)))
     }
 
-    pub(crate) fn parse_hour(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let arg = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_hour(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let arg = self.get_formula(&mut args, &rule_name)?;
         let hour = match arg {
             Expr::Time(time) => time.hour(),
             Expr::String(time) => Self::timestring_to_naivetime(&time, &rule_name)?.hour(),
@@ -100,10 +99,10 @@ impl Formula<'_> {
         Ok(Expr::Number(f64::from(hour)))
     }
 
-    pub(crate) fn parse_timevalue(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let arg = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_timevalue(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let arg = self.get_formula(&mut args, &rule_name)?;
         let time = match arg {
             Expr::String(datestring) => Self::timestring_to_naivetime(&datestring, &rule_name)?,
             _ => return Err(Error::Parser(rule_name)),
@@ -111,10 +110,10 @@ impl Formula<'_> {
         Ok(Expr::Time(time))
     }
 
-    pub(crate) fn parse_datevalue(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let arg = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_datevalue(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let arg = self.get_formula(&mut args, &rule_name)?;
         let date = match arg {
             Expr::String(datestring) => Self::datestring_to_naivedate(&datestring, &rule_name)?,
             _ => return Err(Error::Parser(rule_name)),
@@ -122,11 +121,11 @@ impl Formula<'_> {
         Ok(Expr::Date(date))
     }
 
-    pub(crate) fn parse_eomonth(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let date = Self::get_formula(&mut args, &rule_name)?;
-        let num = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_eomonth(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let date = self.get_formula(&mut args, &rule_name)?;
+        let num = self.get_formula(&mut args, &rule_name)?;
         let date = match (date, num) {
             (Expr::String(date), Expr::Number(num)) => {
                 let date = Self::datestring_to_naivedate(&date, &rule_name)?;
@@ -150,11 +149,11 @@ impl Formula<'_> {
         Ok(Expr::Date(date))
     }
 
-    pub(crate) fn parse_edate(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let date = Self::get_formula(&mut args, &rule_name)?;
-        let num = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_edate(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let date = self.get_formula(&mut args, &rule_name)?;
+        let num = self.get_formula(&mut args, &rule_name)?;
         let date = match (date, num) {
             (Expr::String(date), Expr::Number(num)) => {
                 let date = Self::datestring_to_naivedate(&date, &rule_name)?;
@@ -167,11 +166,11 @@ impl Formula<'_> {
         Ok(Expr::Date(date))
     }
 
-    pub(crate) fn parse_days(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let end_date = Self::get_formula(&mut args, &rule_name)?;
-        let start_date = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_days(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let end_date = self.get_formula(&mut args, &rule_name)?;
+        let start_date = self.get_formula(&mut args, &rule_name)?;
         let days = match (end_date, start_date) {
             (Expr::Date(end_date), Expr::Date(start_date)) => {
                 end_date.signed_duration_since(start_date).num_days() as f64
@@ -188,10 +187,10 @@ impl Formula<'_> {
         Ok(Expr::Number(days))
     }
 
-    pub(crate) fn parse_day(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let arg = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_day(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let arg = self.get_formula(&mut args, &rule_name)?;
         let day = match arg {
             Expr::Date(date) => date.day(),
             Expr::String(date) => Self::datestring_to_naivedate(&date, &rule_name)?.day(),
@@ -200,10 +199,10 @@ impl Formula<'_> {
         Ok(Expr::Number(f64::from(day)))
     }
 
-    pub(crate) fn parse_month(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let arg = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_month(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let arg = self.get_formula(&mut args, &rule_name)?;
         let month = match arg {
             Expr::Date(date) => date.month(),
             Expr::String(date) => Self::datestring_to_naivedate(&date, &rule_name)?.month(),
@@ -212,10 +211,10 @@ impl Formula<'_> {
         Ok(Expr::Number(f64::from(month)))
     }
 
-    pub(crate) fn parse_year(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let arg = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_year(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let arg = self.get_formula(&mut args, &rule_name)?;
         let year = match arg {
             Expr::Date(date) => date.year(),
             Expr::String(date) => Self::datestring_to_naivedate(&date, &rule_name)?.year(),
@@ -224,12 +223,12 @@ impl Formula<'_> {
         Ok(Expr::Number(f64::from(year)))
     }
 
-    pub(crate) fn parse_time(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let hour = Self::get_formula(&mut args, &rule_name)?;
-        let minute = Self::get_formula(&mut args, &rule_name)?;
-        let second = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_time(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let hour = self.get_formula(&mut args, &rule_name)?;
+        let minute = self.get_formula(&mut args, &rule_name)?;
+        let second = self.get_formula(&mut args, &rule_name)?;
         let time = match (hour, minute, second) {
             (Expr::Number(hour), Expr::Number(minute), Expr::Number(second)) => {
                 let time = match NaiveTime::from_hms_opt(hour as u32, minute as u32, second as u32) {
@@ -243,12 +242,12 @@ impl Formula<'_> {
         Ok(time)
     }
 
-    pub(crate) fn parse_date(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let year = Self::get_formula(&mut args, &rule_name)?;
-        let month = Self::get_formula(&mut args, &rule_name)?;
-        let day = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_date(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let year = self.get_formula(&mut args, &rule_name)?;
+        let month = self.get_formula(&mut args, &rule_name)?;
+        let day = self.get_formula(&mut args, &rule_name)?;
         let date = match (year, month, day) {
             (Expr::Number(year), Expr::Number(month), Expr::Number(day)) => {
                 let date = match NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32) {
@@ -262,14 +261,17 @@ impl Formula<'_> {
         Ok(date)
     }
 
-    fn datestring_to_naivedate(datestring: &str, rule_name: &str) -> Result<NaiveDate> {
+    pub(crate) fn datestring_to_naivedate(datestring: &str, rule_name: &str) -> Result<NaiveDate> {
         let mdy = datestring
             .split('/')
             .map(str::parse)
             .collect::<std::result::Result<Vec<_>, _>>()
             .map_err(|_| Error::Parser(rule_name.to_owned()))?;
+        let [month, day, year] = mdy[..] else {
+            return Err(Error::Parser(rule_name.to_owned()));
+        };
         #[allow(clippy::cast_possible_wrap)]
-        NaiveDate::from_ymd_opt(mdy[2] as i32, mdy[0], mdy[1]).ok_or_else(|| Error::Parser(rule_name.to_owned()))
+        NaiveDate::from_ymd_opt(year as i32, month, day).ok_or_else(|| Error::Parser(rule_name.to_owned()))
     }
 
     fn timestring_to_naivetime(timestring: &str, rule_name: &str) -> Result<NaiveTime> {
@@ -0,0 +1,293 @@
+use crate::{error::Error, ErrorKind, Result};
+use chrono::{Datelike, Months, NaiveDate};
+
+/// The day count basis of the financial functions, which is the `basis` argument of them, like Excel
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Basis {
+    /// `0`, US (NASD) 30/360
+    Us30360,
+    /// `1`, actual/actual
+    ActualActual,
+    /// `2`, actual/360
+    Actual360,
+    /// `3`, actual/365
+    Actual365,
+    /// `4`, European 30/360
+    European30360,
+}
+
+impl Basis {
+    /// Return the basis of a number, which is truncated, or `#NUM!` if it's not valid, like Excel
+    pub(crate) fn new(basis: f64) -> Result<Self> {
+        let basis = match basis.trunc() as i64 {
+            0 => Basis::Us30360,
+            1 => Basis::ActualActual,
+            2 => Basis::Actual360,
+            3 => Basis::Actual365,
+            4 => Basis::European30360,
+            _ => return Err(Error::Excel(ErrorKind::Num)),
+        };
+        Ok(basis)
+    }
+
+    /// Return the number of the days between two dates by the basis, where the 30/360 bases count 30 days a month
+    pub(crate) fn days(self, start: NaiveDate, end: NaiveDate) -> f64 {
+        match self {
+            Basis::Us30360 => days360(start, end, false),
+            Basis::European30360 => days360(start, end, true),
+            Basis::ActualActual | Basis::Actual360 | Basis::Actual365 => actual_days(start, end),
+        }
+    }
+
+    /// Return the number of the days of a coupon period, which is only the actual days for the actual/actual basis
+    pub(crate) fn coupon_days(self, previous: NaiveDate, next: NaiveDate, frequency: u32) -> f64 {
+        match self {
+            Basis::ActualActual => actual_days(previous, next),
+            Basis::Actual365 => 365.0 / f64::from(frequency),
+            Basis::Us30360 | Basis::Actual360 | Basis::European30360 => 360.0 / f64::from(frequency),
+        }
+    }
+
+    /// Return the number of the days from a date to the next coupon date, where the 30/360 bases count
+    /// the rest of the coupon period, like Excel
+    pub(crate) fn remaining_days(self, previous: NaiveDate, date: NaiveDate, next: NaiveDate, frequency: u32) -> f64 {
+        match self {
+            Basis::Us30360 | Basis::European30360 => {
+                self.coupon_days(previous, next, frequency) - self.days(previous, date)
+            }
+            Basis::ActualActual | Basis::Actual360 | Basis::Actual365 => actual_days(date, next),
+        }
+    }
+
+    /// Return the fraction of the years between two dates by the basis, like `YEARFRAC` of Excel
+    pub(crate) fn year_fraction(self, start: NaiveDate, end: NaiveDate) -> f64 {
+        let (start, end) = if start <= end { (start, end) } else { (end, start) };
+        match self {
+            Basis::Us30360 => us_year_fraction_days360(start, end) / 360.0,
+            Basis::European30360 => days360(start, end, true) / 360.0,
+            Basis::Actual360 => actual_days(start, end) / 360.0,
+            Basis::Actual365 => actual_days(start, end) / 365.0,
+            Basis::ActualActual => actual_days(start, end) / actual_year_length(start, end),
+        }
+    }
+}
+
+/// The coupon period of a security, which contains the settlement date.
+/// The coupon dates are found backward from the maturity date, like Excel.
+pub(crate) struct CouponPeriod {
+    /// The coupon date before, or at, the settlement date, like `COUPPCD`
+    pub(crate) previous: NaiveDate,
+    /// The coupon date after the settlement date, like `COUPNCD`
+    pub(crate) next: NaiveDate,
+    /// The number of the coupons from the settlement to the maturity date, like `COUPNUM`
+    pub(crate) count: u32,
+    /// The days from the previous coupon date to the settlement date, like `COUPDAYBS`
+    pub(crate) accrued_days: f64,
+    /// The days of the coupon period, like `COUPDAYS`
+    pub(crate) days: f64,
+    /// The days from the settlement date to the next coupon date, like `COUPDAYSNC`
+    pub(crate) remaining_days: f64,
+}
+
+impl CouponPeriod {
+    /// The settlement date should be before the maturity date, and the frequency should be `1`, `2` or `4`
+    pub(crate) fn new(settlement: NaiveDate, maturity: NaiveDate, frequency: u32, basis: Basis) -> Self {
+        let months = 12 / frequency;
+        let mut count = 1;
+        while coupon_date(maturity, -((count * months) as i32)) > settlement {
+            count += 1;
+        }
+        let previous = coupon_date(maturity, -((count * months) as i32));
+        let next = coupon_date(maturity, -(((count - 1) * months) as i32));
+
+        let accrued_days = basis.days(previous, settlement);
+        let days = basis.coupon_days(previous, next, frequency);
+        let remaining_days = basis.remaining_days(previous, settlement, next, frequency);
+        Self {
+            previous,
+            next,
+            count,
+            accrued_days,
+            days,
+            remaining_days,
+        }
+    }
+}
+
+/// The odd first, or last, coupon period of a security, which is measured in the regular coupon periods.
+/// The regular, or quasi, coupon periods are found from the first coupon date of the odd first period,
+/// or from the last interest date of the odd last period, like Excel.
+pub(crate) struct OddPeriod {
+    /// The length of the odd period, like the sum of `DC / NL` of Excel
+    pub(crate) periods: f64,
+    /// The length from the start of the odd period to the settlement date, like the sum of `A / NL` of Excel
+    pub(crate) accrued: f64,
+    /// The length from the settlement date to the end of the odd period, like the sum of `DSC / NL` of Excel
+    pub(crate) remaining: f64,
+}
+
+impl OddPeriod {
+    /// The settlement date should be in the odd period, which starts at `start` and ends at `end`,
+    /// and the frequency should be `1`, `2` or `4`
+    pub(crate) fn new(
+        start: NaiveDate,
+        settlement: NaiveDate,
+        end: NaiveDate,
+        anchor: NaiveDate,
+        frequency: u32,
+        basis: Basis,
+    ) -> Self {
+        let months = (12 / frequency) as i32;
+        let mut index = 0;
+        while coupon_date(anchor, index * months) > start {
+            index -= 1;
+        }
+        while coupon_date(anchor, (index + 1) * months) <= start {
+            index += 1;
+        }
+
+        let mut period = Self {
+            periods: 0.0,
+            accrued: 0.0,
+            remaining: 0.0,
+        };
+        loop {
+            let previous = coupon_date(anchor, index * months);
+            if previous >= end {
+                break;
+            }
+            let next = coupon_date(anchor, (index + 1) * months);
+            let days = basis.coupon_days(previous, next, frequency);
+            let (first, last) = (previous.max(start), next.min(end));
+            period.periods += basis.days(first, last) / days;
+            if settlement > first {
+                period.accrued += basis.days(first, last.min(settlement)) / days;
+            }
+            if settlement < last {
+                period.remaining += if settlement <= first {
+                    basis.days(first, last)
+                } else if next <= end {
+                    basis.remaining_days(previous, settlement, next, frequency)
+                } else {
+                    basis.days(settlement, last)
+                } / days;
+            }
+            index += 1;
+        }
+        period
+    }
+}
+
+/// Return a coupon date, which is some months after, or before, a coupon date.
+/// If the coupon date is the last day of a month, the other coupon dates are the last days of their months too.
+pub(crate) fn coupon_date(date: NaiveDate, months: i32) -> NaiveDate {
+    let shifted = if months >= 0 {
+        date.checked_add_months(Months::new(months.unsigned_abs()))
+            .unwrap_or(NaiveDate::MAX)
+    } else {
+        date.checked_sub_months(Months::new(months.unsigned_abs()))
+            .unwrap_or(NaiveDate::MIN)
+    };
+    if is_last_day_of_month(date) {
+        last_day_of_month(shifted)
+    } else {
+        shifted
+    }
+}
+
+fn last_day_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1)
+        .and_then(|first_day| first_day.checked_add_months(Months::new(1)))
+        .and_then(|next_month| next_month.pred_opt())
+        .unwrap_or(date)
+}
+
+pub(crate) fn actual_days(start: NaiveDate, end: NaiveDate) -> f64 {
+    end.signed_duration_since(start).num_days() as f64
+}
+
+pub(crate) fn is_leap_year(year: i32) -> bool {
+    NaiveDate::from_ymd_opt(year, 2, 29).is_some()
+}
+
+pub(crate) fn is_last_day_of_month(date: NaiveDate) -> bool {
+    date.succ_opt().is_some_and(|next| next.month() != date.month())
+}
+
+fn is_last_day_of_february(date: NaiveDate) -> bool {
+    date.month() == 2 && is_last_day_of_month(date)
+}
+
+fn days360_from_parts(start: NaiveDate, start_day: u32, end: NaiveDate, end_day: u32) -> f64 {
+    let years = f64::from(end.year() - start.year());
+    let months = f64::from(end.month()) - f64::from(start.month());
+    years * 360.0 + months * 30.0 + f64::from(end_day) - f64::from(start_day)
+}
+
+/// Return the days between two dates, where every month has 30 days, like `DAYS360` of Excel.
+/// The US method moves the last day of February and the 31st days, and the European method moves only the 31st days.
+pub(crate) fn days360(start: NaiveDate, end: NaiveDate, european: bool) -> f64 {
+    let mut start_day = start.day();
+    let mut end_day = end.day();
+    if european {
+        start_day = start_day.min(30);
+        end_day = end_day.min(30);
+    } else {
+        if is_last_day_of_february(start) {
+            if is_last_day_of_february(end) {
+                end_day = 30;
+            }
+            start_day = 30;
+        }
+        if start_day >= 30 {
+            start_day = 30;
+            if end_day == 31 {
+                end_day = 30;
+            }
+        }
+    }
+    days360_from_parts(start, start_day, end, end_day)
+}
+
+/// The US 30/360 days of `YEARFRAC`, which is a bit different from `DAYS360`, like Excel
+fn us_year_fraction_days360(start: NaiveDate, end: NaiveDate) -> f64 {
+    let mut start_day = start.day();
+    let mut end_day = end.day();
+    if start_day == 31 && end_day == 31 {
+        start_day = 30;
+        end_day = 30;
+    } else if start_day == 31 {
+        start_day = 30;
+    } else if start_day == 30 && end_day == 31 {
+        end_day = 30;
+    } else if is_last_day_of_february(start) && is_last_day_of_february(end) {
+        start_day = 30;
+        end_day = 30;
+    } else if is_last_day_of_february(start) {
+        start_day = 30;
+    }
+    days360_from_parts(start, start_day, end, end_day)
+}
+
+/// Return the length of the year of the actual/actual basis, which is the average length of the years
+/// between two dates, if they are more than a year apart, like Excel
+fn actual_year_length(start: NaiveDate, end: NaiveDate) -> f64 {
+    let within_a_year = start.year() == end.year()
+        || (end.year() == start.year() + 1 && (start.month(), start.day()) >= (end.month(), end.day()));
+    if within_a_year {
+        let has_february_29 = (start.year()..=end.year()).any(|year| {
+            NaiveDate::from_ymd_opt(year, 2, 29).is_some_and(|february_29| start <= february_29 && february_29 <= end)
+        });
+        if (start.year() == end.year() && is_leap_year(start.year())) || has_february_29 {
+            366.0
+        } else {
+            365.0
+        }
+    } else {
+        let years = f64::from(end.year() - start.year() + 1);
+        let days = (start.year()..=end.year())
+            .map(|year| if is_leap_year(year) { 366.0 } else { 365.0 })
+            .sum::<f64>();
+        days / years
+    }
+}
@@ -0,0 +1,383 @@
+/// The maximum length of a season, which is the number of the hours of a year, like Excel
+pub(crate) const MAX_SEASONALITY: f64 = 8760.0;
+/// The minimum autocorrelation of the detrended values, at the length of a season, where the season is detected
+const MIN_SEASONAL_CORRELATION: f64 = 0.5;
+/// The relative difference of a distance of the timeline from a multiple of the step, under which it's a multiple
+const STEP_TOLERANCE: f64 = 1e-9;
+/// The largest part of the points of a timeline which can be missing, like Excel
+const MAX_MISSING: f64 = 0.3;
+/// The maximum number of the rounds of the optimization of the smoothing parameters
+const MAX_ROUNDS: usize = 100;
+/// The precision of the smoothing parameters
+const PRECISION: f64 = 1e-8;
+
+/// How the values at the same point of a timeline are aggregated
+#[derive(Clone, Copy)]
+pub(crate) enum Aggregation {
+    Average,
+    Count,
+    Max,
+    Median,
+    Min,
+    Sum,
+}
+
+impl Aggregation {
+    /// Return the aggregation of its number, 1 for `AVERAGE`, 2 and 3 for `COUNT` and `COUNTA`, 4 for `MAX`,
+    /// 5 for `MEDIAN`, 6 for `MIN` and 7 for `SUM`, like Excel
+    pub(crate) fn new(number: f64) -> Option<Self> {
+        let aggregation = match number.trunc() {
+            1.0 => Self::Average,
+            2.0 | 3.0 => Self::Count,
+            4.0 => Self::Max,
+            5.0 => Self::Median,
+            6.0 => Self::Min,
+            7.0 => Self::Sum,
+            _ => return None,
+        };
+        Some(aggregation)
+    }
+
+    fn aggregate(self, mut values: Vec<f64>) -> f64 {
+        let n = values.len() as f64;
+        match self {
+            Self::Average => values.iter().sum::<f64>() / n,
+            Self::Count => n,
+            Self::Max => values.into_iter().fold(f64::NEG_INFINITY, f64::max),
+            Self::Median => {
+                values.sort_by(f64::total_cmp);
+                let middle = values.len() / 2;
+                if values.len() % 2 == 0 {
+                    (values[middle - 1] + values[middle]) / 2.0
+                } else {
+                    values[middle]
+                }
+            }
+            Self::Min => values.into_iter().fold(f64::INFINITY, f64::min),
+            Self::Sum => values.iter().sum(),
+        }
+    }
+}
+
+/// A time series, whose values are at the consecutive steps of its timeline
+pub(crate) struct Series {
+    pub(crate) values: Vec<f64>,
+    /// The last point of the timeline
+    pub(crate) end: f64,
+    pub(crate) step: f64,
+}
+
+impl Series {
+    /// Create a series from the points of a timeline and their values, which are sorted by the points,
+    /// and the values of the same point are aggregated.
+    /// The step is the smallest distance of the points, and the other distances must be its multiples.
+    /// The missing values are interpolated between the nearest ones, or they are zero if `complete` is false.
+    ///
+    /// Return `None` if the series has fewer than two points, the timeline has no consistent step,
+    /// or more than 30% of its points are missing.
+    pub(crate) fn new(mut points: Vec<(f64, f64)>, complete: bool, aggregation: Aggregation) -> Option<Self> {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut aggregated: Vec<(f64, f64)> = Vec::new();
+        let mut index = 0;
+        while index < points.len() {
+            let point = points[index].0;
+            let same = points[index..].iter().take_while(|(other, _)| *other == point).count();
+            let values = points[index..index + same].iter().map(|(_, value)| *value).collect();
+            aggregated.push((point, aggregation.aggregate(values)));
+            index += same;
+        }
+        if aggregated.len() < 2 {
+            return None;
+        }
+
+        let step = aggregated
+            .windows(2)
+            .map(|pair| pair[1].0 - pair[0].0)
+            .fold(f64::INFINITY, f64::min);
+        let start = aggregated[0].0;
+        let mut positions = Vec::with_capacity(aggregated.len());
+        for (point, _) in &aggregated {
+            let steps = (point - start) / step;
+            if (steps - steps.round()).abs() > steps * STEP_TOLERANCE {
+                return None;
+            }
+            positions.push(steps.round());
+        }
+
+        // The missing points are counted before they are filled, so a distant point doesn't fill the memory
+        let length = positions[positions.len() - 1] + 1.0;
+        if length - positions.len() as f64 > MAX_MISSING * length {
+            return None;
+        }
+
+        let mut values = Vec::with_capacity(length as usize);
+        for (position, (_, value)) in positions.into_iter().zip(aggregated) {
+            let position = position as usize;
+            if let Some(&previous) = values.last() {
+                let missing = position - values.len();
+                for i in 1..=missing {
+                    let fraction = i as f64 / (missing + 1) as f64;
+                    values.push(if complete {
+                        previous + fraction * (value - previous)
+                    } else {
+                        0.0
+                    });
+                }
+            }
+            values.push(value);
+        }
+        let end = start + (values.len() - 1) as f64 * step;
+        Some(Self { values, end, step })
+    }
+
+    /// Return the length of the season, which is the first peak of the autocorrelation of the detrended values,
+    /// where it's at least 0.5, or zero if there's no season
+    pub(crate) fn seasonality(&self) -> usize {
+        let n = self.values.len();
+        if n < 4 {
+            return 0;
+        }
+
+        // The values are detrended by their regression line
+        let mean_x = (n - 1) as f64 / 2.0;
+        let mean_y = self.values.iter().sum::<f64>() / n as f64;
+        let (mut xx, mut xy) = (0.0, 0.0);
+        for (x, y) in self.values.iter().enumerate() {
+            xx += (x as f64 - mean_x).powi(2);
+            xy += (x as f64 - mean_x) * (y - mean_y);
+        }
+        let slope = xy / xx;
+        let residuals = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(x, y)| y - mean_y - slope * (x as f64 - mean_x))
+            .collect::<Vec<_>>();
+        let variance = residuals.iter().map(|residual| residual * residual).sum::<f64>() / n as f64;
+        if variance <= f64::EPSILON * mean_y.abs().max(1.0) {
+            return 0;
+        }
+
+        let correlations = (0..=(n / 2 + 1).min(n - 1))
+            .map(|lag| {
+                let sum = residuals.iter().zip(&residuals[lag..]).map(|(a, b)| a * b).sum::<f64>();
+                sum / (n - lag) as f64 / variance
+            })
+            .collect::<Vec<_>>();
+        (2..=n / 2)
+            .find(|&lag| {
+                correlations[lag] >= MIN_SEASONAL_CORRELATION
+                    && correlations[lag] >= correlations[lag - 1]
+                    && correlations
+                        .get(lag + 1)
+                        .map_or(true, |next| correlations[lag] >= *next)
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// The additive error, additive trend and additive seasonality (AAA) model of the exponential smoothing,
+/// which is the triple exponential smoothing of Holt-Winters, fitted to a series
+pub(crate) struct Model {
+    pub(crate) alpha: f64,
+    pub(crate) beta: f64,
+    pub(crate) gamma: f64,
+    season: usize,
+    level: f64,
+    trend: f64,
+    seasons: Vec<f64>,
+    /// The values of the series, and the one-step forecasts of the values after the first season
+    values: Vec<f64>,
+    forecasts: Vec<f64>,
+}
+
+impl Model {
+    /// Fit the model to the values, with the length of a season, or zero if there's no season.
+    /// The smoothing parameters, from 0 to 1, minimize the sum of the squares of the errors of the one-step forecasts,
+    /// and they are optimized one by one, by the golden-section search.
+    ///
+    /// Return `None` if there are fewer than two seasons, or two values if there's no season.
+    pub(crate) fn fit(values: &[f64], season: usize) -> Option<Self> {
+        if values.len() < 2 * season.max(1) {
+            return None;
+        }
+
+        let mut parameters = [0.5, 0.1, if season > 0 { 0.1 } else { 0.0 }];
+        let count = if season > 0 { 3 } else { 2 };
+        for _ in 0..MAX_ROUNDS {
+            let previous = parameters;
+            for i in 0..count {
+                parameters[i] = golden_section_search(|parameter| {
+                    let mut parameters = parameters;
+                    parameters[i] = parameter;
+                    Self::smooth(values, season, parameters).squared_error()
+                });
+            }
+            if previous.iter().zip(&parameters).all(|(a, b)| (a - b).abs() < PRECISION) {
+                break;
+            }
+        }
+        Some(Self::smooth(values, season, parameters))
+    }
+
+    /// Smooth the values by the parameters, from the states of the first season.
+    /// The initial level is the mean of the first season, at its middle, the initial trend is the difference of the means
+    /// of the first two seasons, divided by the length, and the initial seasons are the differences from the trend line.
+    fn smooth(values: &[f64], season: usize, [alpha, beta, gamma]: [f64; 3]) -> Self {
+        let length = season.max(1);
+        let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+        let mut trend = (mean(&values[length..2 * length]) - mean(&values[..length])) / length as f64;
+        let middle = (length - 1) as f64 / 2.0;
+        let mut level = mean(&values[..length]);
+        let mut seasons = values[..length]
+            .iter()
+            .enumerate()
+            .map(|(i, value)| value - level - (i as f64 - middle) * trend)
+            .collect::<Vec<_>>();
+        if season == 0 {
+            seasons[0] = 0.0;
+        }
+        level += middle * trend;
+
+        let mut forecasts = Vec::with_capacity(values.len() - length);
+        for (t, value) in values.iter().enumerate().skip(length) {
+            let seasonal = seasons[t % length];
+            forecasts.push(level + trend + seasonal);
+            let next = alpha * (value - seasonal) + (1.0 - alpha) * (level + trend);
+            trend = beta * (next - level) + (1.0 - beta) * trend;
+            seasons[t % length] = gamma * (value - next) + (1.0 - gamma) * seasonal;
+            level = next;
+        }
+
+        Self {
+            alpha,
+            beta,
+            gamma,
+            season,
+            level,
+            trend,
+            seasons,
+            values: values.to_vec(),
+            forecasts,
+        }
+    }
+
+    /// The errors of the one-step forecasts, and the values
+    fn errors(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let actual = &self.values[self.values.len() - self.forecasts.len()..];
+        actual
+            .iter()
+            .zip(&self.forecasts)
+            .map(|(value, forecast)| (value - forecast, *value))
+    }
+
+    fn squared_error(&self) -> f64 {
+        self.errors().map(|(error, _)| error * error).sum()
+    }
+
+    /// Return the forecast of a number of steps after the last value, which is interpolated between the whole steps.
+    /// The forecast of zero steps is the smoothed last value.
+    pub(crate) fn forecast(&self, steps: f64) -> f64 {
+        let length = self.seasons.len();
+        let last = (self.values.len() - 1) % length;
+        let forecast = |steps: f64| {
+            let season = self.seasons[(last + (steps % length as f64) as usize) % length];
+            self.level + steps * self.trend + season
+        };
+        let (lower, upper) = (steps.floor(), steps.ceil());
+        let forecast_lower = forecast(lower);
+        forecast_lower + (steps - lower) * (forecast(upper) - forecast_lower)
+    }
+
+    /// Return the half width of the prediction interval of a forecast, with a normal quantile of the confidence.
+    /// The variance of the forecast of `h` steps is `s^2 (1 + c_1^2 + ... + c_(h-1)^2)`, where `s^2` is the mean squared
+    /// error, and `c_j = alpha (1 + j beta) + gamma (1 - alpha) d_j`, where `d_j` is 1 if `j` is a multiple of the season.
+    ///
+    /// The sum is calculated by the sums of the powers of `j`, so it doesn't depend on the distance of the forecast.
+    pub(crate) fn interval(&self, steps: f64, quantile: f64) -> f64 {
+        let (alpha, beta) = (self.alpha, self.beta);
+        // The sum of `(alpha (1 + j beta))^2` for `j` from 1 to `n`
+        let n = steps.ceil().max(1.0) - 1.0;
+        let (sum, squares) = (n * (n + 1.0) / 2.0, n * (n + 1.0) * (2.0 * n + 1.0) / 6.0);
+        let mut variance = alpha * alpha * (n + 2.0 * beta * sum + beta * beta * squares);
+
+        // The terms of the multiples of the season, `j = k m`, have `gamma (1 - alpha)` too
+        if self.season > 0 {
+            let seasonal = self.gamma * (1.0 - alpha);
+            let (k, m) = ((n / self.season as f64).floor(), self.season as f64);
+            variance += k * (2.0 * seasonal * alpha + seasonal * seasonal)
+                + 2.0 * seasonal * alpha * beta * m * k * (k + 1.0) / 2.0;
+        }
+        quantile * (self.rmse() * self.rmse() * (1.0 + variance)).sqrt()
+    }
+
+    /// The mean absolute scaled error, which is the mean absolute error divided by the one of the naive forecasts,
+    /// which are the previous values
+    pub(crate) fn mase(&self) -> f64 {
+        let naive = self
+            .values
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .sum::<f64>()
+            / (self.values.len() - 1) as f64;
+        self.mae() / naive
+    }
+
+    /// The symmetric mean absolute percentage error, where the errors of the zero values and forecasts are zero
+    pub(crate) fn smape(&self) -> f64 {
+        let sum = self
+            .errors()
+            .map(|(error, value)| {
+                let scale = (value.abs() + (value - error).abs()) / 2.0;
+                if scale == 0.0 {
+                    0.0
+                } else {
+                    error.abs() / scale
+                }
+            })
+            .sum::<f64>();
+        sum / self.forecasts.len() as f64
+    }
+
+    /// The mean absolute error
+    pub(crate) fn mae(&self) -> f64 {
+        self.errors().map(|(error, _)| error.abs()).sum::<f64>() / self.forecasts.len() as f64
+    }
+
+    /// The root mean squared error
+    pub(crate) fn rmse(&self) -> f64 {
+        (self.squared_error() / self.forecasts.len() as f64).sqrt()
+    }
+}
+
+/// Return the number from 0 to 1 where a function is minimal, which is searched by the golden-section search
+fn golden_section_search(function: impl Fn(f64) -> f64) -> f64 {
+    let ratio = (5_f64.sqrt() - 1.0) / 2.0;
+    let (mut lower, mut upper) = (0.0, 1.0);
+    let mut x1 = upper - ratio * (upper - lower);
+    let mut x2 = lower + ratio * (upper - lower);
+    let (mut f1, mut f2) = (function(x1), function(x2));
+    while upper - lower > PRECISION {
+        if f1 <= f2 {
+            upper = x2;
+            (x2, f2) = (x1, f1);
+            x1 = upper - ratio * (upper - lower);
+            f1 = function(x1);
+        } else {
+            lower = x1;
+            (x1, f1) = (x2, f2);
+            x2 = lower + ratio * (upper - lower);
+            f2 = function(x2);
+        }
+    }
+
+    // The bounds are included, since the best parameters are often 0 or 1
+    [(lower + upper) / 2.0, 0.0, 1.0]
+        .into_iter()
+        .map(|x| (x, function(x)))
+        .fold(
+            (0.5, f64::INFINITY),
+            |best, (x, f)| if f < best.1 { (x, f) } else { best },
+        )
+        .0
+}
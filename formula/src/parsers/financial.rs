@@ -1 +1,1609 @@
+use crate::{
+    ast::Node,
+    error::Error,
+    evaluator::Evaluator,
+    parsers::{
+        day_count::{coupon_date, Basis, CouponPeriod, OddPeriod},
+        statistical::Values,
+    },
+    ErrorKind, Expr, Result, Rule,
+};
+use chrono::{Months, NaiveDate};
+use std::slice::Iter;
 
+/// The maximum number of the iterations of `RATE`, like Excel
+const RATE_ITERATIONS: usize = 20;
+/// The precision of `RATE`, like Excel
+const RATE_PRECISION: f64 = 1e-7;
+
+/// The maximum number of the iterations of the Newton's method of the root finder
+const ROOT_ITERATIONS: usize = 100;
+/// The precision of the root finder
+const ROOT_PRECISION: f64 = 1e-12;
+/// The rates which are checked to bracket a root, when the Newton's method doesn't converge
+const ROOT_BRACKETS: [f64; 16] = [
+    -0.999_999, -0.99, -0.9, -0.5, -0.2, -0.1, 0.0, 0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0, 100.0, 1000.0,
+];
+
+/// The cash flows and their times in periods
+type CashFlows = Vec<(f64, f64)>;
+
+/// Find a rate, greater than `-1`, where the function is zero. The function returns its value and its derivative.
+///
+/// The rate is found by the Newton's method from the guess, and if it doesn't converge, by the bisection method
+/// between the nearest rates to the guess, where the function changes its sign.
+/// So it returns `None` instead of looping or returning a wrong rate.
+fn find_root(function: impl Fn(f64) -> (f64, f64), guess: f64) -> Option<f64> {
+    let mut rate = guess;
+    for _ in 0..ROOT_ITERATIONS {
+        let (value, derivative) = function(rate);
+        let step = value / derivative;
+        if !step.is_finite() || rate - step <= -1.0 {
+            break;
+        }
+        rate -= step;
+        if step.abs() < ROOT_PRECISION * rate.abs().max(1.0) {
+            return Some(rate);
+        }
+    }
+
+    let value = |rate: f64| function(rate).0;
+    let (mut lower, mut upper) = ROOT_BRACKETS
+        .windows(2)
+        .map(|bracket| (bracket[0], bracket[1]))
+        .filter(|(lower, upper)| value(*lower) * value(*upper) <= 0.0)
+        .min_by(|a, b| {
+            let distance = |(lower, upper): (f64, f64)| (guess - guess.clamp(lower, upper)).abs();
+            distance(*a).total_cmp(&distance(*b))
+        })?;
+    let lower_sign = value(lower).signum();
+    while upper - lower > ROOT_PRECISION * lower.abs().max(1.0) {
+        let middle = (lower + upper) / 2.0;
+        if value(middle).signum() == lower_sign {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+    let rate = (lower + upper) / 2.0;
+    value(rate).is_finite().then_some(rate)
+}
+
+/// Return the net present value of the cash flows at the given times, in periods, and its derivative by the rate
+fn net_present_value(rate: f64, flows: &[(f64, f64)]) -> (f64, f64) {
+    flows.iter().fold((0.0, 0.0), |(value, derivative), (flow, time)| {
+        let discount = (1.0 + rate).powf(*time);
+        (
+            value + flow / discount,
+            derivative - time * flow / (discount * (1.0 + rate)),
+        )
+    })
+}
+
+/// Return the depreciation of a period by the double-declining balance method, or another factor, like `DDB`
+fn declining_balance(cost: f64, salvage: f64, life: f64, period: f64, factor: f64) -> f64 {
+    let rate = factor / life;
+    let (rate, old_value) = if rate >= 1.0 {
+        (1.0, if period == 1.0 { cost } else { 0.0 })
+    } else {
+        (rate, cost * (1.0 - rate).powf(period - 1.0))
+    };
+    let new_value = cost * (1.0 - rate).powf(period);
+    let depreciation = if new_value < salvage {
+        old_value - salvage
+    } else {
+        old_value - new_value
+    };
+    depreciation.max(0.0)
+}
+
+/// Return the depreciation of the first periods of an asset by the declining balance method,
+/// which switches to the straight-line method when it's larger, like `VDB`.
+/// The last period can be partial, and the straight-line method spreads the rest over `remaining_life`.
+fn switching_declining_balance(
+    cost: f64,
+    salvage: f64,
+    life: f64,
+    remaining_life: f64,
+    periods: f64,
+    factor: f64,
+) -> f64 {
+    let last_period = periods.ceil();
+    let mut rest = cost - salvage;
+    let mut straight_line = None;
+    let mut depreciation = 0.0;
+    let mut period = 1.0;
+    while period <= last_period {
+        let term = match straight_line {
+            Some(straight_line) => straight_line,
+            None => {
+                let declining = declining_balance(cost, salvage, life, period, factor);
+                let straight = rest / (remaining_life - (period - 1.0));
+                if straight > declining {
+                    straight_line = Some(straight);
+                    straight
+                } else {
+                    rest -= declining;
+                    declining
+                }
+            }
+        };
+        depreciation += if period == last_period {
+            term * (periods + 1.0 - last_period)
+        } else {
+            term
+        };
+        period += 1.0;
+    }
+    depreciation
+}
+
+/// Return the price of a security per 100 face value, which pays periodic interest, and its derivative by the yield,
+/// like `PRICE`
+fn bond_price(period: &CouponPeriod, rate: f64, yld: f64, redemption: f64, frequency: f64) -> (f64, f64) {
+    let coupon = 100.0 * rate / frequency;
+    let accrued = coupon * period.accrued_days / period.days;
+    let remaining = period.remaining_days / period.days;
+    if period.count == 1 {
+        let discount = 1.0 + remaining * yld / frequency;
+        let price = (redemption + coupon) / discount - accrued;
+        let derivative = -(redemption + coupon) * remaining / frequency / (discount * discount);
+        return (price, derivative);
+    }
+
+    let base = 1.0 + yld / frequency;
+    let (price, derivative) = bond_cash_flows(period, coupon, redemption, remaining).fold(
+        (-accrued, 0.0),
+        |(price, derivative), (cash_flow, time)| {
+            let value = cash_flow / base.powf(time);
+            (price + value, derivative - time / frequency * value / base)
+        },
+    );
+    (price, derivative)
+}
+
+/// Return the cash flows of a security from the settlement date, and their times in coupon periods
+fn bond_cash_flows(
+    period: &CouponPeriod,
+    coupon: f64,
+    redemption: f64,
+    remaining: f64,
+) -> impl Iterator<Item = (f64, f64)> {
+    let count = period.count;
+    (1..=count).map(move |k| {
+        let cash_flow = if k == count { coupon + redemption } else { coupon };
+        (cash_flow, f64::from(k - 1) + remaining)
+    })
+}
+
+/// Return the future value of an annuity, where the payments are made at the end of the periods,
+/// or at the beginning if `at_beginning` is `true`
+fn future_value(rate: f64, nper: f64, pmt: f64, pv: f64, at_beginning: bool) -> f64 {
+    if rate == 0.0 {
+        return -(pv + pmt * nper);
+    }
+    let term = (1.0 + rate).powf(nper);
+    let due = if at_beginning { 1.0 + rate } else { 1.0 };
+    -(pv * term + pmt * due * (term - 1.0) / rate)
+}
+
+fn present_value(rate: f64, nper: f64, pmt: f64, fv: f64, at_beginning: bool) -> f64 {
+    if rate == 0.0 {
+        return -(fv + pmt * nper);
+    }
+    let term = (1.0 + rate).powf(nper);
+    let due = if at_beginning { 1.0 + rate } else { 1.0 };
+    -(fv + pmt * due * (term - 1.0) / rate) / term
+}
+
+fn payment(rate: f64, nper: f64, pv: f64, fv: f64, at_beginning: bool) -> f64 {
+    if rate == 0.0 {
+        return -(pv + fv) / nper;
+    }
+    let term = (1.0 + rate).powf(nper);
+    let due = if at_beginning { 1.0 + rate } else { 1.0 };
+    -rate * (pv * term + fv) / (due * (term - 1.0))
+}
+
+/// Return the interest part of the payment of a period, which starts from `1`
+fn interest_payment(rate: f64, per: f64, nper: f64, pv: f64, fv: f64, at_beginning: bool) -> f64 {
+    let pmt = payment(rate, nper, pv, fv, at_beginning);
+    let interest = if !at_beginning {
+        future_value(rate, per - 1.0, pmt, pv, false)
+    } else if per == 1.0 {
+        // There is no interest before the first payment
+        0.0
+    } else {
+        future_value(rate, per - 2.0, pmt, pv, true) - pmt
+    };
+    interest * rate
+}
+
+impl Evaluator<'_> {
+    /// Return the `type` argument, where any value except `0` means the payments are at the beginning of the periods,
+    /// like Excel
+    fn get_payment_type(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<bool> {
+        Ok(self.get_opt_number(args, rule_name, 0.0)? != 0.0)
+    }
+
+    pub(crate) fn parse_pmt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let nper = self.get_number(&mut args, &rule_name)?;
+        let pv = self.get_number(&mut args, &rule_name)?;
+        let fv = self.get_opt_number(&mut args, &rule_name, 0.0)?;
+        let at_beginning = self.get_payment_type(&mut args, &rule_name)?;
+
+        Ok(Expr::Number(payment(rate, nper, pv, fv, at_beginning)))
+    }
+
+    pub(crate) fn parse_ipmt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let per = self.get_number(&mut args, &rule_name)?;
+        let nper = self.get_number(&mut args, &rule_name)?;
+        let pv = self.get_number(&mut args, &rule_name)?;
+        let fv = self.get_opt_number(&mut args, &rule_name, 0.0)?;
+        let at_beginning = self.get_payment_type(&mut args, &rule_name)?;
+
+        if per < 1.0 || per > nper {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number(interest_payment(rate, per, nper, pv, fv, at_beginning)))
+    }
+
+    pub(crate) fn parse_ppmt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let per = self.get_number(&mut args, &rule_name)?;
+        let nper = self.get_number(&mut args, &rule_name)?;
+        let pv = self.get_number(&mut args, &rule_name)?;
+        let fv = self.get_opt_number(&mut args, &rule_name, 0.0)?;
+        let at_beginning = self.get_payment_type(&mut args, &rule_name)?;
+
+        if per < 1.0 || per > nper {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let pmt = payment(rate, nper, pv, fv, at_beginning);
+        Ok(Expr::Number(
+            pmt - interest_payment(rate, per, nper, pv, fv, at_beginning),
+        ))
+    }
+
+    /// Return the interest and the principal parts of the payments of a range of periods, like `CUMIPMT`
+    fn get_cumulative_payments(&self, rule: Rule, args: &[Node]) -> Result<(f64, f64)> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let nper = self.get_number(&mut args, &rule_name)?.trunc();
+        let pv = self.get_number(&mut args, &rule_name)?;
+        let start = self.get_number(&mut args, &rule_name)?.trunc();
+        let end = self.get_number(&mut args, &rule_name)?.trunc();
+        let payment_type = self.get_number(&mut args, &rule_name)?.trunc();
+
+        if rate <= 0.0 || nper <= 0.0 || pv <= 0.0 || start < 1.0 || end < start || end > nper {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let at_beginning = match payment_type {
+            0.0 => false,
+            1.0 => true,
+            _ => return Err(Error::Excel(ErrorKind::Num)),
+        };
+
+        let pmt = payment(rate, nper, pv, 0.0, at_beginning);
+        let mut interest = 0.0;
+        let mut principal = 0.0;
+        let mut per = start;
+        while per <= end {
+            let ipmt = interest_payment(rate, per, nper, pv, 0.0, at_beginning);
+            interest += ipmt;
+            principal += pmt - ipmt;
+            per += 1.0;
+        }
+        Ok((interest, principal))
+    }
+
+    pub(crate) fn parse_cumipmt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (interest, _) = self.get_cumulative_payments(rule, args)?;
+        Ok(Expr::Number(interest))
+    }
+
+    pub(crate) fn parse_cumprinc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (_, principal) = self.get_cumulative_payments(rule, args)?;
+        Ok(Expr::Number(principal))
+    }
+
+    pub(crate) fn parse_fv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let nper = self.get_number(&mut args, &rule_name)?;
+        let pmt = self.get_number(&mut args, &rule_name)?;
+        let pv = self.get_opt_number(&mut args, &rule_name, 0.0)?;
+        let at_beginning = self.get_payment_type(&mut args, &rule_name)?;
+
+        Ok(Expr::Number(future_value(rate, nper, pmt, pv, at_beginning)))
+    }
+
+    pub(crate) fn parse_fvschedule(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let principal = self.get_number(&mut args, &rule_name)?;
+        let schedule = Self::get_numbers([self.get_formula(&mut args, &rule_name)?], &rule_name)?;
+
+        Ok(Expr::Number(
+            schedule.iter().fold(principal, |value, rate| value * (1.0 + rate)),
+        ))
+    }
+
+    pub(crate) fn parse_pv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let nper = self.get_number(&mut args, &rule_name)?;
+        let pmt = self.get_number(&mut args, &rule_name)?;
+        let fv = self.get_opt_number(&mut args, &rule_name, 0.0)?;
+        let at_beginning = self.get_payment_type(&mut args, &rule_name)?;
+
+        Ok(Expr::Number(present_value(rate, nper, pmt, fv, at_beginning)))
+    }
+
+    pub(crate) fn parse_nper(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let pmt = self.get_number(&mut args, &rule_name)?;
+        let pv = self.get_number(&mut args, &rule_name)?;
+        let fv = self.get_opt_number(&mut args, &rule_name, 0.0)?;
+        let at_beginning = self.get_payment_type(&mut args, &rule_name)?;
+
+        if rate == 0.0 {
+            if pmt == 0.0 {
+                return Err(Error::Excel(ErrorKind::Num));
+            }
+            return Ok(Expr::Number(-(pv + fv) / pmt));
+        }
+        let due = if at_beginning { 1.0 + rate } else { 1.0 };
+        let ratio = (pmt * due - fv * rate) / (pv * rate + pmt * due);
+        if ratio <= 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number(ratio.ln() / rate.ln_1p()))
+    }
+
+    /// The rate is found by the Newton's method from the guess, which fails with `#NUM!` if it doesn't converge
+    /// after 20 iterations, like Excel
+    pub(crate) fn parse_rate(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let nper = self.get_number(&mut args, &rule_name)?;
+        let pmt = self.get_number(&mut args, &rule_name)?;
+        let pv = self.get_number(&mut args, &rule_name)?;
+        let fv = self.get_opt_number(&mut args, &rule_name, 0.0)?;
+        let payment_type = if self.get_payment_type(&mut args, &rule_name)? {
+            1.0
+        } else {
+            0.0
+        };
+        let guess = self.get_opt_number(&mut args, &rule_name, 0.1)?;
+
+        // The balance at the end of the periods, which is zero for the rate, and its derivative
+        let balance = |rate: f64| {
+            if rate.abs() < 1e-10 {
+                let value = pv + pmt * nper + fv;
+                let derivative = pv * nper + pmt * payment_type * nper + pmt * nper * (nper - 1.0) / 2.0;
+                return (value, derivative);
+            }
+            let term = (1.0 + rate).powf(nper);
+            let term_derivative = nper * (1.0 + rate).powf(nper - 1.0);
+            let annuity = (term - 1.0) / rate;
+            let annuity_derivative = (term_derivative * rate - (term - 1.0)) / (rate * rate);
+            let due = 1.0 + rate * payment_type;
+            let value = pv * term + pmt * due * annuity + fv;
+            let derivative = pv * term_derivative + pmt * payment_type * annuity + pmt * due * annuity_derivative;
+            (value, derivative)
+        };
+
+        let mut rate = guess;
+        for _ in 0..RATE_ITERATIONS {
+            let (value, derivative) = balance(rate);
+            let step = value / derivative;
+            if !step.is_finite() {
+                break;
+            }
+            rate -= step;
+            if step.abs() < RATE_PRECISION {
+                return Ok(Expr::Number(rate));
+            }
+        }
+        Err(Error::Excel(ErrorKind::Num))
+    }
+
+    pub(crate) fn parse_pduration(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let pv = self.get_number(&mut args, &rule_name)?;
+        let fv = self.get_number(&mut args, &rule_name)?;
+
+        if rate <= 0.0 || pv <= 0.0 || fv <= 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number((fv.ln() - pv.ln()) / rate.ln_1p()))
+    }
+
+    pub(crate) fn parse_rri(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let nper = self.get_number(&mut args, &rule_name)?;
+        let pv = self.get_number(&mut args, &rule_name)?;
+        let fv = self.get_number(&mut args, &rule_name)?;
+
+        if nper <= 0.0 || pv == 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number((fv / pv).powf(1.0 / nper) - 1.0))
+    }
+
+    pub(crate) fn parse_effect(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let nominal_rate = self.get_number(&mut args, &rule_name)?;
+        let npery = self.get_number(&mut args, &rule_name)?.trunc();
+
+        if nominal_rate <= 0.0 || npery < 1.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number((1.0 + nominal_rate / npery).powf(npery) - 1.0))
+    }
+
+    pub(crate) fn parse_nominal(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let effect_rate = self.get_number(&mut args, &rule_name)?;
+        let npery = self.get_number(&mut args, &rule_name)?.trunc();
+
+        if effect_rate <= 0.0 || npery < 1.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number(((1.0 + effect_rate).powf(1.0 / npery) - 1.0) * npery))
+    }
+
+    /// Return the cash flows of an argument, where the values except the numbers are ignored, like Excel
+    fn get_cash_flows(&self, arg: Option<&Node>, rule_name: &str) -> Result<Vec<f64>> {
+        let arg = arg.ok_or_else(|| Error::Parser(rule_name.to_owned()))?;
+        self.get_stat_numbers(std::slice::from_ref(arg), Values::Numbers)
+    }
+
+    /// Return the cash flows and their dates, as the number of the years from the first date.
+    /// All the values should be numbers and dates, and no date can be before the first date, like Excel.
+    fn get_dated_cash_flows(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<CashFlows> {
+        let values = Self::get_range(self.get_formula(args, rule_name)?);
+        let dates = Self::get_range(self.get_formula(args, rule_name)?);
+        if values.len() != dates.len() {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+
+        let dates = dates
+            .into_iter()
+            .map(|date| Self::to_date(date, rule_name))
+            .collect::<Result<Vec<_>>>()?;
+        let first = *dates.first().ok_or(Error::Excel(ErrorKind::Num))?;
+        let mut flows = Vec::with_capacity(values.len());
+        for (value, date) in values.into_iter().zip(dates) {
+            let value = match value {
+                Expr::Number(value) => value,
+                Expr::Error(kind) => return Err(Error::Excel(kind)),
+                _ => return Err(Error::Excel(ErrorKind::Value)),
+            };
+            let days = date.signed_duration_since(first).num_days();
+            if days < 0 {
+                return Err(Error::Excel(ErrorKind::Num));
+            }
+            flows.push((value, days as f64 / 365.0));
+        }
+        Ok(flows)
+    }
+
+    /// Return a date of a financial function, which is a date, a date time, a serial number of a date,
+    /// like `DATE(2024, 1, 31) + 1`, or a text of a date, like `"1/31/2024"`
+    pub(crate) fn to_date(value: Expr, rule_name: &str) -> Result<NaiveDate> {
+        match value {
+            Expr::Date(date) => Ok(date),
+            Expr::Number(serial) => Self::from_serial(serial).ok_or(Error::Excel(ErrorKind::Num)),
+            Expr::Datetime(datetime) => Ok(datetime.date_naive()),
+            Expr::String(date) => {
+                Self::datestring_to_naivedate(&date, rule_name).map_err(|_| Error::Excel(ErrorKind::Value))
+            }
+            Expr::Error(kind) => Err(Error::Excel(kind)),
+            _ => Err(Error::Excel(ErrorKind::Value)),
+        }
+    }
+
+    /// The cash flows should have a positive and a negative value, otherwise there is no rate of return
+    fn check_cash_flows<'a>(flows: impl IntoIterator<Item = &'a f64> + Clone) -> Result<()> {
+        let positive = flows.clone().into_iter().any(|flow| *flow > 0.0);
+        let negative = flows.into_iter().any(|flow| *flow < 0.0);
+        if positive && negative {
+            Ok(())
+        } else {
+            Err(Error::Excel(ErrorKind::Num))
+        }
+    }
+
+    /// The values are at the end of the periods, so the first value is discounted too, like Excel
+    pub(crate) fn parse_npv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let (rate, values) = args.split_first().ok_or(Error::Parser(rule_name.clone()))?;
+        let rate = self.get_number(&mut std::slice::from_ref(rate).iter(), &rule_name)?;
+        let values = self.get_stat_numbers(values, Values::Numbers)?;
+
+        let flows = values
+            .into_iter()
+            .zip(1..)
+            .map(|(value, period)| (value, f64::from(period)))
+            .collect::<Vec<_>>();
+        Ok(Expr::Number(net_present_value(rate, &flows).0))
+    }
+
+    pub(crate) fn parse_xnpv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let flows = self.get_dated_cash_flows(&mut args, &rule_name)?;
+
+        if rate <= -1.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number(net_present_value(rate, &flows).0))
+    }
+
+    pub(crate) fn parse_irr(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let values = self.get_cash_flows(args.first(), &rule_name)?;
+        let guess = self.get_opt_number(&mut args[1..].iter(), &rule_name, 0.1)?;
+
+        Self::check_cash_flows(&values)?;
+        let flows = values
+            .into_iter()
+            .zip(0..)
+            .map(|(value, period)| (value, f64::from(period)))
+            .collect::<Vec<_>>();
+        let rate = find_root(|rate| net_present_value(rate, &flows), guess).ok_or(Error::Excel(ErrorKind::Num))?;
+        Ok(Expr::Number(rate))
+    }
+
+    pub(crate) fn parse_xirr(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let flows = self.get_dated_cash_flows(&mut args, &rule_name)?;
+        let guess = self.get_opt_number(&mut args, &rule_name, 0.1)?;
+
+        Self::check_cash_flows(flows.iter().map(|(flow, _)| flow))?;
+        let rate = find_root(|rate| net_present_value(rate, &flows), guess).ok_or(Error::Excel(ErrorKind::Num))?;
+        Ok(Expr::Number(rate))
+    }
+
+    /// The negative values are discounted by the finance rate, and the positive values are compounded
+    /// by the reinvestment rate, like Excel
+    pub(crate) fn parse_mirr(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let values = self.get_cash_flows(args.first(), &rule_name)?;
+        let mut args = args[1..].iter();
+        let finance_rate = self.get_number(&mut args, &rule_name)?;
+        let reinvest_rate = self.get_number(&mut args, &rule_name)?;
+
+        if Self::check_cash_flows(&values).is_err() {
+            return Err(Error::Excel(ErrorKind::DivZero));
+        }
+        let periods = values.len() as f64;
+        let (negative, positive) = values
+            .iter()
+            .zip(0..)
+            .fold((0.0, 0.0), |(negative, positive), (value, period)| {
+                let period = f64::from(period);
+                if *value < 0.0 {
+                    (negative + value / (1.0 + finance_rate).powf(period), positive)
+                } else {
+                    (
+                        negative,
+                        positive + value * (1.0 + reinvest_rate).powf(periods - period - 1.0),
+                    )
+                }
+            });
+        Ok(Expr::Number((-positive / negative).powf(1.0 / (periods - 1.0)) - 1.0))
+    }
+
+    pub(crate) fn parse_sln(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let cost = self.get_number(&mut args, &rule_name)?;
+        let salvage = self.get_number(&mut args, &rule_name)?;
+        let life = self.get_number(&mut args, &rule_name)?;
+
+        if life == 0.0 {
+            return Err(Error::Excel(ErrorKind::DivZero));
+        }
+        Ok(Expr::Number((cost - salvage) / life))
+    }
+
+    pub(crate) fn parse_syd(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let cost = self.get_number(&mut args, &rule_name)?;
+        let salvage = self.get_number(&mut args, &rule_name)?;
+        let life = self.get_number(&mut args, &rule_name)?;
+        let per = self.get_number(&mut args, &rule_name)?;
+
+        if life <= 0.0 || per <= 0.0 || per > life {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number(
+            (cost - salvage) * (life - per + 1.0) * 2.0 / (life * (life + 1.0)),
+        ))
+    }
+
+    /// The rate is rounded to three decimals, and the first and the last years are partial by `month`, like Excel
+    pub(crate) fn parse_db(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let cost = self.get_number(&mut args, &rule_name)?;
+        let salvage = self.get_number(&mut args, &rule_name)?;
+        let life = self.get_number(&mut args, &rule_name)?.trunc();
+        let period = self.get_number(&mut args, &rule_name)?.trunc();
+        let month = self.get_opt_number(&mut args, &rule_name, 12.0)?.trunc();
+
+        let last_period = if month == 12.0 { life } else { life + 1.0 };
+        if cost < 0.0 || salvage < 0.0 || life <= 0.0 || period <= 0.0 || period > last_period {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        if !(1.0..=12.0).contains(&month) {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        if cost == 0.0 {
+            return Ok(Expr::Number(0.0));
+        }
+
+        let rate = ((1.0 - (salvage / cost).powf(1.0 / life)) * 1000.0).round() / 1000.0;
+        let mut total = cost * rate * month / 12.0;
+        let mut depreciation = total;
+        let mut current = 2.0;
+        while current <= period {
+            depreciation = if current == life + 1.0 {
+                (cost - total) * rate * (12.0 - month) / 12.0
+            } else {
+                (cost - total) * rate
+            };
+            total += depreciation;
+            current += 1.0;
+        }
+        Ok(Expr::Number(depreciation))
+    }
+
+    pub(crate) fn parse_ddb(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let cost = self.get_number(&mut args, &rule_name)?;
+        let salvage = self.get_number(&mut args, &rule_name)?;
+        let life = self.get_number(&mut args, &rule_name)?;
+        let period = self.get_number(&mut args, &rule_name)?;
+        let factor = self.get_opt_number(&mut args, &rule_name, 2.0)?;
+
+        if cost < 0.0 || salvage < 0.0 || life <= 0.0 || period <= 0.0 || period > life || factor <= 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number(declining_balance(cost, salvage, life, period, factor)))
+    }
+
+    /// The periods can be partial, and it switches to the straight-line method when it's larger,
+    /// unless `no_switch` is `TRUE`, like Excel
+    pub(crate) fn parse_vdb(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let mut cost = self.get_number(&mut args, &rule_name)?;
+        let salvage = self.get_number(&mut args, &rule_name)?;
+        let life = self.get_number(&mut args, &rule_name)?;
+        let mut start = self.get_number(&mut args, &rule_name)?;
+        let mut end = self.get_number(&mut args, &rule_name)?;
+        let factor = self.get_opt_number(&mut args, &rule_name, 2.0)?;
+        let no_switch = self.get_opt_number(&mut args, &rule_name, 0.0)? != 0.0;
+
+        if cost < 0.0 || salvage < 0.0 || life <= 0.0 || start < 0.0 || end < start || end > life || factor <= 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+
+        if no_switch {
+            let first_period = start.floor() + 1.0;
+            let last_period = end.ceil();
+            let mut depreciation = 0.0;
+            let mut period = first_period;
+            while period <= last_period {
+                let term = declining_balance(cost, salvage, life, period, factor);
+                depreciation += if period == first_period {
+                    term * (end.min(first_period) - start)
+                } else if period == last_period {
+                    term * (end + 1.0 - last_period)
+                } else {
+                    term
+                };
+                period += 1.0;
+            }
+            return Ok(Expr::Number(depreciation));
+        }
+
+        let mut remaining_life = life;
+        if start != start.floor() && factor > 1.0 && start >= life / 2.0 {
+            let part = start - life / 2.0;
+            start = life / 2.0;
+            end -= part;
+            remaining_life += 1.0;
+        }
+        cost -= switching_declining_balance(cost, salvage, life, remaining_life, start, factor);
+        let depreciation = switching_declining_balance(cost, salvage, life, life - start, end - start, factor);
+        Ok(Expr::Number(depreciation))
+    }
+
+    fn get_date(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<NaiveDate> {
+        Self::to_date(self.get_formula(args, rule_name)?, rule_name)
+    }
+
+    fn get_basis(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<Basis> {
+        Basis::new(self.get_opt_number(args, rule_name, 0.0)?)
+    }
+
+    /// The depreciation of the French accounting system, where the rate is increased by the life of the asset,
+    /// and the depreciations are rounded, like Excel
+    pub(crate) fn parse_amordegrc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let mut cost = self.get_number(&mut args, &rule_name)?;
+        let date_purchased = self.get_date(&mut args, &rule_name)?;
+        let first_period = self.get_date(&mut args, &rule_name)?;
+        let salvage = self.get_number(&mut args, &rule_name)?;
+        let period = self.get_number(&mut args, &rule_name)?.trunc();
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+
+        if cost < 0.0 || salvage < 0.0 || salvage > cost || period < 0.0 || rate <= 0.0 || basis == Basis::Actual360 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        // Like Excel, the fractional lives between 0 and 3, or between 4 and 5, are not valid
+        let life = 1.0 / rate;
+        if life.fract() != 0.0 && (life < 3.0 || (4.0..5.0).contains(&life)) {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let coefficient = match life {
+            life if life < 3.0 => 1.0,
+            life if life < 5.0 => 1.5,
+            life if life <= 6.0 => 2.0,
+            _ => 2.5,
+        };
+        let rate = rate * coefficient;
+
+        let mut depreciation = (basis.year_fraction(date_purchased, first_period) * rate * cost).round();
+        cost -= depreciation;
+        let mut rest = cost - salvage;
+        let mut current = 0.0;
+        while current < period {
+            depreciation = (rate * cost).round();
+            rest -= depreciation;
+            if rest < 0.0 {
+                // The rest is depreciated in the two last periods
+                let depreciation = if period - current <= 1.0 {
+                    (cost * 0.5).round()
+                } else {
+                    0.0
+                };
+                return Ok(Expr::Number(depreciation));
+            }
+            cost -= depreciation;
+            current += 1.0;
+        }
+        Ok(Expr::Number(depreciation))
+    }
+
+    /// The linear depreciation of the French accounting system, where the first period is partial, like Excel
+    pub(crate) fn parse_amorlinc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let cost = self.get_number(&mut args, &rule_name)?;
+        let date_purchased = self.get_date(&mut args, &rule_name)?;
+        let first_period = self.get_date(&mut args, &rule_name)?;
+        let salvage = self.get_number(&mut args, &rule_name)?;
+        let period = self.get_number(&mut args, &rule_name)?.trunc();
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+
+        if cost < 0.0 || salvage < 0.0 || salvage > cost || period < 0.0 || rate <= 0.0 || basis == Basis::Actual360 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let full_rate = cost * rate;
+        let first_rate = basis.year_fraction(date_purchased, first_period) * rate * cost;
+        let full_periods = ((cost - salvage - first_rate) / full_rate).trunc();
+
+        let depreciation = if period == 0.0 {
+            first_rate
+        } else if period <= full_periods {
+            full_rate
+        } else if period == full_periods + 1.0 {
+            cost - salvage - full_rate * full_periods - first_rate
+        } else {
+            0.0
+        };
+        Ok(Expr::Number(depreciation))
+    }
+
+    /// Return the frequency of the coupons, which is `1`, `2` or `4`, or `#NUM!`, like Excel
+    fn get_frequency(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<u32> {
+        match self.get_number(args, rule_name)?.trunc() {
+            1.0 => Ok(1),
+            2.0 => Ok(2),
+            4.0 => Ok(4),
+            _ => Err(Error::Excel(ErrorKind::Num)),
+        }
+    }
+
+    /// Return the settlement and the maturity dates, where the settlement date should be before the maturity date
+    fn get_settlement_and_maturity(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<(NaiveDate, NaiveDate)> {
+        let settlement = self.get_date(args, rule_name)?;
+        let maturity = self.get_date(args, rule_name)?;
+        if settlement >= maturity {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok((settlement, maturity))
+    }
+
+    fn get_coupon_period(&self, rule: Rule, args: &[Node]) -> Result<CouponPeriod> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let (settlement, maturity) = self.get_settlement_and_maturity(&mut args, &rule_name)?;
+        let frequency = self.get_frequency(&mut args, &rule_name)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+
+        Ok(CouponPeriod::new(settlement, maturity, frequency, basis))
+    }
+
+    pub(crate) fn parse_coupdaybs(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        Ok(Expr::Number(self.get_coupon_period(rule, args)?.accrued_days))
+    }
+
+    pub(crate) fn parse_coupdays(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        Ok(Expr::Number(self.get_coupon_period(rule, args)?.days))
+    }
+
+    pub(crate) fn parse_coupdaysnc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        Ok(Expr::Number(self.get_coupon_period(rule, args)?.remaining_days))
+    }
+
+    pub(crate) fn parse_coupncd(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        Ok(Expr::Date(self.get_coupon_period(rule, args)?.next))
+    }
+
+    pub(crate) fn parse_coupnum(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        Ok(Expr::Number(f64::from(self.get_coupon_period(rule, args)?.count)))
+    }
+
+    pub(crate) fn parse_couppcd(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        Ok(Expr::Date(self.get_coupon_period(rule, args)?.previous))
+    }
+
+    pub(crate) fn parse_price(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let (settlement, maturity) = self.get_settlement_and_maturity(&mut args, &rule_name)?;
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let yld = self.get_number(&mut args, &rule_name)?;
+        let redemption = self.get_number(&mut args, &rule_name)?;
+        let frequency = self.get_frequency(&mut args, &rule_name)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+
+        if rate < 0.0 || yld < 0.0 || redemption <= 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let period = CouponPeriod::new(settlement, maturity, frequency, basis);
+        Ok(Expr::Number(
+            bond_price(&period, rate, yld, redemption, f64::from(frequency)).0,
+        ))
+    }
+
+    /// The yield of a security with more than one coupon is found by the root finder of `IRR`, like Excel
+    pub(crate) fn parse_yield_(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let (settlement, maturity) = self.get_settlement_and_maturity(&mut args, &rule_name)?;
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let pr = self.get_number(&mut args, &rule_name)?;
+        let redemption = self.get_number(&mut args, &rule_name)?;
+        let frequency = self.get_frequency(&mut args, &rule_name)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+
+        if rate < 0.0 || pr <= 0.0 || redemption <= 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let period = CouponPeriod::new(settlement, maturity, frequency, basis);
+        let frequency = f64::from(frequency);
+        if period.count == 1 {
+            let coupon = rate / frequency;
+            let paid = pr / 100.0 + period.accrued_days / period.days * coupon;
+            let yld = (redemption / 100.0 + coupon - paid) / paid * frequency * period.days / period.remaining_days;
+            return Ok(Expr::Number(yld));
+        }
+
+        let yld = find_root(
+            |yld| {
+                let (price, derivative) = bond_price(&period, rate, yld, redemption, frequency);
+                (price - pr, derivative)
+            },
+            rate,
+        )
+        .ok_or(Error::Excel(ErrorKind::Num))?;
+        Ok(Expr::Number(yld))
+    }
+
+    /// Return the Macaulay duration of a security, like `DURATION`
+    fn get_duration(&self, rule: Rule, args: &[Node]) -> Result<(f64, f64, f64)> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let (settlement, maturity) = self.get_settlement_and_maturity(&mut args, &rule_name)?;
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let yld = self.get_number(&mut args, &rule_name)?;
+        let frequency = self.get_frequency(&mut args, &rule_name)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+
+        if rate < 0.0 || yld < 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let period = CouponPeriod::new(settlement, maturity, frequency, basis);
+        let frequency = f64::from(frequency);
+        let base = 1.0 + yld / frequency;
+        let remaining = period.remaining_days / period.days;
+        let (weighted, price) = bond_cash_flows(&period, 100.0 * rate / frequency, 100.0, remaining).fold(
+            (0.0, 0.0),
+            |(weighted, price), (cash_flow, time)| {
+                let value = cash_flow / base.powf(time);
+                (weighted + time * value, price + value)
+            },
+        );
+        Ok((weighted / price / frequency, yld, frequency))
+    }
+
+    pub(crate) fn parse_duration(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (duration, _, _) = self.get_duration(rule, args)?;
+        Ok(Expr::Number(duration))
+    }
+
+    pub(crate) fn parse_mduration(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (duration, yld, frequency) = self.get_duration(rule, args)?;
+        Ok(Expr::Number(duration / (1.0 + yld / frequency)))
+    }
+
+    /// The interest is accrued over the coupon periods of the first interest date, from the issue date,
+    /// or from the first interest date if `calc_method` is `FALSE` and it's before the settlement date, like Excel
+    pub(crate) fn parse_accrint(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let issue = self.get_date(&mut args, &rule_name)?;
+        let first_interest = self.get_date(&mut args, &rule_name)?;
+        let settlement = self.get_date(&mut args, &rule_name)?;
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let par = self.get_opt_number(&mut args, &rule_name, 1000.0)?;
+        let frequency = self.get_frequency(&mut args, &rule_name)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+        let calc_method = self.get_opt_number(&mut args, &rule_name, 1.0)? != 0.0;
+
+        if rate <= 0.0 || par <= 0.0 || issue >= settlement {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let start = if !calc_method && settlement > first_interest {
+            first_interest
+        } else {
+            issue
+        };
+
+        let months = (12 / frequency) as i32;
+        let mut index = 0;
+        while coupon_date(first_interest, index * months) > start {
+            index -= 1;
+        }
+        while coupon_date(first_interest, (index + 1) * months) <= start {
+            index += 1;
+        }
+        let mut periods = 0.0;
+        loop {
+            let previous = coupon_date(first_interest, index * months);
+            if previous >= settlement {
+                break;
+            }
+            let next = coupon_date(first_interest, (index + 1) * months);
+            let days = basis.days(previous.max(start), next.min(settlement));
+            periods += days / basis.coupon_days(previous, next, frequency);
+            index += 1;
+        }
+        Ok(Expr::Number(par * rate / f64::from(frequency) * periods))
+    }
+
+    pub(crate) fn parse_accrintm(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let issue = self.get_date(&mut args, &rule_name)?;
+        let settlement = self.get_date(&mut args, &rule_name)?;
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let par = self.get_opt_number(&mut args, &rule_name, 1000.0)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+
+        if rate <= 0.0 || par <= 0.0 || issue >= settlement {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number(par * rate * basis.year_fraction(issue, settlement)))
+    }
+
+    /// Return the arguments of a discounted security, like `PRICEDISC`, which are the fraction of the years
+    /// from the settlement to the maturity date, and two positive numbers
+    fn get_discounted_security(&self, rule: Rule, args: &[Node]) -> Result<(f64, f64, f64)> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let (settlement, maturity) = self.get_settlement_and_maturity(&mut args, &rule_name)?;
+        let value1 = self.get_number(&mut args, &rule_name)?;
+        let value2 = self.get_number(&mut args, &rule_name)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+
+        if value1 <= 0.0 || value2 <= 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok((basis.year_fraction(settlement, maturity), value1, value2))
+    }
+
+    pub(crate) fn parse_pricedisc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (years, discount, redemption) = self.get_discounted_security(rule, args)?;
+        Ok(Expr::Number(redemption * (1.0 - discount * years)))
+    }
+
+    pub(crate) fn parse_yielddisc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (years, pr, redemption) = self.get_discounted_security(rule, args)?;
+        Ok(Expr::Number((redemption / pr - 1.0) / years))
+    }
+
+    pub(crate) fn parse_disc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (years, pr, redemption) = self.get_discounted_security(rule, args)?;
+        Ok(Expr::Number((1.0 - pr / redemption) / years))
+    }
+
+    pub(crate) fn parse_intrate(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (years, investment, redemption) = self.get_discounted_security(rule, args)?;
+        Ok(Expr::Number((redemption / investment - 1.0) / years))
+    }
+
+    pub(crate) fn parse_received(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (years, investment, discount) = self.get_discounted_security(rule, args)?;
+        let rest = 1.0 - discount * years;
+        if rest <= 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number(investment / rest))
+    }
+
+    /// Return the arguments of a security which pays interest at maturity, like `PRICEMAT`, which are
+    /// the accrued interest at the settlement date, the fraction of the years from the settlement to the maturity date,
+    /// the total interest at the maturity date, and the yield or the price
+    fn get_security_at_maturity(&self, rule: Rule, args: &[Node]) -> Result<(f64, f64, f64, f64)> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let (settlement, maturity) = self.get_settlement_and_maturity(&mut args, &rule_name)?;
+        let issue = self.get_date(&mut args, &rule_name)?;
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let value = self.get_number(&mut args, &rule_name)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+
+        if rate < 0.0 || value < 0.0 || issue > settlement {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let accrued = basis.year_fraction(issue, settlement) * rate;
+        let remaining = basis.year_fraction(settlement, maturity);
+        let total = basis.year_fraction(issue, maturity) * rate;
+        Ok((accrued, remaining, total, value))
+    }
+
+    pub(crate) fn parse_pricemat(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (accrued, remaining, total, yld) = self.get_security_at_maturity(rule, args)?;
+        Ok(Expr::Number(
+            100.0 * (1.0 + total) / (1.0 + remaining * yld) - 100.0 * accrued,
+        ))
+    }
+
+    pub(crate) fn parse_yieldmat(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (accrued, remaining, total, pr) = self.get_security_at_maturity(rule, args)?;
+        if pr == 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number(((1.0 + total) / (pr / 100.0 + accrued) - 1.0) / remaining))
+    }
+
+    /// Return the arguments of a security with an odd first coupon period, like `ODDFPRICE`, which are
+    /// the cash flows from the settlement date and their times in coupon periods, where the accrued interest
+    /// is paid at the settlement date, the yield or the price, and the frequency
+    fn get_odd_first_security(&self, rule: Rule, args: &[Node]) -> Result<(CashFlows, f64, f64)> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let (settlement, maturity) = self.get_settlement_and_maturity(&mut args, &rule_name)?;
+        let issue = self.get_date(&mut args, &rule_name)?;
+        let first_coupon = self.get_date(&mut args, &rule_name)?;
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let value = self.get_number(&mut args, &rule_name)?;
+        let redemption = self.get_number(&mut args, &rule_name)?;
+        let frequency = self.get_frequency(&mut args, &rule_name)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+
+        // The dates should be in order, like `maturity >= first_coupon > settlement > issue`
+        if rate < 0.0
+            || value < 0.0
+            || redemption <= 0.0
+            || !(issue < settlement && settlement < first_coupon && first_coupon <= maturity)
+        {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let period = OddPeriod::new(issue, settlement, first_coupon, first_coupon, frequency, basis);
+        let months = 12 / frequency;
+        let mut count = 0;
+        while coupon_date(maturity, -((count * months) as i32)) > first_coupon {
+            count += 1;
+        }
+
+        let coupon = 100.0 * rate / f64::from(frequency);
+        let first_flow = coupon * period.periods + if count == 0 { redemption } else { 0.0 };
+        let flows = [(-coupon * period.accrued, 0.0), (first_flow, period.remaining)]
+            .into_iter()
+            .chain((1..=count).map(|k| {
+                let cash_flow = if k == count { coupon + redemption } else { coupon };
+                (cash_flow, period.remaining + f64::from(k))
+            }))
+            .collect();
+        Ok((flows, value, f64::from(frequency)))
+    }
+
+    pub(crate) fn parse_oddfprice(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (flows, yld, frequency) = self.get_odd_first_security(rule, args)?;
+        Ok(Expr::Number(net_present_value(yld / frequency, &flows).0))
+    }
+
+    /// The yield is found by the root finder of `IRR`, like Excel
+    pub(crate) fn parse_oddfyield(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (flows, pr, frequency) = self.get_odd_first_security(rule, args)?;
+        if pr == 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let rate = find_root(
+            |rate| {
+                let (value, derivative) = net_present_value(rate, &flows);
+                (value - pr, derivative)
+            },
+            0.1 / frequency,
+        )
+        .ok_or(Error::Excel(ErrorKind::Num))?;
+        Ok(Expr::Number(rate * frequency))
+    }
+
+    /// Return the arguments of a security with an odd last coupon period, like `ODDLPRICE`, which are
+    /// the odd period, the coupon, the redemption, the yield or the price, and the frequency
+    fn get_odd_last_security(&self, rule: Rule, args: &[Node]) -> Result<(OddPeriod, f64, f64, f64, f64)> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let (settlement, maturity) = self.get_settlement_and_maturity(&mut args, &rule_name)?;
+        let last_interest = self.get_date(&mut args, &rule_name)?;
+        let rate = self.get_number(&mut args, &rule_name)?;
+        let value = self.get_number(&mut args, &rule_name)?;
+        let redemption = self.get_number(&mut args, &rule_name)?;
+        let frequency = self.get_frequency(&mut args, &rule_name)?;
+        let basis = self.get_basis(&mut args, &rule_name)?;
+
+        if rate < 0.0 || value < 0.0 || redemption <= 0.0 || last_interest >= settlement {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let period = OddPeriod::new(last_interest, settlement, maturity, last_interest, frequency, basis);
+        let frequency = f64::from(frequency);
+        Ok((period, 100.0 * rate / frequency, redemption, value, frequency))
+    }
+
+    pub(crate) fn parse_oddlprice(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (period, coupon, redemption, yld, frequency) = self.get_odd_last_security(rule, args)?;
+        let discount = 1.0 + period.remaining * yld / frequency;
+        Ok(Expr::Number(
+            (redemption + coupon * period.periods) / discount - coupon * period.accrued,
+        ))
+    }
+
+    pub(crate) fn parse_oddlyield(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (period, coupon, redemption, pr, frequency) = self.get_odd_last_security(rule, args)?;
+        if pr == 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        let paid = pr + coupon * period.accrued;
+        Ok(Expr::Number(
+            (redemption + coupon * period.periods - paid) / paid * frequency / period.remaining,
+        ))
+    }
+
+    /// Return the arguments of a Treasury bill, like `TBILLPRICE`, which are the days from the settlement
+    /// to the maturity date, which should be in a year, and a positive number
+    fn get_treasury_bill(&self, rule: Rule, args: &[Node]) -> Result<(f64, f64)> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let (settlement, maturity) = self.get_settlement_and_maturity(&mut args, &rule_name)?;
+        let value = self.get_number(&mut args, &rule_name)?;
+
+        let a_year_later = settlement.checked_add_months(Months::new(12)).unwrap_or(NaiveDate::MAX);
+        if value <= 0.0 || maturity > a_year_later {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok((maturity.signed_duration_since(settlement).num_days() as f64, value))
+    }
+
+    /// The bond-equivalent yield of a Treasury bill, which is compounded semiannually
+    /// if the bill matures after half a year, like Excel
+    pub(crate) fn parse_tbilleq(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (days, discount) = self.get_treasury_bill(rule, args)?;
+        let price = 1.0 - discount * days / 360.0;
+        if price <= 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        if days <= 182.0 {
+            return Ok(Expr::Number(365.0 * discount / (360.0 - discount * days)));
+        }
+        let term = days / 365.0;
+        let root = (term * term - (2.0 * term - 1.0) * (1.0 - 1.0 / price)).sqrt();
+        Ok(Expr::Number((root - term) / (term - 0.5)))
+    }
+
+    pub(crate) fn parse_tbillprice(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (days, discount) = self.get_treasury_bill(rule, args)?;
+        let price = 100.0 * (1.0 - discount * days / 360.0);
+        if price <= 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Ok(Expr::Number(price))
+    }
+
+    pub(crate) fn parse_tbillyield(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (days, pr) = self.get_treasury_bill(rule, args)?;
+        Ok(Expr::Number((100.0 - pr) / pr * 360.0 / days))
+    }
+
+    /// Return the arguments of `DOLLARDE` and `DOLLARFR`, which are a dollar price, the fraction, which is truncated,
+    /// and the power of 10 which is greater than, or equal to, the fraction
+    fn get_dollar(&self, rule: Rule, args: &[Node]) -> Result<(f64, f64, f64)> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let dollar = self.get_number(&mut args, &rule_name)?;
+        let fraction = self.get_number(&mut args, &rule_name)?.trunc();
+
+        if fraction < 0.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        if fraction == 0.0 {
+            return Err(Error::Excel(ErrorKind::DivZero));
+        }
+        Ok((dollar, fraction, 10_f64.powf(fraction.log10().ceil())))
+    }
+
+    /// Convert a fractional dollar price, like `1.02` for `1 2/16`, to a decimal one
+    pub(crate) fn parse_dollarde(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (dollar, fraction, power) = self.get_dollar(rule, args)?;
+        Ok(Expr::Number(dollar.trunc() + dollar.fract() * power / fraction))
+    }
+
+    /// Convert a decimal dollar price to a fractional one, like `1.02` for `1 2/16`
+    pub(crate) fn parse_dollarfr(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (dollar, fraction, power) = self.get_dollar(rule, args)?;
+        Ok(Expr::Number(dollar.trunc() + dollar.fract() * fraction / power))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Formula;
+
+    fn assert_number(value: Expr, expected: f64) {
+        match value {
+            Expr::Number(number) => assert!((number - expected).abs() < 1e-9, "{number} != {expected}"),
+            value => panic!("{value:?} is not {expected}"),
+        }
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_parse_financial_types() {
+        let numbers = [
+            ("=PMT(0.08/12, 10, 10000)", -1_037.032_089_359_150_3),
+            ("=PMT(0.08/12, 10, 10000, 0, 1)", -1_030.164_327_177_977_2),
+            ("=PMT(0.06/12, 18*12, 0, 50000)", -129.081_160_867_995_4),
+            ("=PMT(0, 10, 1000)", -100.0),
+            ("=IPMT(0.1/12, 1, 3*12, 8000)", -66.666_666_666_666_67),
+            ("=IPMT(0.1, 3, 3, 8000)", -292.447_129_909_365_6),
+            ("=IPMT(0.1, 1, 3, 8000, 0, 1)", 0.0),
+            ("=IPMT(0.1, 2, 3, 8000, 0, 1)", -507.552_870_090_634_7),
+            ("=PPMT(0.1/12, 1, 2*12, 2000)", -75.623_186_008_368_9),
+            ("=PPMT(0.08, 10, 10, 200000)", -27_598.053_462_421_4),
+            ("=CUMIPMT(0.09/12, 30*12, 125000, 13, 24, 0)", -11_135.232_130_750_8),
+            ("=CUMIPMT(0.09/12, 30*12, 125000, 1, 1, 0)", -937.5),
+            ("=CUMPRINC(0.09/12, 30*12, 125000, 13, 24, 0)", -934.107_123_420_072),
+            ("=CUMPRINC(0.09/12, 30*12, 125000, 1, 1, 0)", -68.278_271_180_978_4),
+            ("=FV(0.06/12, 10, -200, -500, 1)", 2_581.403_374_060_12),
+            ("=FV(0.12/12, 12, -1000)", 12_682.503_013_196_97),
+            ("=FV(0, 12, -100, -1000)", 2_200.0),
+            ("=FVSCHEDULE(1, {0.09, 0.11, 0.1})", 1.330_89),
+            ("=PV(0.08/12, 12*20, 500, 0)", -59_777.145_851_187_8),
+            ("=PV(0.08/12, 12*20, 500, 0, 1)", -60_175.660_156_862_4),
+            ("=NPER(0.12/12, -100, -1000, 10000, 1)", 59.673_865_674_294_6),
+            ("=NPER(0.12/12, -100, -1000)", -9.578_594_039_813_1),
+            ("=NPER(0, -100, 1000)", 10.0),
+            ("=RATE(4*12, -200, 8000)", 0.007_701_472_488_201_37),
+            ("=RATE(4*12, -200, 8000) * 12", 0.092_417_669_858_416_4),
+            ("=RATE(10, -100, 1000)", 0.0),
+            ("=RATE(10, -100, 800, 0, 1, 0.2)", 0.053_446_167_393_037_9),
+            ("=PDURATION(0.025, 2000, 2200)", 3.859_866_162_622_66),
+            ("=RRI(96, 10000, 11000)", 0.000_993_307_376_291_33),
+            ("=EFFECT(0.0525, 4)", 0.053_542_667_370_758_4),
+            ("=EFFECT(0.0525, 4.9)", 0.053_542_667_370_758_4),
+            ("=NOMINAL(0.053543, 4)", 0.052_500_319_868_356),
+            ("=NPV(0.1, -10000, 3000, 4200, 6800)", 1_188.443_412_335_221_6),
+            ("=NPV(0.08, {8000, 9200, 10000, 12000, 14500}) - 40000", 1_922.061_554_932_363),
+            ("=NPV(0.1, {1000, 'a', TRUE})", 909.090_909_090_909_1),
+            (
+                "=XNPV(0.09, {-10000, 2750, 4250, 3250, 2750}, {DATE(2008, 1, 1), DATE(2008, 3, 1), DATE(2008, 10, 30), DATE(2009, 2, 15), DATE(2009, 4, 1)})",
+                2_086.647_602_031_535,
+            ),
+            (
+                "=XNPV(0.09, {-10000, 2750, 4250, 3250, 2750}, {DATE(2008, 1, 1), DATE(2008, 1, 1) + 60, DATE(2008, 10, 30), 39859, 39904})",
+                2_086.647_602_031_535,
+            ),
+            ("=IRR({-70000, 12000, 15000, 18000, 21000})", -0.021_244_848_273_410_947),
+            ("=IRR({-70000, 12000, 15000, 18000, 21000, 26000})", 0.086_630_948_036_531_6),
+            ("=IRR({-70000, 12000, 15000, 18000, 21000, 26000}, 50)", 0.086_630_948_036_531_6),
+            ("=IRR({-70000, 12000, 15000}, -0.1)", -0.443_506_941_334_740_5),
+            (
+                "=XIRR({-10000, 2750, 4250, 3250, 2750}, {DATE(2008, 1, 1), DATE(2008, 3, 1), DATE(2008, 10, 30), DATE(2009, 2, 15), DATE(2009, 4, 1)})",
+                0.373_362_533_518_831_36,
+            ),
+            (
+                "=XIRR({-10000, 2750, 4250, 3250, 2750}, {39448, 39508, 39751, 39859, 39904})",
+                0.373_362_533_518_831_36,
+            ),
+            ("=XIRR({-100, 110}, {'1/1/2023', '1/1/2024'})", 0.1),
+            ("=MIRR({-120000, 39000, 30000, 21000, 37000, 46000}, 0.1, 0.12)", 0.126_094_130_365_905_1),
+            ("=MIRR({-120000, 39000, 30000, 21000}, 0.1, 0.12)", -0.048_044_655_249_980_806),
+            ("=MIRR({-120000, 39000, 30000, 21000, 37000, 46000}, 0.1, 0.14)", 0.134_759_110_828_314_82),
+            ("=SLN(30000, 7500, 10)", 2_250.0),
+            ("=SYD(30000, 7500, 10, 1)", 4_090.909_090_909_091),
+            ("=SYD(30000, 7500, 10, 10)", 409.090_909_090_909_07),
+            ("=DB(1000000, 100000, 6, 1, 7)", 186_083.333_333_333_34),
+            ("=DB(1000000, 100000, 6, 2, 7)", 259_639.416_666_666_66),
+            ("=DB(1000000, 100000, 6, 3, 7)", 176_814.442_750_000_02),
+            ("=DB(1000000, 100000, 6, 4, 7)", 120_410.635_512_749_98),
+            ("=DB(1000000, 100000, 6, 5, 7)", 81_999.642_784_182_74),
+            ("=DB(1000000, 100000, 6, 6, 7)", 55_841.756_736_028_46),
+            ("=DB(1000000, 100000, 6, 7, 7)", 15_845.098_473_848_071),
+            ("=DB(1000000, 100000, 6, 6)", 46_722.518_280_620_934),
+            ("=DDB(2400, 300, 10*365, 1)", 1.315_068_493_150_647_6),
+            ("=DDB(2400, 300, 10*12, 1, 2)", 40.0),
+            ("=DDB(2400, 300, 10, 1, 2)", 480.0),
+            ("=DDB(2400, 300, 10, 2, 1.5)", 306.0),
+            ("=DDB(2400, 300, 10, 10)", 22.122_547_200_000_156),
+            ("=VDB(2400, 300, 10*365, 0, 1)", 1.315_068_493_150_647_6),
+            ("=VDB(2400, 300, 10*12, 0, 1)", 40.0),
+            ("=VDB(2400, 300, 10, 0, 1)", 480.0),
+            ("=VDB(2400, 300, 10*12, 6, 18)", 396.306_053_264_751_9),
+            ("=VDB(2400, 300, 10*12, 6, 18, 1.5)", 311.808_936_658_233_05),
+            ("=VDB(2400, 300, 10, 0, 0.875, 1.5)", 315.0),
+            ("=VDB(2400, 300, 10, 0, 10)", 2_100.0),
+            ("=VDB(2400, 300, 10, 1.5, 3.5, 2, TRUE)", 622.08),
+            ("=AMORDEGRC(2400, DATE(2008, 8, 19), DATE(2008, 12, 31), 300, 1, 0.15, 1)", 776.0),
+            ("=AMORDEGRC(2400, DATE(2008, 8, 19), DATE(2008, 12, 31), 300, 0, 0.15, 1)", 330.0),
+            ("=AMORLINC(2400, DATE(2008, 8, 19), DATE(2008, 12, 31), 300, 1, 0.15, 1)", 360.0),
+            ("=AMORLINC(2400, DATE(2008, 8, 19), DATE(2008, 12, 31), 300, 0, 0.15, 1)", 131.803_278_688_524_6),
+            ("=AMORLINC(2400, DATE(2008, 8, 19), DATE(2008, 12, 31), 300, 6, 0.15, 1)", 168.196_721_311_475_4),
+            ("=AMORLINC(2400, DATE(2008, 8, 19), DATE(2008, 12, 31), 300, 7, 0.15, 1)", 0.0),
+            ("=COUPDAYBS(DATE(2011, 1, 25), DATE(2011, 11, 15), 2, 1)", 71.0),
+            ("=COUPDAYBS(DATE(2011, 1, 25), DATE(2011, 11, 15), 2)", 70.0),
+            ("=COUPDAYS(DATE(2011, 1, 25), DATE(2011, 11, 15), 2, 1)", 181.0),
+            ("=COUPDAYS(DATE(2011, 1, 25), DATE(2011, 11, 15), 2, 3)", 182.5),
+            ("=COUPDAYSNC(DATE(2011, 1, 25), DATE(2011, 11, 15), 2, 1)", 110.0),
+            ("=COUPDAYSNC(DATE(2011, 1, 25), DATE(2011, 11, 15), 2)", 110.0),
+            ("=COUPNUM(DATE(2007, 1, 25), DATE(2008, 11, 15), 2, 1)", 4.0),
+            ("=COUPNUM(DATE(2007, 1, 25), DATE(2008, 11, 15), 4)", 8.0),
+            ("=PRICE(DATE(2008, 2, 15), DATE(2017, 11, 15), 0.0575, 0.065, 100, 2, 0)", 94.634_361_621_322_13),
+            (
+                "=PRICE(DATE(2008, 2, 15) + 0, DATE(2017, 11, 15), 0.0575, 0.065, 100, 2, 0)",
+                94.634_361_621_322_13,
+            ),
+            ("=YIELD(DATE(2008, 2, 15), DATE(2016, 11, 15), 0.0575, 95.04287, 100, 2, 0)", 0.065_000_006_880_754_79),
+            (
+                "=YIELD(DATE(2008, 2, 15), DATE(2008, 11, 15), 0.0575, 99.5, 100, 1, 1)",
+                (1.0575 - (0.995 + 92.0 / 366.0 * 0.0575)) / (0.995 + 92.0 / 366.0 * 0.0575) * 366.0 / 274.0,
+            ),
+            ("=DURATION(DATE(2018, 7, 1), DATE(2048, 1, 1), 0.08, 0.09, 2, 1)", 10.919_145_281_591_925),
+            ("=MDURATION(DATE(2008, 1, 1), DATE(2016, 1, 1), 0.08, 0.09, 2, 1)", 5.735_669_813_918_838),
+            ("=ACCRINT(DATE(2008, 3, 1), DATE(2008, 8, 31), DATE(2008, 5, 1), 0.1, 1000, 2, 0)", 16.666_666_666_666_668),
+            (
+                "=ACCRINT(DATE(2008, 3, 5), DATE(2008, 8, 31), DATE(2008, 5, 1), 0.1, 1000, 2, 0, FALSE)",
+                15.555_555_555_555_555,
+            ),
+            ("=ACCRINT(DATE(2008, 3, 15), DATE(2008, 9, 15), DATE(2009, 3, 15), 0.1, 1000, 2, 0)", 100.0),
+            ("=ACCRINT(DATE(2008, 3, 15), DATE(2008, 9, 15), DATE(2009, 3, 15), 0.1, 1000, 2, 0, FALSE)", 50.0),
+            ("=ACCRINTM(DATE(2008, 4, 1), DATE(2008, 6, 15), 0.1, 1000, 3)", 20.547_945_205_479_45),
+            ("=PRICEDISC(DATE(2008, 2, 16), DATE(2008, 3, 1), 0.0525, 100, 2)", 99.795_833_333_333_33),
+            ("=YIELDDISC(DATE(2008, 2, 16), DATE(2008, 3, 1), 99.795, 100, 2)", 0.052_822_571_986_860_085),
+            ("=DISC(DATE(2018, 1, 7), DATE(2048, 1, 1), 97.975, 100, 1)", 0.000_675_415_560_811_948_7),
+            ("=INTRATE(DATE(2008, 2, 15), DATE(2008, 5, 15), 1000000, 1014420, 2)", 0.057_680_000_000_000_4),
+            ("=RECEIVED(DATE(2008, 2, 15), DATE(2008, 5, 15), 1000000, 0.0575, 2)", 1_014_584.654_407_102_1),
+            ("=PRICEMAT(DATE(2008, 2, 15), DATE(2008, 4, 13), DATE(2007, 11, 11), 0.061, 0.061, 0)", 99.984_498_875_556_94),
+            ("=YIELDMAT(DATE(2008, 3, 15), DATE(2008, 11, 3), DATE(2007, 11, 8), 0.0625, 100.0123, 0)", 0.060_954_333_691_538_576),
+            (
+                "=ODDFPRICE(DATE(2008, 11, 11), DATE(2021, 3, 1), DATE(2008, 10, 15), DATE(2009, 3, 1), 0.0785, 0.0625, 100, 2, 1)",
+                113.597_717_474_078_83,
+            ),
+            (
+                "=ODDFPRICE(DATE(2008, 11, 11), DATE(2021, 3, 1), DATE(2008, 1, 15), DATE(2009, 3, 1), 0.0785, 0.0625, 100, 2)",
+                113.489_526_451_741,
+            ),
+            (
+                "=ODDFPRICE(DATE(2008, 11, 11), DATE(2009, 3, 1), DATE(2008, 10, 15), DATE(2009, 3, 1), 0.0785, 0.0625, 100, 2, 1)",
+                100.477_594_465_096_29,
+            ),
+            (
+                "=ODDFYIELD(DATE(2008, 11, 11), DATE(2021, 3, 1), DATE(2008, 10, 15), DATE(2009, 3, 1), 0.0575, 84.5, 100, 2, 0)",
+                0.077_245_541_597_817_48,
+            ),
+            (
+                "=ODDLPRICE(DATE(2008, 2, 7), DATE(2008, 6, 15), DATE(2007, 10, 15), 0.0375, 0.0405, 100, 2, 0)",
+                99.878_286_014_721_34,
+            ),
+            (
+                "=ODDLPRICE(DATE(2008, 2, 7), DATE(2008, 6, 15), DATE(2007, 10, 15), 0.0375, 0.0405, 100, 2, 1)",
+                99.879_167_681_529_11,
+            ),
+            (
+                "=ODDLYIELD(DATE(2008, 4, 20), DATE(2008, 6, 15), DATE(2007, 12, 24), 0.0375, 99.875, 100, 2, 0)",
+                0.045_192_235_629_169_16,
+            ),
+            ("=TBILLEQ(DATE(2008, 3, 31), DATE(2008, 6, 1), 0.0914)", 0.094_151_493_565_943_4),
+            ("=TBILLEQ(DATE(2008, 3, 31), DATE(2009, 1, 31), 0.0914)", 0.098_516_819_071_549_34),
+            ("=TBILLPRICE(DATE(2008, 3, 31), DATE(2008, 6, 1), 0.09)", 98.45),
+            ("=TBILLYIELD(DATE(2008, 3, 31), DATE(2008, 6, 1), 98.45)", 0.091_416_962_925_342_64),
+            ("=DOLLARDE(1.02, 16)", 1.125),
+            ("=DOLLARDE(1.1, 32)", 1.312_5),
+            ("=DOLLARDE(-1.1, 32)", -1.312_5),
+            ("=DOLLARDE(1.5, 10)", 1.5),
+            ("=DOLLARFR(1.125, 16)", 1.02),
+            ("=DOLLARFR(1.125, 32.9)", 1.04),
+        ];
+        for (formula, number) in numbers {
+            let formula = Formula::new(formula).unwrap();
+            assert_number(formula.parse().unwrap(), number);
+        }
+
+        let values = [
+            ("=IPMT(0.1, 4, 3, 8000)", Expr::Error(ErrorKind::Num)),
+            ("=PPMT(0.1, 0, 3, 8000)", Expr::Error(ErrorKind::Num)),
+            ("=CUMIPMT(0.09/12, 360, 125000, 13, 12, 0)", Expr::Error(ErrorKind::Num)),
+            ("=CUMIPMT(0.09/12, 360, 125000, 1, 1, 2)", Expr::Error(ErrorKind::Num)),
+            ("=CUMPRINC(0, 360, 125000, 1, 1, 0)", Expr::Error(ErrorKind::Num)),
+            ("=NPER(0.1, -50, 1000)", Expr::Error(ErrorKind::Num)),
+            ("=RATE(10, 100, 1000)", Expr::Error(ErrorKind::Num)),
+            ("=PDURATION(0, 2000, 2200)", Expr::Error(ErrorKind::Num)),
+            ("=EFFECT(0.05, 0)", Expr::Error(ErrorKind::Num)),
+            ("=NOMINAL(-0.05, 4)", Expr::Error(ErrorKind::Num)),
+            ("=PMT(0.1, 0, 1000)", Expr::Error(ErrorKind::Num)),
+            ("=PMT('a', 10, 1000)", Expr::Error(ErrorKind::Value)),
+            ("=PMT(#N/A, 10, 1000)", Expr::Error(ErrorKind::NA)),
+            ("=NPV(0.1, 100, 'a')", Expr::Error(ErrorKind::Value)),
+            (
+                "=XNPV(0.1, {-100, 110}, {DATE(2024, 1, 1)})",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=XNPV(0.1, {-100, 110}, {DATE(2024, 1, 1), DATE(2023, 1, 1)})",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=XNPV(0.1, {-100, 110}, {DATE(2024, 1, 1), 'a'})",
+                Expr::Error(ErrorKind::Value),
+            ),
+            ("=XNPV(0.1, {-100, 110}, {-1, 0})", Expr::Error(ErrorKind::Num)),
+            ("=IRR({100, 200, 300})", Expr::Error(ErrorKind::Num)),
+            ("=IRR({-100, -200})", Expr::Error(ErrorKind::Num)),
+            ("=IRR({-100, 100, #N/A})", Expr::Error(ErrorKind::NA)),
+            (
+                "=XIRR({100, 200}, {DATE(2024, 1, 1), DATE(2025, 1, 1)})",
+                Expr::Error(ErrorKind::Num),
+            ),
+            ("=MIRR({100, 200}, 0.1, 0.1)", Expr::Error(ErrorKind::DivZero)),
+            (
+                "=COUPNUM(DATE(2011, 11, 15), DATE(2011, 11, 15), 2)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=COUPDAYS(DATE(2011, 1, 25), DATE(2011, 11, 15), 3)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=COUPDAYS(DATE(2011, 1, 25), DATE(2011, 11, 15), 2, 5)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            ("=COUPDAYS('a', DATE(2011, 11, 15), 2)", Expr::Error(ErrorKind::Value)),
+            (
+                "=PRICE(DATE(2008, 2, 15), DATE(2017, 11, 15), -0.1, 0.065, 100, 2)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=YIELD(DATE(2008, 2, 15), DATE(2017, 11, 15), 0.0575, 0, 100, 2)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=ACCRINTM(DATE(2008, 6, 15), DATE(2008, 4, 1), 0.1, 1000)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=DISC(DATE(2018, 1, 7), DATE(2048, 1, 1), 0, 100)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            ("=SLN(30000, 7500, 0)", Expr::Error(ErrorKind::DivZero)),
+            ("=SYD(30000, 7500, 10, 11)", Expr::Error(ErrorKind::Num)),
+            ("=DB(1000000, 100000, 6, 7)", Expr::Error(ErrorKind::Num)),
+            ("=DB(1000000, 100000, 6, 1, 13)", Expr::Error(ErrorKind::Num)),
+            ("=DDB(2400, 300, 10, 11)", Expr::Error(ErrorKind::Num)),
+            ("=VDB(2400, 300, 10, 5, 4)", Expr::Error(ErrorKind::Num)),
+            (
+                "=AMORDEGRC(2400, DATE(2008, 8, 19), DATE(2008, 12, 31), 300, 1, 0.15, 5)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=AMORDEGRC(2400, DATE(2008, 8, 19), DATE(2008, 12, 31), 300, 1, 0.4)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=ODDFPRICE(DATE(2009, 3, 1), DATE(2021, 3, 1), DATE(2008, 10, 15), DATE(2009, 3, 1), 0.0785, 0.0625, 100, 2)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=ODDFYIELD(DATE(2008, 11, 11), DATE(2021, 3, 1), DATE(2008, 10, 15), DATE(2009, 3, 1), 0.0575, 84.5, 100, 3)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=ODDLPRICE(DATE(2007, 10, 15), DATE(2008, 6, 15), DATE(2007, 10, 15), 0.0375, 0.0405, 100, 2)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=ODDLYIELD(DATE(2008, 4, 20), DATE(2008, 6, 15), DATE(2007, 12, 24), 0.0375, 0, 100, 2)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            (
+                "=TBILLPRICE(DATE(2008, 3, 31), DATE(2009, 4, 1), 0.09)",
+                Expr::Error(ErrorKind::Num),
+            ),
+            ("=TBILLYIELD(DATE(2008, 3, 31), DATE(2008, 6, 1), 0)", Expr::Error(ErrorKind::Num)),
+            ("=DOLLARDE(1.02, -1)", Expr::Error(ErrorKind::Num)),
+            ("=DOLLARFR(1.02, 0.5)", Expr::Error(ErrorKind::DivZero)),
+        ];
+        for (formula, value) in values {
+            let formula = Formula::new(formula).unwrap();
+            assert_eq!(formula.parse().unwrap(), value, "{formula:?}");
+        }
+
+        let dates = [
+            ("=COUPNCD(DATE(2011, 1, 25), DATE(2011, 11, 15), 2, 1)", (2011, 5, 15)),
+            ("=COUPPCD(DATE(2011, 1, 25), DATE(2011, 11, 15), 2, 1)", (2010, 11, 15)),
+            ("=COUPPCD(DATE(2011, 5, 15), DATE(2011, 11, 15), 2)", (2011, 5, 15)),
+            ("=COUPNCD(DATE(2011, 1, 25), DATE(2011, 8, 31), 2)", (2011, 2, 28)),
+            ("=COUPPCD(DATE(2011, 3, 25), DATE(2011, 8, 31), 4)", (2011, 2, 28)),
+            ("=COUPNCD(DATE(2011, 3, 25), DATE(2011, 8, 31), 4)", (2011, 5, 31)),
+        ];
+        for (formula, (year, month, day)) in dates {
+            let formula = Formula::new(formula).unwrap();
+            let date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
+            assert_eq!(formula.parse().unwrap(), Expr::Date(date), "{formula:?}");
+        }
+    }
+}
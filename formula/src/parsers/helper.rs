@@ -1,12 +1,194 @@
-use crate::{error::Error, Expr, Formula, Result, Rule};
-use pest::iterators::Pairs;
+use crate::{ast::Node, error::Error, evaluator::Evaluator, ErrorKind, Expr, Result};
+use chrono::{Days, NaiveDate, NaiveTime};
+use std::slice::Iter;
 
-impl Formula<'_> {
-    pub(crate) fn get_formula(args: &mut Pairs<Rule>, rule_name: &str) -> Result<Expr> {
-        Self::parse_pair(args.next().ok_or_else(|| Error::Parser(rule_name.to_owned()))?)
+/// The serial number of the last date of Excel, 9999-12-31
+const MAX_SERIAL: f64 = 2_958_465.0;
+
+/// The date of the serial number 0 of Excel, so the serial numbers after 1900-02-28 are the same as Excel
+fn serial_epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1899, 12, 30).unwrap_or_default()
+}
+
+/// The significant digits of the numbers of Excel
+pub(crate) const SIGNIFICANT_DIGITS: usize = 15;
+
+/// Return a number rounded to the significant digits of Excel, which removes the errors of the binary fractions,
+/// like 2.675, which is 2.67499999999999982236431605997495353221893310546875
+pub(crate) fn to_decimal(number: f64) -> f64 {
+    if number == 0.0 || !number.is_finite() {
+        return number;
+    }
+    format!("{number:.*e}", SIGNIFICANT_DIGITS - 1)
+        .parse()
+        .unwrap_or(number)
+}
+
+impl Evaluator<'_> {
+    pub(crate) fn get_formula(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<Expr> {
+        self.parse_arg(args.next().ok_or_else(|| Error::Parser(rule_name.to_owned()))?)
+    }
+
+    /// Return an optional argument of a function, or the default if it's missing or omitted, like `XMATCH(2, A1:A3, , -1)`
+    pub(crate) fn get_opt_formula_with_default(&self, args: &mut Iter<Node>, default: Expr) -> Result<Expr> {
+        match args.next() {
+            Some(arg) if !arg.is_empty() => self.parse_arg(arg),
+            _ => Ok(default),
+        }
+    }
+
+    /// Return a number argument of a function. The booleans and the numeric texts are converted, and an empty value
+    /// is `0`, like Excel.
+    pub(crate) fn get_number(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<f64> {
+        let value = self.get_formula(args, rule_name)?;
+        Self::to_number(value, rule_name)
+    }
+
+    pub(crate) fn get_opt_number(&self, args: &mut Iter<Node>, rule_name: &str, default: f64) -> Result<f64> {
+        let value = self.get_opt_formula_with_default(args, Expr::Number(default))?;
+        Self::to_number(value, rule_name)
+    }
+
+    /// Convert a value to a number, like Excel. The booleans are `1` and `0`, the dates and the times are their serial
+    /// numbers, an empty value is `0`, and the texts which aren't numbers are `#VALUE!`.
+    pub(crate) fn to_number(value: Expr, rule_name: &str) -> Result<f64> {
+        match value {
+            Expr::Number(number) => Ok(number),
+            Expr::Bool(bool) => Ok(f64::from(u8::from(bool))),
+            Expr::String(text) => text.trim().parse().map_err(|_| Error::Excel(ErrorKind::Value)),
+            Expr::Null => Ok(0.0),
+            Expr::Error(kind) => Err(Error::Excel(kind)),
+            value => Self::to_serial(&value).ok_or_else(|| Error::Parser(rule_name.to_owned())),
+        }
+    }
+
+    /// Return the serial number of a date or a time, which is the number of days since 1899-12-30,
+    /// and the fraction of a day, like Excel
+    pub(crate) fn to_serial(value: &Expr) -> Option<f64> {
+        let epoch = serial_epoch();
+        let milliseconds = match value {
+            Expr::Date(date) => return Some((*date - epoch).num_days() as f64),
+            Expr::Datetime(datetime) => {
+                (datetime.naive_utc() - epoch.and_hms_opt(0, 0, 0).unwrap_or_default()).num_milliseconds()
+            }
+            Expr::Time(time) => (*time - NaiveTime::from_hms_opt(0, 0, 0).unwrap_or_default()).num_milliseconds(),
+            _ => return None,
+        };
+        Some(milliseconds as f64 / 86_400_000.0)
+    }
+
+    /// Return the date of a serial number, where the time is ignored, or `None` if it's not a date of Excel,
+    /// which is from 0 to 2958465, the serial number of 9999-12-31
+    pub(crate) fn from_serial(serial: f64) -> Option<NaiveDate> {
+        if !(0.0..MAX_SERIAL + 1.0).contains(&serial) {
+            return None;
+        }
+        serial_epoch().checked_add_days(Days::new(serial as u64))
+    }
+
+    /// Evaluate an argument of a function, and propagate it as `Error::Excel` if it's an error value
+    pub(crate) fn parse_arg(&self, arg: &Node) -> Result<Expr> {
+        match self.parse_node(arg)? {
+            Expr::Error(kind) => Err(Error::Excel(kind)),
+            value => Ok(value),
+        }
+    }
+
+    /// Evaluate an argument of a function, like `parse_node`, but return the errors as error values,
+    /// for the functions that handle the errors, like `IFERROR`.
+    /// So the invalid arguments are `#VALUE!` and the unknown names are `#NAME?`, like Excel.
+    pub(crate) fn parse_arg_or_error(&self, arg: &Node) -> Result<Expr> {
+        match self.parse_node(arg) {
+            Err(Error::Parser(_)) => Ok(Expr::Error(ErrorKind::Value)),
+            Err(Error::UnknownName(_)) => Ok(Expr::Error(ErrorKind::Name)),
+            Err(Error::Excel(kind)) => Ok(Expr::Error(kind)),
+            value => value,
+        }
+    }
+
+    /// Return the items of an array and its nested arrays, like the rows of a range.
+    /// The error values are kept, so the functions can propagate them.
+    pub(crate) fn flatten(array: Vec<Expr>) -> Vec<Expr> {
+        array
+            .into_iter()
+            .flat_map(|item| match item {
+                Expr::Array(array) => Self::flatten(array),
+                item => vec![item],
+            })
+            .collect()
+    }
+
+    /// Return the rows of a table, which is an array of rows, a single row or a single value
+    pub(crate) fn to_rows(value: Expr) -> Vec<Vec<Expr>> {
+        match value {
+            Expr::Array(items) if !items.is_empty() && items.iter().all(|item| matches!(item, Expr::Array(_))) => items
+                .into_iter()
+                .map(|row| match row {
+                    Expr::Array(row) => row,
+                    row => vec![row],
+                })
+                .collect(),
+            Expr::Array(items) => vec![items],
+            value => vec![vec![value]],
+        }
+    }
+
+    /// Return the numbers of a table, which must have only numbers, like the arguments of `LINEST`.
+    /// The error values are propagated, and the other values are `#VALUE!`.
+    pub(crate) fn to_number_table(value: Expr) -> Result<Vec<Vec<f64>>> {
+        let rows = Self::to_rows(value);
+        if rows.iter().any(|row| row.is_empty() || row.len() != rows[0].len()) {
+            return Err(Error::Excel(ErrorKind::Value));
+        }
+        rows.into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|item| match item {
+                        Expr::Number(number) => Ok(number),
+                        Expr::Error(kind) => Err(Error::Excel(kind)),
+                        _ => Err(Error::Excel(ErrorKind::Value)),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Return the columns of a table as its rows
+    pub(crate) fn transpose(rows: Vec<Vec<Expr>>) -> Vec<Vec<Expr>> {
+        let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+        let mut columns = vec![Vec::with_capacity(rows.len()); width];
+        for row in rows {
+            for (column, item) in columns
+                .iter_mut()
+                .zip(row.into_iter().chain(std::iter::repeat(Expr::Null)))
+            {
+                column.push(item);
+            }
+        }
+        columns
     }
 
-    pub(crate) fn get_opt_formula_with_default(args: &mut Pairs<Rule>, default: Expr) -> Result<Expr> {
-        args.next().map_or(Ok(default), Self::parse_pair)
+    /// Return the numbers of the arguments, including the numbers in the arrays, like `SUM` does.
+    /// The empty values and the other types in the arrays are ignored, but the error values are propagated, like Excel.
+    pub(crate) fn get_numbers(args: impl IntoIterator<Item = Expr>, rule_name: &str) -> Result<Vec<f64>> {
+        let mut numbers = Vec::new();
+        for arg in args {
+            match arg {
+                Expr::Number(number) => numbers.push(number),
+                Expr::Array(array) => {
+                    for item in Self::flatten(array) {
+                        match item {
+                            Expr::Number(number) => numbers.push(number),
+                            Expr::Error(kind) => return Err(Error::Excel(kind)),
+                            _ => {}
+                        }
+                    }
+                }
+                Expr::Error(kind) => return Err(Error::Excel(kind)),
+                Expr::Null => {}
+                _ => return Err(Error::Parser(rule_name.to_owned())),
+            }
+        }
+        Ok(numbers)
     }
 }
@@ -0,0 +1,94 @@
+use crate::{ast::Node, error::Error, evaluator::Evaluator, ErrorKind, Expr, Result, Rule};
+
+impl Evaluator<'_> {
+    pub(crate) fn parse_error_type(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let value = args.first().ok_or(Error::Parser(rule_name))?;
+
+        let res = match self.parse_arg_or_error(value)? {
+            Expr::Error(kind) => Expr::Number(f64::from(kind.code())),
+            _ => Expr::Error(ErrorKind::NA),
+        };
+        Ok(res)
+    }
+
+    pub(crate) fn parse_iserror(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let value = args.first().ok_or(Error::Parser(rule_name))?;
+
+        let res = matches!(self.parse_arg_or_error(value)?, Expr::Error(_));
+        Ok(Expr::Bool(res))
+    }
+
+    pub(crate) fn parse_isna(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let value = args.first().ok_or(Error::Parser(rule_name))?;
+
+        let res = matches!(self.parse_arg_or_error(value)?, Expr::Error(ErrorKind::NA));
+        Ok(Expr::Bool(res))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Formula;
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_parse_information_types() {
+        let formula = Formula::new("=#N/A").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Error(ErrorKind::NA));
+
+        let formula = Formula::new("={1, #div/0!; #VALUE!, 2}").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(
+            value,
+            Expr::Array(vec![
+                Expr::Array(vec![Expr::Number(1.0), Expr::Error(ErrorKind::DivZero)]),
+                Expr::Array(vec![Expr::Error(ErrorKind::Value), Expr::Number(2.0)]),
+            ])
+        );
+
+        assert!(Formula::new("=#UNKNOWN!").is_err());
+
+        let errors = [
+            ("=1+1/0", Expr::Error(ErrorKind::DivZero)),
+            ("=#N/A+1/0", Expr::Error(ErrorKind::NA)),
+            ("=-#NULL!", Expr::Error(ErrorKind::Null)),
+            ("=UPPER(#N/A)", Expr::Error(ErrorKind::NA)),
+            ("=CONCAT('a', 'b', #REF!)", Expr::Error(ErrorKind::Ref)),
+            ("=SUM(1, {2, #NUM!})", Expr::Error(ErrorKind::Num)),
+            ("=SQRT(-1)", Expr::Error(ErrorKind::Num)),
+            ("=LN(0)", Expr::Error(ErrorKind::Num)),
+            ("=MOD(1, 0)", Expr::Error(ErrorKind::DivZero)),
+            ("=ISERROR(1/0)", Expr::Bool(true)),
+            ("=ISERROR(NOT('a'))", Expr::Bool(true)),
+            ("=ISERROR(unknown)", Expr::Bool(true)),
+            ("=ISERROR(1)", Expr::Bool(false)),
+            ("=ISERROR(A1)", Expr::Bool(false)),
+            ("=ISNA(#N/A)", Expr::Bool(true)),
+            ("=ISNA(#VALUE!)", Expr::Bool(false)),
+            ("=ISNA(1)", Expr::Bool(false)),
+            ("=ERROR.TYPE(#NULL!)", Expr::Number(1.0)),
+            ("=ERROR.TYPE(1/0)", Expr::Number(2.0)),
+            ("=ERROR.TYPE(NOT('a'))", Expr::Number(3.0)),
+            ("=ERROR.TYPE(#REF!)", Expr::Number(4.0)),
+            ("=ERROR.TYPE(unknown)", Expr::Number(5.0)),
+            ("=ERROR.TYPE(SQRT(-1))", Expr::Number(6.0)),
+            ("=ERROR.TYPE(#N/A)", Expr::Number(7.0)),
+            ("=ERROR.TYPE(#GETTING_DATA)", Expr::Number(8.0)),
+            ("=ERROR.TYPE(#SPILL!)", Expr::Number(9.0)),
+            ("=ERROR.TYPE(#CALC!)", Expr::Number(14.0)),
+            ("=ERROR.TYPE(1)", Expr::Error(ErrorKind::NA)),
+        ];
+        for (formula, value) in errors {
+            let formula = Formula::new(formula).unwrap();
+            assert_eq!(formula.parse().unwrap(), value, "{formula:?}");
+        }
+
+        assert_eq!(ErrorKind::DivZero.to_string(), "#DIV/0!");
+        assert_eq!(ErrorKind::NA.to_string(), "#N/A");
+    }
+}
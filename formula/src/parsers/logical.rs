@@ -1,12 +1,11 @@
-use crate::{error::Error, Expr, Formula, Result, Rule};
-use pest::iterators::Pair;
+use crate::{ast::Node, error::Error, evaluator::Evaluator, ErrorKind, Expr, Result, Rule};
 
-impl Formula<'_> {
-    pub(crate) fn parse_and(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let bool = Self::get_formula(&mut args, &rule_name)?;
-        let bools = args.map(Self::parse_pair).collect::<Result<Vec<_>>>()?;
+impl Evaluator<'_> {
+    pub(crate) fn parse_and(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let bool = self.get_formula(&mut args, &rule_name)?;
+        let bools = args.map(|arg| self.parse_arg(arg)).collect::<Result<Vec<_>>>()?;
 
         let bool = match bool {
             Expr::Bool(mut bool) => {
@@ -23,11 +22,11 @@ impl Formula<'_> {
         Ok(Expr::Bool(bool))
     }
 
-    pub(crate) fn parse_or(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let bool = Self::get_formula(&mut args, &rule_name)?;
-        let bools = args.map(Self::parse_pair).collect::<Result<Vec<_>>>()?;
+    pub(crate) fn parse_or(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let bool = self.get_formula(&mut args, &rule_name)?;
+        let bools = args.map(|arg| self.parse_arg(arg)).collect::<Result<Vec<_>>>()?;
 
         let bool = match bool {
             Expr::Bool(mut bool) => {
@@ -44,11 +43,11 @@ impl Formula<'_> {
         Ok(Expr::Bool(bool))
     }
 
-    pub(crate) fn parse_xor(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let bool = Self::get_formula(&mut args, &rule_name)?;
-        let bools = args.map(Self::parse_pair).collect::<Result<Vec<_>>>()?;
+    pub(crate) fn parse_xor(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let bool = self.get_formula(&mut args, &rule_name)?;
+        let bools = args.map(|arg| self.parse_arg(arg)).collect::<Result<Vec<_>>>()?;
 
         let bool = match bool {
             Expr::Bool(mut bool) => {
@@ -66,10 +65,10 @@ impl Formula<'_> {
         Ok(Expr::Bool(bool))
     }
 
-    pub(crate) fn parse_not(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let bool = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_not(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let bool = self.get_formula(&mut args, &rule_name)?;
 
         let bool = match bool {
             Expr::Bool(b) => !b,
@@ -78,40 +77,40 @@ impl Formula<'_> {
         Ok(Expr::Bool(bool))
     }
 
-    pub(crate) fn parse_iferror(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
+    pub(crate) fn parse_iferror(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
         let value = args.next();
         let error_value = args.next().ok_or_else(|| Error::Parser(rule_name.clone()))?;
 
-        let value = value.unwrap_or_else(|| error_value.clone());
-        let value = Self::parse_pair(value)
-            .or_else(|_| Self::parse_pair(error_value))
-            .map_err(|_| Error::Parser(rule_name))?;
+        let value = value.unwrap_or(error_value);
+        let value = match self.parse_arg_or_error(value)? {
+            Expr::Error(_) => self.parse_node(error_value).map_err(|_| Error::Parser(rule_name))?,
+            value => value,
+        };
 
         Ok(value)
     }
 
-    pub(crate) fn parse_ifna(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let value = Self::get_formula(&mut args, &rule_name)?;
-        let na_value = Self::get_formula(&mut args, &rule_name)?;
-
-        let value = match value {
-            Expr::Null => na_value,
-            _ => return Err(Error::Parser(rule_name)),
-        };
+    pub(crate) fn parse_ifna(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let value = args.next().ok_or_else(|| Error::Parser(rule_name.clone()))?;
+        let na_value = args.next().ok_or(Error::Parser(rule_name))?;
 
-        Ok(value)
+        match self.parse_node(value)? {
+            Expr::Error(ErrorKind::NA) => self.parse_node(na_value),
+            value => Ok(value),
+        }
     }
 
-    pub(crate) fn parse_if(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let value = Self::get_formula(&mut args, &rule_name)?;
-        let true_value = Self::get_opt_formula_with_default(&mut args, Expr::Number(0.0))?;
-        let false_value = Self::get_opt_formula_with_default(&mut args, Expr::Number(0.0))?;
+    /// Only the selected value is evaluated, so the errors of the other value are ignored, like Excel
+    pub(crate) fn parse_if(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let value = self.get_formula(&mut args, &rule_name)?;
+        let true_value = args.next();
+        let false_value = args.next();
 
         let value = match value {
             Expr::Bool(b) => {
@@ -124,7 +123,7 @@ impl Formula<'_> {
             _ => return Err(Error::Parser(rule_name)),
         };
 
-        Ok(value)
+        value.map_or(Ok(Expr::Number(0.0)), |value| self.parse_node(value))
     }
 }
 
@@ -164,14 +163,37 @@ mod tests {
         let value = formula.parse().unwrap();
         assert_eq!(value, Expr::Bool(true));
 
-        // let formula = Formula::new("=IFNA(x, 'not found')").unwrap();
-        // let value = formula.parse().unwrap();
-        // assert_eq!(value, Expr::String("not found".to_string()));
+        let formula = Formula::new("=IFNA(#N/A, 'not found')").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::String("not found".to_string()));
 
         let formula = Formula::new("=IFERROR(NOT('a'), 'err')").unwrap();
         let value = formula.parse().unwrap();
         assert_eq!(value, Expr::String("err".to_string()));
 
+        let errors = [
+            ("=IFNA(1, 'not found')", Expr::Number(1.0)),
+            ("=IFNA(A1, 'not found')", Expr::Null),
+            ("=IFNA(1/0, 'not found')", Expr::Error(ErrorKind::DivZero)),
+            (
+                "=IFNA(SUM({1, #N/A}), 'not found')",
+                Expr::String("not found".to_string()),
+            ),
+            ("=IFERROR(1/0, 'err')", Expr::String("err".to_string())),
+            ("=IFERROR(#N/A, #NUM!)", Expr::Error(ErrorKind::Num)),
+            ("=IFERROR(SQRT(-1) + 1, 'err')", Expr::String("err".to_string())),
+            ("=IFERROR(unknown, 'err')", Expr::String("err".to_string())),
+            ("=IFERROR(1, 'err')", Expr::Number(1.0)),
+            ("=IF(TRUE, 1, 1/0)", Expr::Number(1.0)),
+            ("=IF(FALSE, 1, 1/0)", Expr::Error(ErrorKind::DivZero)),
+            ("=IF(#REF!, 1, 2)", Expr::Error(ErrorKind::Ref)),
+            ("=AND(TRUE, #N/A)", Expr::Error(ErrorKind::NA)),
+        ];
+        for (formula, value) in errors {
+            let formula = Formula::new(formula).unwrap();
+            assert_eq!(formula.parse().unwrap(), value);
+        }
+
         let formula = Formula::new("=IF(TRUE, 'true', 'false')").unwrap();
         let value = formula.parse().unwrap();
         assert_eq!(value, Expr::String("true".to_string()));
@@ -0,0 +1,558 @@
+use crate::{
+    ast::Node, error::Error, evaluator::Evaluator, parsers::criteria::wildcard_match, ErrorKind, Expr, Result, Rule,
+};
+use std::cmp::Ordering;
+
+/// How a lookup value is matched, like the `match_mode` of `XLOOKUP`
+#[derive(Clone, Copy, PartialEq)]
+enum MatchMode {
+    Exact,
+    /// The exact value, or the next smaller value
+    ExactOrSmaller,
+    /// The exact value, or the next larger value
+    ExactOrLarger,
+    /// The exact value, where the texts can have the `*`, `?` and `~` wildcards
+    Wildcard,
+}
+
+/// How the lookup values are searched, like the `search_mode` of `XLOOKUP`
+#[derive(Clone, Copy, PartialEq)]
+enum SearchMode {
+    FirstToLast,
+    LastToFirst,
+    /// A binary search of the values, which are sorted in ascending order
+    BinaryAscending,
+    /// A binary search of the values, which are sorted in descending order
+    BinaryDescending,
+}
+
+impl MatchMode {
+    fn new(mode: f64) -> Result<Self> {
+        let mode = match mode as i64 {
+            0 => MatchMode::Exact,
+            -1 => MatchMode::ExactOrSmaller,
+            1 => MatchMode::ExactOrLarger,
+            2 => MatchMode::Wildcard,
+            _ => return Err(Error::Excel(ErrorKind::Value)),
+        };
+        Ok(mode)
+    }
+}
+
+impl SearchMode {
+    fn new(mode: f64) -> Result<Self> {
+        let mode = match mode as i64 {
+            1 => SearchMode::FirstToLast,
+            -1 => SearchMode::LastToFirst,
+            2 => SearchMode::BinaryAscending,
+            -2 => SearchMode::BinaryDescending,
+            _ => return Err(Error::Excel(ErrorKind::Value)),
+        };
+        Ok(mode)
+    }
+}
+
+impl Evaluator<'_> {
+    /// Compare two values of a lookup, like Excel, where the numbers are smaller than the texts,
+    /// and the texts are smaller than the booleans. The texts are compared case-insensitively.
+    /// The other values, like the empty values or the errors, are not comparable.
+    fn compare_lookup(value1: &Expr, value2: &Expr) -> Option<Ordering> {
+        let rank = |value: &Expr| match value {
+            Expr::Number(_) => Some(0),
+            Expr::String(_) => Some(1),
+            Expr::Bool(_) => Some(2),
+            _ => None,
+        };
+        match (value1, value2) {
+            (Expr::Number(value1), Expr::Number(value2)) => value1.partial_cmp(value2),
+            (Expr::String(value1), Expr::String(value2)) => Some(value1.to_lowercase().cmp(&value2.to_lowercase())),
+            (Expr::Bool(value1), Expr::Bool(value2)) => Some(value1.cmp(value2)),
+            _ => Some(rank(value1)?.cmp(&rank(value2)?)),
+        }
+    }
+
+    fn matches_lookup(lookup: &Expr, value: &Expr, match_mode: MatchMode) -> bool {
+        match (lookup, value) {
+            (Expr::String(lookup), Expr::String(value)) if match_mode == MatchMode::Wildcard => {
+                wildcard_match(&lookup.to_lowercase(), &value.to_lowercase())
+            }
+            _ => Self::compare_lookup(value, lookup) == Some(Ordering::Equal),
+        }
+    }
+
+    /// Return the position of the lookup value in the values, or `None` if it's not found
+    fn find(lookup: &Expr, values: &[Expr], match_mode: MatchMode, search_mode: SearchMode) -> Option<usize> {
+        match search_mode {
+            SearchMode::FirstToLast | SearchMode::LastToFirst => {
+                let mut positions: Box<dyn Iterator<Item = usize>> = if search_mode == SearchMode::FirstToLast {
+                    Box::new(0..values.len())
+                } else {
+                    Box::new((0..values.len()).rev())
+                };
+                if let Some(position) = positions.find(|i| Self::matches_lookup(lookup, &values[*i], match_mode)) {
+                    return Some(position);
+                }
+
+                // The nearest value is the largest smaller value, or the smallest larger value
+                let wanted = match match_mode {
+                    MatchMode::ExactOrSmaller => Ordering::Less,
+                    MatchMode::ExactOrLarger => Ordering::Greater,
+                    _ => return None,
+                };
+                let mut nearest: Option<usize> = None;
+                let positions: Box<dyn Iterator<Item = usize>> = if search_mode == SearchMode::FirstToLast {
+                    Box::new(0..values.len())
+                } else {
+                    Box::new((0..values.len()).rev())
+                };
+                for i in positions {
+                    if Self::compare_lookup(&values[i], lookup) != Some(wanted) {
+                        continue;
+                    }
+                    let closer = nearest.map_or(true, |nearest| {
+                        Self::compare_lookup(&values[i], &values[nearest]) == Some(wanted.reverse())
+                    });
+                    if closer {
+                        nearest = Some(i);
+                    }
+                }
+                nearest
+            }
+            SearchMode::BinaryAscending | SearchMode::BinaryDescending => {
+                let descending = search_mode == SearchMode::BinaryDescending;
+                // The number of the values which are before the lookup value in the sort order
+                let before = |value: &Expr, inclusive: bool| {
+                    let ordering = Self::compare_lookup(value, lookup).unwrap_or(Ordering::Greater);
+                    let ordering = if descending { ordering.reverse() } else { ordering };
+                    ordering.is_lt() || (inclusive && ordering.is_eq())
+                };
+                let smaller = if descending {
+                    match_mode == MatchMode::ExactOrLarger
+                } else {
+                    match_mode == MatchMode::ExactOrSmaller
+                };
+                let position = if smaller {
+                    // The last value which is equal or before the lookup value
+                    values.partition_point(|value| before(value, true)).checked_sub(1)?
+                } else {
+                    // The first value which is equal or after the lookup value
+                    values.partition_point(|value| before(value, false))
+                };
+                let value = values.get(position)?;
+                let ordering = Self::compare_lookup(value, lookup)?;
+                let found = match match_mode {
+                    MatchMode::Exact | MatchMode::Wildcard => ordering.is_eq(),
+                    // The nearest value should have the same type of the lookup value
+                    _ => ordering.is_eq() || std::mem::discriminant(value) == std::mem::discriminant(lookup),
+                };
+                found.then_some(position)
+            }
+        }
+    }
+
+    /// Return the position of a lookup value in the first column of a table, like `VLOOKUP`
+    fn lookup_in_rows(&self, rule: Rule, args: &[Node], to_rows: fn(Vec<Vec<Expr>>) -> Vec<Vec<Expr>>) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let lookup = self.get_formula(&mut args, &rule_name)?;
+        let table = to_rows(Self::to_rows(self.get_formula(&mut args, &rule_name)?));
+        let index = self.get_opt_number(&mut args, &rule_name, 1.0)?.trunc();
+        let approximate = self.get_opt_number(&mut args, &rule_name, 1.0)? != 0.0;
+
+        let width = table.iter().map(Vec::len).max().unwrap_or_default();
+        if index < 1.0 {
+            return Err(Error::Excel(ErrorKind::Value));
+        }
+        if index > width as f64 {
+            return Err(Error::Excel(ErrorKind::Ref));
+        }
+
+        let keys = table
+            .iter()
+            .map(|row| row.first().cloned().unwrap_or(Expr::Null))
+            .collect::<Vec<_>>();
+        let position = if approximate {
+            Self::find(&lookup, &keys, MatchMode::ExactOrSmaller, SearchMode::BinaryAscending)
+        } else {
+            Self::find(&lookup, &keys, MatchMode::Wildcard, SearchMode::FirstToLast)
+        };
+        let position = position.ok_or(Error::Excel(ErrorKind::NA))?;
+        Ok(table[position].get(index as usize - 1).cloned().unwrap_or(Expr::Null))
+    }
+
+    pub(crate) fn parse_vlookup(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.lookup_in_rows(rule, args, |rows| rows)
+    }
+
+    pub(crate) fn parse_hlookup(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.lookup_in_rows(rule, args, Self::transpose)
+    }
+
+    /// Without a result vector, the first row or column of the table is searched, and the value of its last
+    /// row or column is returned, by the larger dimension of the table, like Excel
+    pub(crate) fn parse_lookup(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let lookup = self.get_formula(&mut args, &rule_name)?;
+        let lookup_vector = self.get_formula(&mut args, &rule_name)?;
+
+        let (keys, results) = match args.next() {
+            Some(results) => (
+                Self::get_range(lookup_vector),
+                Self::get_range(self.parse_arg(results)?),
+            ),
+            None => {
+                let rows = Self::to_rows(lookup_vector);
+                let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+                let rows = if width > rows.len() {
+                    Self::transpose(rows)
+                } else {
+                    rows
+                };
+                let keys = rows
+                    .iter()
+                    .map(|row| row.first().cloned().unwrap_or(Expr::Null))
+                    .collect();
+                let results = rows
+                    .iter()
+                    .map(|row| row.last().cloned().unwrap_or(Expr::Null))
+                    .collect();
+                (keys, results)
+            }
+        };
+
+        let position = Self::find(&lookup, &keys, MatchMode::ExactOrSmaller, SearchMode::BinaryAscending)
+            .ok_or(Error::Excel(ErrorKind::NA))?;
+        results.get(position).cloned().ok_or(Error::Excel(ErrorKind::NA))
+    }
+
+    /// A row or a column number of `0` returns the whole column or row, like Excel
+    pub(crate) fn parse_index(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let has_column = args.len() > 2;
+        let mut args = args.iter();
+        let rows = Self::to_rows(self.get_formula(&mut args, &rule_name)?);
+        let row = self.get_opt_number(&mut args, &rule_name, 0.0)?.trunc();
+        let column = self.get_opt_number(&mut args, &rule_name, 0.0)?.trunc();
+
+        // The index of a single row is its column, and the index of a single column is its row
+        let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+        let (row, column) = match (rows.len(), width) {
+            (1, _) if !has_column => (1.0, row),
+            (_, 1) if !has_column => (row, 1.0),
+            _ => (row, column),
+        };
+        if row < 0.0 || column < 0.0 {
+            return Err(Error::Excel(ErrorKind::Value));
+        }
+        if row > rows.len() as f64 || column > width as f64 {
+            return Err(Error::Excel(ErrorKind::Ref));
+        }
+
+        let (row, column) = (row as usize, column as usize);
+        let value = match (row, column) {
+            (0, 0) => Expr::from_rows(rows),
+            (0, column) => Expr::from_rows(
+                rows.into_iter()
+                    .map(|row| vec![row.get(column - 1).cloned().unwrap_or(Expr::Null)])
+                    .collect(),
+            ),
+            (row, 0) => Expr::Array(rows[row - 1].clone()),
+            (row, column) => rows[row - 1].get(column - 1).cloned().unwrap_or(Expr::Null),
+        };
+        Ok(value)
+    }
+
+    /// The approximate match types need sorted values, `1` in ascending order and `-1` in descending order, like Excel
+    pub(crate) fn parse_match(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let lookup = self.get_formula(&mut args, &rule_name)?;
+        let values = self.get_formula(&mut args, &rule_name)?;
+        let match_type = self.get_opt_number(&mut args, &rule_name, 1.0)?;
+
+        let values = Self::get_vector(values)?;
+        let position = match match_type {
+            t if t > 0.0 => Self::find(&lookup, &values, MatchMode::ExactOrSmaller, SearchMode::BinaryAscending),
+            t if t < 0.0 => Self::find(&lookup, &values, MatchMode::ExactOrLarger, SearchMode::BinaryDescending),
+            _ => Self::find(&lookup, &values, MatchMode::Wildcard, SearchMode::FirstToLast),
+        };
+        let position = position.ok_or(Error::Excel(ErrorKind::NA))?;
+        Ok(Expr::Number((position + 1) as f64))
+    }
+
+    pub(crate) fn parse_xmatch(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let lookup = self.get_formula(&mut args, &rule_name)?;
+        let values = Self::get_vector(self.get_formula(&mut args, &rule_name)?)?;
+        let match_mode = MatchMode::new(self.get_opt_number(&mut args, &rule_name, 0.0)?)?;
+        let search_mode = SearchMode::new(self.get_opt_number(&mut args, &rule_name, 1.0)?)?;
+
+        let position = Self::find(&lookup, &values, match_mode, search_mode).ok_or(Error::Excel(ErrorKind::NA))?;
+        Ok(Expr::Number((position + 1) as f64))
+    }
+
+    /// The row or the column of the return array is returned, by the shape of the lookup array,
+    /// and `if_not_found` is only evaluated if the value is not found, like Excel
+    pub(crate) fn parse_xlookup(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let lookup = self.get_formula(&mut args, &rule_name)?;
+        let lookup_rows = Self::to_rows(self.get_formula(&mut args, &rule_name)?);
+        let return_rows = Self::to_rows(self.get_formula(&mut args, &rule_name)?);
+        let if_not_found = args.next().filter(|arg| !arg.is_empty());
+        let match_mode = MatchMode::new(self.get_opt_number(&mut args, &rule_name, 0.0)?)?;
+        let search_mode = SearchMode::new(self.get_opt_number(&mut args, &rule_name, 1.0)?)?;
+
+        let vertical = lookup_rows.len() > 1 || lookup_rows.first().map_or(0, Vec::len) == 1;
+        let (values, results) = if vertical {
+            (Self::get_vector(Expr::from_rows(lookup_rows))?, return_rows)
+        } else {
+            (
+                Self::get_vector(Expr::from_rows(lookup_rows))?,
+                Self::transpose(return_rows),
+            )
+        };
+        if values.len() != results.len() {
+            return Err(Error::Excel(ErrorKind::Value));
+        }
+
+        match Self::find(&lookup, &values, match_mode, search_mode) {
+            Some(position) => {
+                let mut result = results[position].clone();
+                let value = match result.len() {
+                    1 => result.remove(0),
+                    _ if vertical => Expr::Array(result),
+                    _ => Expr::from_rows(result.into_iter().map(|item| vec![item]).collect()),
+                };
+                Ok(value)
+            }
+            None => match if_not_found {
+                Some(if_not_found) => self.parse_node(if_not_found),
+                None => Ok(Expr::Error(ErrorKind::NA)),
+            },
+        }
+    }
+
+    /// Return the values of a single row or column, otherwise it's `#N/A`, like Excel
+    fn get_vector(values: Expr) -> Result<Vec<Expr>> {
+        let rows = Self::to_rows(values);
+        match rows.len() {
+            1 => Ok(rows.into_iter().flatten().collect()),
+            _ if rows.iter().all(|row| row.len() == 1) => Ok(rows.into_iter().flatten().collect()),
+            _ => Err(Error::Excel(ErrorKind::NA)),
+        }
+    }
+
+    /// Only the chosen value is evaluated, like Excel
+    pub(crate) fn parse_choose(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let index = self.get_opt_number(&mut args, &rule_name, 0.0)?.trunc();
+        let values = args.as_slice();
+
+        if index < 1.0 || index > values.len() as f64 {
+            return Err(Error::Excel(ErrorKind::Value));
+        }
+        self.parse_node(&values[index as usize - 1])
+    }
+
+    pub(crate) fn parse_rows(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rows = Self::to_rows(self.get_formula(&mut args, &rule_name)?);
+        Ok(Expr::Number(rows.len() as f64))
+    }
+
+    pub(crate) fn parse_columns(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rows = Self::to_rows(self.get_formula(&mut args, &rule_name)?);
+        Ok(Expr::Number(rows.iter().map(Vec::len).max().unwrap_or_default() as f64))
+    }
+
+    pub(crate) fn parse_transpose(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let rows = Self::to_rows(self.get_formula(&mut args, &rule_name)?);
+        Ok(Expr::from_rows(Self::transpose(rows)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Formula;
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_parse_lookup_types() {
+        let table = "{1, 'one', 'I'; 2, 'two', 'II'; 3, 'three', 'III'; 5, 'five', 'V'}";
+        let values = [
+            (format!("=VLOOKUP(2, {table}, 2)"), Expr::String("two".to_string())),
+            (format!("=VLOOKUP(4, {table}, 3)"), Expr::String("III".to_string())),
+            (
+                format!("=VLOOKUP(9, {table}, 2, TRUE)"),
+                Expr::String("five".to_string()),
+            ),
+            (
+                format!("=VLOOKUP(3, {table}, 2, FALSE)"),
+                Expr::String("three".to_string()),
+            ),
+            (format!("=VLOOKUP(4, {table}, 2, FALSE)"), Expr::Error(ErrorKind::NA)),
+            (format!("=VLOOKUP(0, {table}, 2)"), Expr::Error(ErrorKind::NA)),
+            (format!("=VLOOKUP(1, {table}, 4)"), Expr::Error(ErrorKind::Ref)),
+            (format!("=VLOOKUP(1, {table}, 0)"), Expr::Error(ErrorKind::Value)),
+            (
+                "=VLOOKUP('t*', {'one', 1; 'two', 2; 'three', 3}, 2, FALSE)".to_string(),
+                Expr::Number(2.0),
+            ),
+            (
+                "=VLOOKUP('TWO', {'one', 1; 'two', 2}, 2, 0)".to_string(),
+                Expr::Number(2.0),
+            ),
+            (
+                "=HLOOKUP('b', {'a', 'b', 'c'; 1, 2, 3}, 2, FALSE)".to_string(),
+                Expr::Number(2.0),
+            ),
+            (
+                "=HLOOKUP(2.5, {1, 2, 3; 'x', 'y', 'z'}, 2)".to_string(),
+                Expr::String("y".to_string()),
+            ),
+            (
+                "=LOOKUP(4.2, {1, 2, 3, 4, 5}, {'a', 'b', 'c', 'd', 'e'})".to_string(),
+                Expr::String("d".to_string()),
+            ),
+            ("=LOOKUP(0, {1, 2}, {'a', 'b'})".to_string(), Expr::Error(ErrorKind::NA)),
+            ("=LOOKUP('c', {'a', 1; 'b', 2; 'd', 3})".to_string(), Expr::Number(2.0)),
+            (
+                "=LOOKUP(2, {1, 2, 3; 'a', 'b', 'c'})".to_string(),
+                Expr::String("b".to_string()),
+            ),
+            ("=INDEX({1, 2; 3, 4}, 2, 1)".to_string(), Expr::Number(3.0)),
+            ("=INDEX({1, 2, 3}, 2)".to_string(), Expr::Number(2.0)),
+            ("=INDEX({1; 2; 3}, 3)".to_string(), Expr::Number(3.0)),
+            (
+                "=INDEX({1, 2; 3, 4}, 0, 2)".to_string(),
+                Expr::Array(vec![
+                    Expr::Array(vec![Expr::Number(2.0)]),
+                    Expr::Array(vec![Expr::Number(4.0)]),
+                ]),
+            ),
+            (
+                "=INDEX({1, 2; 3, 4}, 2, 0)".to_string(),
+                Expr::Array(vec![Expr::Number(3.0), Expr::Number(4.0)]),
+            ),
+            ("=INDEX({1, 2; 3, 4}, 3, 1)".to_string(), Expr::Error(ErrorKind::Ref)),
+            ("=MATCH(25, {10, 20, 30})".to_string(), Expr::Number(2.0)),
+            ("=MATCH(30, {10, 20, 30}, 1)".to_string(), Expr::Number(3.0)),
+            ("=MATCH(5, {10, 20, 30})".to_string(), Expr::Error(ErrorKind::NA)),
+            ("=MATCH('b?', {'a', 'bc', 'b'}, 0)".to_string(), Expr::Number(2.0)),
+            ("=MATCH(25, {30, 20, 10}, -1)".to_string(), Expr::Number(1.0)),
+            ("=MATCH(20, {30; 20; 10}, -1)".to_string(), Expr::Number(2.0)),
+            ("=MATCH(1, {1, 2; 3, 4}, 0)".to_string(), Expr::Error(ErrorKind::NA)),
+            ("=XMATCH(3, {5, 3, 1, 3})".to_string(), Expr::Number(2.0)),
+            ("=XMATCH(3, {5, 3, 1, 3}, 0, -1)".to_string(), Expr::Number(4.0)),
+            ("=XMATCH(4, {5, 3, 1, 3}, -1)".to_string(), Expr::Number(2.0)),
+            ("=XMATCH(4, {5, 3, 1, 3}, 1)".to_string(), Expr::Number(1.0)),
+            ("=XMATCH(6, {5, 3, 1, 3}, 1)".to_string(), Expr::Error(ErrorKind::NA)),
+            ("=XMATCH('*e', {'a', 'be', 'ce'}, 2)".to_string(), Expr::Number(2.0)),
+            (
+                "=XMATCH('*e', {'a', 'be', 'ce'}, 0)".to_string(),
+                Expr::Error(ErrorKind::NA),
+            ),
+            ("=XMATCH(7, {1, 3, 5, 7, 9}, 0, 2)".to_string(), Expr::Number(4.0)),
+            ("=XMATCH(6, {1, 3, 5, 7, 9}, -1, 2)".to_string(), Expr::Number(3.0)),
+            ("=XMATCH(6, {1, 3, 5, 7, 9}, 1, 2)".to_string(), Expr::Number(4.0)),
+            ("=XMATCH(6, {9, 7, 5, 3, 1}, -1, -2)".to_string(), Expr::Number(3.0)),
+            ("=XMATCH(6, {9, 7, 5, 3, 1}, 1, -2)".to_string(), Expr::Number(2.0)),
+            (
+                "=XMATCH(6, {9, 7, 5, 3, 1}, 0, -2)".to_string(),
+                Expr::Error(ErrorKind::NA),
+            ),
+            ("=XMATCH(1, {1, 2}, 3)".to_string(), Expr::Error(ErrorKind::Value)),
+            ("=XMATCH(3, {5, 3, 1, 3}, , -1)".to_string(), Expr::Number(4.0)),
+            (
+                "=XLOOKUP('b', {'a'; 'b'; 'c'}, {1, 10; 2, 20; 3, 30})".to_string(),
+                Expr::Array(vec![Expr::Number(2.0), Expr::Number(20.0)]),
+            ),
+            (
+                "=XLOOKUP('b', {'a', 'b', 'c'}, {1, 2, 3})".to_string(),
+                Expr::Number(2.0),
+            ),
+            (
+                "=XLOOKUP('b', {'a', 'b', 'c'}, {1, 2, 3; 10, 20, 30})".to_string(),
+                Expr::Array(vec![
+                    Expr::Array(vec![Expr::Number(2.0)]),
+                    Expr::Array(vec![Expr::Number(20.0)]),
+                ]),
+            ),
+            (
+                "=XLOOKUP('d', {'a', 'b', 'c'}, {1, 2, 3}, 'none')".to_string(),
+                Expr::String("none".to_string()),
+            ),
+            (
+                "=XLOOKUP('d', {'a', 'b', 'c'}, {1, 2, 3})".to_string(),
+                Expr::Error(ErrorKind::NA),
+            ),
+            (
+                "=XLOOKUP('b', {'a', 'b', 'c'}, {1, 2, 3}, 1/0)".to_string(),
+                Expr::Number(2.0),
+            ),
+            (
+                "=XLOOKUP(25, {10, 20, 30}, {1, 2, 3}, 0, 1)".to_string(),
+                Expr::Number(3.0),
+            ),
+            (
+                "=XLOOKUP(25, {10, 20, 30}, {1, 2, 3}, 0, -1)".to_string(),
+                Expr::Number(2.0),
+            ),
+            (
+                "=XLOOKUP(2, {2, 1, 2}, {1, 2, 3}, 0, 0, -1)".to_string(),
+                Expr::Number(3.0),
+            ),
+            (
+                "=XLOOKUP(25, {10, 20, 30}, {1, 2, 3}, , -1)".to_string(),
+                Expr::Number(2.0),
+            ),
+            (
+                "=XLOOKUP(5, {10, 20, 30}, {1, 2, 3}, , -1)".to_string(),
+                Expr::Error(ErrorKind::NA),
+            ),
+            (
+                "=XLOOKUP(2, {2, 1, 2}, {1, 2, 3}, , , -1)".to_string(),
+                Expr::Number(3.0),
+            ),
+            (
+                "=XLOOKUP(1, {1, 2}, {1, 2, 3})".to_string(),
+                Expr::Error(ErrorKind::Value),
+            ),
+            ("=CHOOSE(2, 'a', 'b', 1/0)".to_string(), Expr::String("b".to_string())),
+            ("=CHOOSE(4, 'a', 'b', 'c')".to_string(), Expr::Error(ErrorKind::Value)),
+            ("=ROWS({1, 2; 3, 4; 5, 6})".to_string(), Expr::Number(3.0)),
+            ("=ROWS({1, 2})".to_string(), Expr::Number(1.0)),
+            ("=ROWS(1)".to_string(), Expr::Number(1.0)),
+            ("=COLUMNS({1, 2; 3, 4; 5, 6})".to_string(), Expr::Number(2.0)),
+            (
+                "=TRANSPOSE({1, 2; 3, 4; 5, 6})".to_string(),
+                Expr::Array(vec![
+                    Expr::Array(vec![Expr::Number(1.0), Expr::Number(3.0), Expr::Number(5.0)]),
+                    Expr::Array(vec![Expr::Number(2.0), Expr::Number(4.0), Expr::Number(6.0)]),
+                ]),
+            ),
+            (
+                "=TRANSPOSE({1, 2})".to_string(),
+                Expr::Array(vec![
+                    Expr::Array(vec![Expr::Number(1.0)]),
+                    Expr::Array(vec![Expr::Number(2.0)]),
+                ]),
+            ),
+            ("=SUM(TRANSPOSE({1, 2; 3, 4}))".to_string(), Expr::Number(10.0)),
+        ];
+        for (formula, value) in values {
+            let formula = Formula::new(&formula).unwrap();
+            assert_eq!(formula.parse().unwrap(), value, "{formula:?}");
+        }
+    }
+}
@@ -1,13 +1,27 @@
-use crate::{error::Error, Expr, Formula, Result, Rule};
-use pest::iterators::Pair;
+use crate::{
+    ast::Node,
+    context::{MAX_COLUMNS, MAX_ROWS},
+    error::Error,
+    evaluator::Evaluator,
+    parsers::{
+        criteria::Criteria,
+        helper::{to_decimal, SIGNIFICANT_DIGITS},
+        matrix::Matrix,
+        statistical::Values,
+    },
+    ErrorKind, Expr, Result, Rule,
+};
 use rand::Rng;
-use std::f64::consts::PI;
+use std::{f64::consts::PI, slice::Iter};
 
-impl Formula<'_> {
-    pub(crate) fn parse_abs(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+/// The largest integer of `GCD` and `LCM`, which is 2^53, like Excel
+const MAX_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+impl Evaluator<'_> {
+    pub(crate) fn parse_abs(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.abs(),
@@ -16,10 +30,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_acos(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_acos(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.acos(),
@@ -28,10 +42,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_acosh(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_acosh(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.acosh(),
@@ -40,10 +54,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_cos(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_cos(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.cos(),
@@ -52,10 +66,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_cosh(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_cosh(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.cosh(),
@@ -64,10 +78,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_asin(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_asin(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.asin(),
@@ -76,10 +90,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_asinh(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_asinh(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.asinh(),
@@ -88,10 +102,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_sin(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_sin(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.sin(),
@@ -100,10 +114,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_sinh(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_sinh(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.sinh(),
@@ -112,10 +126,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_atan(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_atan(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.atan(),
@@ -124,11 +138,11 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_atan2(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_atan2(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
 
         let res = match (operand1, operand2) {
             (Expr::Number(operand1), Expr::Number(operand2)) => operand1.atan2(operand2),
@@ -137,10 +151,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_atanh(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_atanh(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.atanh(),
@@ -149,10 +163,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_tan(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_tan(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.tan(),
@@ -161,10 +175,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_tanh(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_tanh(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.tanh(),
@@ -173,29 +187,34 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    #[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
-    pub(crate) fn parse_pi(_pair: Pair<Rule>) -> Result<Expr> {
+    #[allow(clippy::unnecessary_wraps)]
+    pub(crate) fn parse_pi(&self) -> Result<Expr> {
         Ok(Expr::Number(PI))
     }
 
-    pub(crate) fn parse_mod(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_mod(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
 
         let res = match (operand1, operand2) {
-            (Expr::Number(operand1), Expr::Number(operand2)) => operand1 % operand2,
+            (Expr::Number(operand1), Expr::Number(operand2)) => {
+                if operand2 == 0.0 {
+                    return Ok(Expr::Error(ErrorKind::DivZero));
+                }
+                operand1 % operand2
+            }
             _ => return Err(Error::Parser(rule_name)),
         };
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_log(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_log(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
 
         let res = match (operand1, operand2) {
             (Expr::Number(operand1), Expr::Number(operand2)) => operand1.log(operand2),
@@ -204,10 +223,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_log10(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_log10(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.log10(),
@@ -216,10 +235,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_ln(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_ln(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.ln(),
@@ -228,15 +247,15 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_sqrt(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_sqrt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => {
                 if operand < 0.0 {
-                    Expr::Null
+                    Expr::Error(ErrorKind::Num)
                 } else {
                     Expr::Number(operand.sqrt())
                 }
@@ -246,15 +265,15 @@ impl Formula<'_> {
         Ok(res)
     }
 
-    pub(crate) fn parse_sqrtpi(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_sqrtpi(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => {
                 if operand < 0.0 {
-                    Expr::Null
+                    Expr::Error(ErrorKind::Num)
                 } else {
                     Expr::Number((operand * PI).sqrt())
                 }
@@ -264,16 +283,16 @@ impl Formula<'_> {
         Ok(res)
     }
 
-    #[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
-    pub(crate) fn parse_rand(_pair: Pair<Rule>) -> Result<Expr> {
+    #[allow(clippy::unnecessary_wraps)]
+    pub(crate) fn parse_rand(&self) -> Result<Expr> {
         let mut rng = rand::thread_rng();
         Ok(Expr::Number(rng.gen_range(0.0..1.0)))
     }
 
-    pub(crate) fn parse_sign(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_sign(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => {
@@ -288,10 +307,10 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_exp(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_exp(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
 
         let res = match operand {
             Expr::Number(operand) => operand.exp(),
@@ -300,25 +319,521 @@ impl Formula<'_> {
         Ok(Expr::Number(res))
     }
 
-    pub(crate) fn parse_sum(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
-        let operands = args.map(Self::parse_pair).collect::<Result<Vec<_>>>()?;
+    pub(crate) fn parse_sum(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        if args.is_empty() {
+            return Err(Error::Parser(rule_name));
+        }
 
-        let res = match operand {
-            Expr::Number(mut operand) => {
-                for o in operands {
-                    match o {
-                        Expr::Number(o) => operand += o,
-                        _ => return Err(Error::Parser(rule_name)),
-                    }
-                }
-                operand
+        // The sum starts from 0, because `Iterator::sum` of no numbers is -0
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        Ok(Expr::Number(numbers.into_iter().fold(0.0, |sum, number| sum + number)))
+    }
+
+    pub(crate) fn parse_sumif(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let range = Self::get_range(self.parse_node(args.next().ok_or_else(|| Error::Parser(rule_name.clone()))?)?);
+        let criteria = Criteria::new(self.get_formula(&mut args, &rule_name)?)?;
+        let values = match args.next() {
+            Some(values) => Self::get_range(self.parse_node(values)?),
+            None => range.clone(),
+        };
+
+        let values = Self::get_matched_values(values, vec![(range, criteria)])?;
+        Ok(Expr::Number(Self::get_matched_numbers(values)?.into_iter().sum()))
+    }
+
+    pub(crate) fn parse_sumifs(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let (values, ranges) = args.split_first().ok_or_else(|| Error::Parser(rule_name.clone()))?;
+        let values = Self::get_range(self.parse_node(values)?);
+        let ranges = self.get_criteria_ranges(ranges, &rule_name)?;
+
+        let values = Self::get_matched_values(values, ranges)?;
+        Ok(Expr::Number(Self::get_matched_numbers(values)?.into_iter().sum()))
+    }
+
+    /// Round a number to a number of digits, which are the decimals if it's positive, or the digits of the integer part
+    /// if it's negative, by a rounding function of the integers
+    fn round_digits(number: f64, digits: f64, round: fn(f64) -> f64) -> f64 {
+        let number = to_decimal(number);
+        let digits = digits.trunc();
+        // The number has no more significant digits to round
+        let precision = (SIGNIFICANT_DIGITS - 1) as f64 - number.abs().log10().floor();
+        if number == 0.0 || !number.is_finite() || digits >= precision {
+            return number;
+        }
+
+        let scale = 10_f64.powf(digits.abs());
+        if scale.is_infinite() {
+            0.0
+        } else if digits >= 0.0 {
+            round(to_decimal(number * scale)) / scale
+        } else {
+            round(to_decimal(number / scale)) * scale
+        }
+    }
+
+    /// Round a number to a multiple of a significance, by a rounding function of their quotient,
+    /// and the number is 0 if the significance is 0
+    fn round_multiple(number: f64, significance: f64, round: fn(f64) -> f64) -> f64 {
+        if significance == 0.0 {
+            return 0.0;
+        }
+        to_decimal(round(to_decimal(number / significance)) * significance)
+    }
+
+    /// Round a number to an integer away from zero, like `ROUNDUP`
+    fn round_up(number: f64) -> f64 {
+        if number < 0.0 {
+            number.floor()
+        } else {
+            number.ceil()
+        }
+    }
+
+    /// Return the number and the digits of the rounding functions, like `ROUND`
+    fn get_round_args(&self, rule: Rule, args: &[Node]) -> Result<(f64, f64)> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?;
+        let digits = self.get_opt_number(&mut args, &rule_name, 0.0)?;
+        Ok((number, digits))
+    }
+
+    /// The halves are rounded away from zero, like Excel
+    pub(crate) fn parse_round(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (number, digits) = self.get_round_args(rule, args)?;
+        Ok(Expr::Number(Self::round_digits(number, digits, f64::round)))
+    }
+
+    pub(crate) fn parse_roundup(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (number, digits) = self.get_round_args(rule, args)?;
+        Ok(Expr::Number(Self::round_digits(number, digits, Self::round_up)))
+    }
+
+    pub(crate) fn parse_rounddown(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let (number, digits) = self.get_round_args(rule, args)?;
+        Ok(Expr::Number(Self::round_digits(number, digits, f64::trunc)))
+    }
+
+    /// `TRUNC` is `ROUNDDOWN`, whose digits are 0 by default
+    pub(crate) fn parse_trunc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.parse_rounddown(rule, args)
+    }
+
+    pub(crate) fn parse_int(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let number = self.get_number(&mut args.iter(), &rule_name)?;
+        Ok(Expr::Number(to_decimal(number).floor()))
+    }
+
+    /// The number and the multiple must have the same sign, and the halves are rounded away from zero
+    pub(crate) fn parse_mround(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?;
+        let multiple = self.get_number(&mut args, &rule_name)?;
+        if number * multiple < 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(Self::round_multiple(number, multiple, f64::round)))
+    }
+
+    /// Return the number and the significance of `CEILING` and `FLOOR`, which must not be negative
+    /// if the number is positive, otherwise it's `None`
+    fn get_significance_args(&self, rule: Rule, args: &[Node]) -> Result<Option<(f64, f64)>> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?;
+        let significance = self.get_number(&mut args, &rule_name)?;
+        Ok((number <= 0.0 || significance >= 0.0).then_some((number, significance)))
+    }
+
+    /// The negative numbers are rounded toward zero by a positive significance, and away from zero
+    /// by a negative one, like Excel
+    pub(crate) fn parse_ceiling(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let Some((number, significance)) = self.get_significance_args(rule, args)? else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        Ok(Expr::Number(Self::round_multiple(number, significance, f64::ceil)))
+    }
+
+    /// The negative numbers are rounded away from zero by a positive significance, and toward zero
+    /// by a negative one, and the significance can't be 0 unless the number is 0, like Excel
+    pub(crate) fn parse_floor(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let Some((number, significance)) = self.get_significance_args(rule, args)? else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        if significance == 0.0 && number != 0.0 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        Ok(Expr::Number(Self::round_multiple(number, significance, f64::floor)))
+    }
+
+    /// Return the number, the absolute significance, which is 1 by default, and whether the negative numbers
+    /// are rounded by the opposite direction, by a nonzero mode, of `CEILING.MATH` and `FLOOR.MATH`
+    fn get_math_significance_args(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<(f64, f64, bool)> {
+        let number = self.get_number(args, rule_name)?;
+        let significance = self.get_opt_number(args, rule_name, 1.0)?.abs();
+        let mode = self.get_opt_number(args, rule_name, 0.0)?;
+        Ok((number, significance, number < 0.0 && mode != 0.0))
+    }
+
+    /// The negative numbers are rounded toward zero, or away from zero by a nonzero mode
+    pub(crate) fn parse_ceiling_math(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let (number, significance, opposite) = self.get_math_significance_args(&mut args.iter(), &rule_name)?;
+        let round = if opposite { f64::floor } else { f64::ceil };
+        Ok(Expr::Number(Self::round_multiple(number, significance, round)))
+    }
+
+    /// The negative numbers are rounded away from zero, or toward zero by a nonzero mode
+    pub(crate) fn parse_floor_math(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let (number, significance, opposite) = self.get_math_significance_args(&mut args.iter(), &rule_name)?;
+        let round = if opposite { f64::ceil } else { f64::floor };
+        Ok(Expr::Number(Self::round_multiple(number, significance, round)))
+    }
+
+    /// The sign of the significance is ignored, so the numbers are always rounded up, like `ISO.CEILING`
+    pub(crate) fn parse_ceiling_precise(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?;
+        let significance = self.get_opt_number(&mut args, &rule_name, 1.0)?.abs();
+        Ok(Expr::Number(Self::round_multiple(number, significance, f64::ceil)))
+    }
+
+    /// The sign of the significance is ignored, so the numbers are always rounded down
+    pub(crate) fn parse_floor_precise(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?;
+        let significance = self.get_opt_number(&mut args, &rule_name, 1.0)?.abs();
+        Ok(Expr::Number(Self::round_multiple(number, significance, f64::floor)))
+    }
+
+    /// The numbers are rounded away from zero
+    pub(crate) fn parse_even(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let number = to_decimal(self.get_number(&mut args.iter(), &rule_name)?);
+        Ok(Expr::Number(number.signum() * (number.abs() / 2.0).ceil() * 2.0))
+    }
+
+    /// The numbers are rounded away from zero, and `ODD(0)` is 1
+    pub(crate) fn parse_odd(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let number = to_decimal(self.get_number(&mut args.iter(), &rule_name)?);
+        let odd = ((number.abs() + 1.0) / 2.0).ceil() * 2.0 - 1.0;
+        Ok(Expr::Number(if number < 0.0 { -odd } else { odd }))
+    }
+
+    pub(crate) fn parse_quotient(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let numerator = self.get_number(&mut args, &rule_name)?;
+        let denominator = self.get_number(&mut args, &rule_name)?;
+        if denominator == 0.0 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        Ok(Expr::Number(to_decimal(numerator / denominator).trunc()))
+    }
+
+    /// Return the integers of `GCD` and `LCM`, which are truncated, or `None` if one is negative or too large
+    fn get_integers(&self, args: &[Node]) -> Result<Option<Vec<u64>>> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        if numbers.iter().any(|number| !(0.0..=MAX_INTEGER).contains(number)) {
+            return Ok(None);
+        }
+        Ok(Some(numbers.into_iter().map(|number| number as u64).collect()))
+    }
+
+    fn gcd(mut a: u64, mut b: u64) -> u64 {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+
+    pub(crate) fn parse_gcd(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let Some(integers) = self.get_integers(args)? else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        let gcd = integers.into_iter().fold(0, Self::gcd);
+        Ok(Expr::Number(gcd as f64))
+    }
+
+    /// The least common multiple is 0 if an integer is 0, and it must not be larger than 2^53, like Excel
+    pub(crate) fn parse_lcm(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let Some(integers) = self.get_integers(args)? else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        let mut lcm: u64 = 1;
+        for integer in integers {
+            if integer == 0 {
+                return Ok(Expr::Number(0.0));
             }
-            _ => return Err(Error::Parser(rule_name)),
+            match (lcm / Self::gcd(lcm, integer)).checked_mul(integer) {
+                Some(multiple) if multiple as f64 <= MAX_INTEGER => lcm = multiple,
+                _ => return Ok(Expr::Error(ErrorKind::Num)),
+            }
+        }
+        Ok(Expr::Number(lcm as f64))
+    }
+
+    /// Return a number, or `#NUM!` if it overflows, like `FACT(171)`
+    fn to_finite_number(number: f64) -> Expr {
+        if number.is_finite() {
+            Expr::Number(number)
+        } else {
+            Expr::Error(ErrorKind::Num)
+        }
+    }
+
+    /// Return the number of the combinations of `k` items of `n` items, which is exact while it's an exact integer,
+    /// since each partial product is a number of combinations too, or infinite if it overflows
+    fn combinations(n: f64, k: f64) -> f64 {
+        let k = k.min(n - k);
+        let mut product = 1.0;
+        for i in 1..=k as u64 {
+            product = product * (n - k + i as f64) / i as f64;
+            if product.is_infinite() {
+                break;
+            }
+        }
+        product
+    }
+
+    /// Return the product of the integers, which is rounded once, since it's kept as the sum of two numbers,
+    /// the rounded product and its rounding error, so `FACT(170)` is the nearest number to 170!
+    fn product(factors: impl Iterator<Item = u64>) -> f64 {
+        let (mut high, mut low) = (1.0_f64, 0.0_f64);
+        for factor in factors {
+            let factor = factor as f64;
+            let product = high * factor;
+            let error = high.mul_add(factor, -product) + low * factor;
+            high = product + error;
+            low = error - (high - product);
+            if high.is_infinite() {
+                break;
+            }
+        }
+        high + low
+    }
+
+    pub(crate) fn parse_fact(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let number = self.get_number(&mut args.iter(), &rule_name)?.trunc();
+        if number < 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        let fact = Self::product(2..=number.min(f64::from(u16::MAX)) as u64);
+        Ok(Self::to_finite_number(fact))
+    }
+
+    /// The double factorial of `n` is the product of the numbers from `n` down to 1 or 2, by the steps of 2
+    pub(crate) fn parse_factdouble(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let number = self.get_number(&mut args.iter(), &rule_name)?.trunc();
+        if number < 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        let number = number.min(f64::from(u16::MAX)) as u64;
+        let fact = Self::product((1..=number).rev().step_by(2));
+        Ok(Self::to_finite_number(fact))
+    }
+
+    pub(crate) fn parse_combin(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?.trunc();
+        let chosen = self.get_number(&mut args, &rule_name)?.trunc();
+        if number < 0.0 || chosen < 0.0 || number < chosen {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Self::to_finite_number(Self::combinations(number, chosen)))
+    }
+
+    /// The combinations with repetitions of `k` items of `n` items are the combinations of `k` of `n + k - 1`
+    pub(crate) fn parse_combina(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?.trunc();
+        let chosen = self.get_number(&mut args, &rule_name)?.trunc();
+        if number < 0.0 || chosen < 0.0 || (number == 0.0 && chosen > 0.0) {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        let combinations = Self::combinations((number + chosen - 1.0).max(0.0), chosen);
+        Ok(Self::to_finite_number(combinations))
+    }
+
+    /// The multinomial is the product of the combinations of each number, of the sum of the numbers so far,
+    /// so it doesn't overflow unless the result does
+    pub(crate) fn parse_multinomial(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        if numbers.iter().any(|number| *number < 0.0) {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let mut sum = 0.0;
+        let mut multinomial = 1.0;
+        for number in numbers {
+            let number = number.trunc();
+            sum += number;
+            multinomial *= Self::combinations(sum, number);
+        }
+        Ok(Self::to_finite_number(multinomial))
+    }
+
+    /// The sum of `a_i x^(n + i m)`, where `a_i` are the coefficients
+    pub(crate) fn parse_seriessum(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let n = self.get_number(&mut args, &rule_name)?;
+        let m = self.get_number(&mut args, &rule_name)?;
+        let coefficients = match self.get_formula(&mut args, &rule_name)? {
+            Expr::Array(array) => Self::flatten(array),
+            value => vec![value],
         };
-        Ok(Expr::Number(res))
+
+        let mut sum = 0.0;
+        for (i, coefficient) in coefficients.into_iter().enumerate() {
+            match coefficient {
+                Expr::Number(coefficient) => sum += coefficient * x.powf(n + i as f64 * m),
+                Expr::Error(kind) => return Ok(Expr::Error(kind)),
+                _ => return Ok(Expr::Error(ErrorKind::Value)),
+            }
+        }
+        Ok(Self::to_finite_number(sum))
+    }
+
+    /// Return the matrix of a value, which must be a rectangular table of numbers, or a number, like `MMULT`
+    fn to_matrix(value: Expr) -> Result<Matrix> {
+        Ok(Matrix::from_rows(&Self::to_number_table(value)?))
+    }
+
+    /// Return the array of a matrix, whose numbers are `#NUM!` if they overflow
+    fn from_matrix(matrix: &Matrix) -> Expr {
+        let rows = (0..matrix.rows())
+            .map(|i| {
+                matrix
+                    .row(i)
+                    .iter()
+                    .map(|value| Self::to_finite_number(*value))
+                    .collect()
+            })
+            .collect();
+        Expr::from_rows(rows)
+    }
+
+    /// Return the square matrix of an argument, or `#VALUE!` if it's not square, like `MDETERM`
+    fn get_square_matrix(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<Matrix> {
+        let matrix = Self::to_matrix(self.get_formula(args, rule_name)?)?;
+        if matrix.rows() != matrix.columns() {
+            return Err(Error::Excel(ErrorKind::Value));
+        }
+        Ok(matrix)
+    }
+
+    /// The columns of the first array must be as many as the rows of the second one
+    pub(crate) fn parse_mmult(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let first = Self::to_matrix(self.get_formula(&mut args, &rule_name)?)?;
+        let second = Self::to_matrix(self.get_formula(&mut args, &rule_name)?)?;
+        match first.multiply(&second) {
+            Some(product) => Ok(Self::from_matrix(&product)),
+            None => Ok(Expr::Error(ErrorKind::Value)),
+        }
+    }
+
+    pub(crate) fn parse_mdeterm(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let matrix = self.get_square_matrix(&mut args.iter(), &rule_name)?;
+        Ok(Expr::Number(matrix.lu().determinant()))
+    }
+
+    /// The singular matrices have no inverse, so they are `#NUM!`
+    pub(crate) fn parse_minverse(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let matrix = self.get_square_matrix(&mut args.iter(), &rule_name)?;
+        match matrix.lu().inverse() {
+            Some(inverse) => Ok(Self::from_matrix(&inverse)),
+            None => Ok(Expr::Error(ErrorKind::Num)),
+        }
+    }
+
+    pub(crate) fn parse_munit(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let size = self.get_number(&mut args.iter(), &rule_name)?.trunc();
+        // The matrix must fit in a sheet, which has fewer columns than rows
+        if size < 1.0 || size > f64::from(MAX_COLUMNS.min(MAX_ROWS)) {
+            return Ok(Expr::Error(ErrorKind::Value));
+        }
+        Ok(Self::from_matrix(&Matrix::identity(size as usize)))
+    }
+
+    /// The arrays must have the same shape, and their items which are not numbers are 0, like Excel
+    pub(crate) fn parse_sumproduct(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        if args.is_empty() {
+            return Err(Error::Parser(rule_name));
+        }
+
+        let mut products: Option<(Vec<usize>, Vec<f64>)> = None;
+        for arg in args {
+            let rows = Self::to_rows(self.parse_arg(arg)?);
+            let shape = rows.iter().map(Vec::len).collect::<Vec<_>>();
+            let numbers = rows
+                .into_iter()
+                .flatten()
+                .map(|item| match item {
+                    Expr::Number(number) => Ok(number),
+                    Expr::Error(kind) => Err(Error::Excel(kind)),
+                    _ => Ok(0.0),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            products = match products {
+                None => Some((shape, numbers)),
+                Some((product_shape, _)) if product_shape != shape => return Ok(Expr::Error(ErrorKind::Value)),
+                Some((product_shape, product)) => {
+                    let product = product.iter().zip(numbers).map(|(a, b)| a * b).collect();
+                    Some((product_shape, product))
+                }
+            };
+        }
+        let sum = products.map_or(0.0, |(_, product)| product.into_iter().sum());
+        Ok(Expr::Number(sum))
+    }
+
+    pub(crate) fn parse_sumsq(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        Ok(Expr::Number(
+            numbers.into_iter().fold(0.0, |sum, number| sum + number * number),
+        ))
+    }
+
+    /// Return the sum of a function of the pairs of the numbers of two arrays, like `SUMX2MY2`,
+    /// where the arrays must have the same number of items, and the pairs which have other values are ignored
+    fn sum_pairs(&self, rule: Rule, args: &[Node], function: fn(f64, f64) -> f64) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let (x, y) = self.get_pairs(&mut args.iter(), &rule_name)?;
+        Ok(Expr::Number(x.into_iter().zip(y).map(|(x, y)| function(x, y)).sum()))
+    }
+
+    pub(crate) fn parse_sumx2my2(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.sum_pairs(rule, args, |x, y| x * x - y * y)
+    }
+
+    pub(crate) fn parse_sumx2py2(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.sum_pairs(rule, args, |x, y| x * x + y * y)
+    }
+
+    pub(crate) fn parse_sumxmy2(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.sum_pairs(rule, args, |x, y| (x - y).powi(2))
     }
 }
 
@@ -337,5 +852,229 @@ mod tests {
         let formula = Formula::new("=SIGN(LOG10(SQRT(SUM(1,2,3,3))))").unwrap();
         let value = formula.parse().unwrap();
         assert_eq!(value, Expr::Number(1.0));
+
+        // The numbers are rounded to 15 significant digits first, like Excel, so 2.675 is not 2.67499999999999982
+        let numbers = [
+            ("=ROUND(2.15, 1)", 2.2),
+            ("=ROUND(2.149, 1)", 2.1),
+            ("=ROUND(-1.475, 2)", -1.48),
+            ("=ROUND(2.675, 2)", 2.68),
+            ("=ROUND(1.005, 2)", 1.01),
+            ("=ROUND(21.5, -1)", 20.0),
+            ("=ROUND(626.3, -3)", 1000.0),
+            ("=ROUND(1.98, -1)", 0.0),
+            ("=ROUND(-50.55, -2)", -100.0),
+            ("=ROUND(0.5, 0)", 1.0),
+            ("=ROUND(-2.5, 0)", -3.0),
+            ("=ROUND(1234.5678, 20)", 1234.5678),
+            ("=ROUND(1, -400)", 0.0),
+            ("=ROUNDUP(3.2, 0)", 4.0),
+            ("=ROUNDUP(1.23456, 3)", 1.235),
+            ("=ROUNDUP(-3.14159, 1)", -3.2),
+            ("=ROUNDUP(31415.92654, -2)", 31500.0),
+            ("=ROUNDUP(0.1 + 0.2, 1)", 0.3),
+            ("=ROUNDDOWN(76.9, 0)", 76.0),
+            ("=ROUNDDOWN(1.23456, 3)", 1.234),
+            ("=ROUNDDOWN(-3.14159, 1)", -3.1),
+            ("=ROUNDDOWN(31415.92654, -2)", 31400.0),
+            ("=MROUND(10, 3)", 9.0),
+            ("=MROUND(-10, -3)", -9.0),
+            ("=MROUND(1.3, 0.2)", 1.4),
+            ("=MROUND(7.5, 5)", 10.0),
+            ("=MROUND(5, 0)", 0.0),
+            ("=TRUNC(8.9)", 8.0),
+            ("=TRUNC(-8.9)", -8.0),
+            ("=TRUNC(1.23456, 2)", 1.23),
+            ("=INT(8.9)", 8.0),
+            ("=INT(-8.9)", -9.0),
+            ("=CEILING(2.5, 1)", 3.0),
+            ("=CEILING(-2.5, -2)", -4.0),
+            ("=CEILING(-2.5, 2)", -2.0),
+            ("=CEILING(1.5, 0.1)", 1.5),
+            ("=CEILING(0.234, 0.01)", 0.24),
+            ("=CEILING(5, 0)", 0.0),
+            ("=FLOOR(3.7, 2)", 2.0),
+            ("=FLOOR(-2.5, -2)", -2.0),
+            ("=FLOOR(-2.5, 2)", -4.0),
+            ("=FLOOR(1.58, 0.1)", 1.5),
+            ("=FLOOR(0.234, 0.01)", 0.23),
+            ("=FLOOR(0, 0)", 0.0),
+            ("=CEILING.MATH(24.3, 5)", 25.0),
+            ("=CEILING.MATH(6.7)", 7.0),
+            ("=CEILING.MATH(-8.1, 2)", -8.0),
+            ("=CEILING.MATH(-5.5, 2, -1)", -6.0),
+            ("=FLOOR.MATH(24.3, 5)", 20.0),
+            ("=FLOOR.MATH(6.7)", 6.0),
+            ("=FLOOR.MATH(-8.1, 2)", -10.0),
+            ("=FLOOR.MATH(-5.5, 2, -1)", -4.0),
+            ("=CEILING.PRECISE(4.3)", 5.0),
+            ("=CEILING.PRECISE(-4.3)", -4.0),
+            ("=CEILING.PRECISE(4.3, -2)", 6.0),
+            ("=ISO.CEILING(-4.3, -2)", -4.0),
+            ("=FLOOR.PRECISE(3.2)", 3.0),
+            ("=FLOOR.PRECISE(-3.2, -1)", -4.0),
+            ("=FLOOR.PRECISE(3.2, -1)", 3.0),
+            ("=EVEN(1.5)", 2.0),
+            ("=EVEN(2)", 2.0),
+            ("=EVEN(-1)", -2.0),
+            ("=EVEN(0)", 0.0),
+            ("=ODD(1.5)", 3.0),
+            ("=ODD(3)", 3.0),
+            ("=ODD(2)", 3.0),
+            ("=ODD(-1)", -1.0),
+            ("=ODD(-2)", -3.0),
+            ("=ODD(0)", 1.0),
+            ("=QUOTIENT(5, 2)", 2.0),
+            ("=QUOTIENT(4.5, 3.1)", 1.0),
+            ("=QUOTIENT(-10, 3)", -3.0),
+            ("=GCD(24, 36)", 12.0),
+            ("=GCD(5, 0)", 5.0),
+            ("=GCD({12, 18}, 8.9)", 2.0),
+            ("=GCD(12, \"18\")", 6.0),
+            ("=LCM(TRUE, 4)", 4.0),
+            ("=LCM(5, 2)", 10.0),
+            ("=LCM(24, 36)", 72.0),
+            ("=LCM({3, 4}, 6)", 12.0),
+            ("=LCM(3, 0)", 0.0),
+            ("=FACT(5)", 120.0),
+            ("=FACT(1.9)", 1.0),
+            ("=FACT(0)", 1.0),
+            ("=FACT(170)", 7.257_415_615_307_999e306),
+            ("=FACTDOUBLE(6)", 48.0),
+            ("=FACTDOUBLE(7)", 105.0),
+            ("=FACTDOUBLE(0)", 1.0),
+            ("=FACTDOUBLE(300)", 8.154_414_069_380_594e307),
+            ("=COMBIN(8, 2)", 28.0),
+            ("=COMBIN(4.9, 2.1)", 6.0),
+            ("=COMBIN(5, 0)", 1.0),
+            ("=COMBIN(50, 25)", 126_410_606_437_752.0),
+            ("=COMBINA(4, 3)", 20.0),
+            ("=COMBINA(10, 3)", 220.0),
+            ("=COMBINA(0, 0)", 1.0),
+            ("=MULTINOMIAL(2, 3, 4)", 1260.0),
+            ("=MULTINOMIAL({1, 2}, 3)", 60.0),
+            ("=MULTINOMIAL(5)", 1.0),
+            ("=MULTINOMIAL(TRUE, \"2\")", 3.0),
+            ("=SERIESSUM(2, 1, 2, {1, 2, 3})", 114.0),
+            (
+                "=SERIESSUM(PI() / 4, 0, 2, {1, -0.5, 1 / 24, -1 / 720})",
+                0.707_103_214_822_845_7,
+            ),
+        ];
+        for (formula, expected) in numbers {
+            let formula = Formula::new(formula).unwrap();
+            assert_eq!(formula.parse().unwrap(), Expr::Number(expected), "{formula:?}");
+        }
+
+        // The LU decomposition has rounding errors, like Excel
+        let numbers = [
+            ("=MDETERM({1, 3, 8, 5; 1, 3, 6, 1; 1, 1, 1, 0; 7, 3, 10, 2})", 88.0),
+            ("=MDETERM({3, 6, 1; 1, 1, 0; 3, 10, 2})", 1.0),
+            ("=MDETERM({3, 6; 1, 1})", -3.0),
+            ("=MDETERM({1, 2, 3; 4, 5, 6; 7, 8, 9})", 0.0),
+            ("=MDETERM(5)", 5.0),
+            ("=SUMPRODUCT({3, 4; 8, 6; 1, 9}, {2, 7; 6, 7; 5, 3})", 156.0),
+            ("=SUMPRODUCT({1, 'a', 3}, {2, 2, 2})", 8.0),
+            ("=SUMPRODUCT({1, 2})", 3.0),
+            ("=SUMSQ(3, 4, {1, 2})", 30.0),
+            ("=SUMSQ({1, 2}, \"3\", TRUE)", 15.0),
+            ("=SUM(1, \"2\")", 3.0),
+            ("=SUM(1, TRUE)", 2.0),
+            ("=SUM({1, \"2\", TRUE})", 1.0),
+            ("=SUM(DATE(2020, 1, 1), 1)", 43832.0),
+            ("=SUMX2MY2({2, 3, 9, 1, 8, 7, 5}, {6, 5, 11, 7, 5, 4, 4})", -55.0),
+            ("=SUMX2PY2({2, 3, 9, 1, 8, 7, 5}, {6, 5, 11, 7, 5, 4, 4})", 521.0),
+            ("=SUMXMY2({2, 3, 9, 1, 8, 7, 5}, {6, 5, 11, 7, 5, 4, 4})", 79.0),
+            ("=SUMXMY2({1, 'a', 3}, {2, 5, 1})", 5.0),
+        ];
+        for (formula, expected) in numbers {
+            let formula = Formula::new(formula).unwrap();
+            match formula.parse().unwrap() {
+                Expr::Number(number) => assert!((number - expected).abs() < 1e-12, "{number} != {expected}"),
+                value => panic!("{value:?} is not {expected}"),
+            }
+        }
+
+        for formula in ["=SUM(A1:B2)", "=SUMSQ(A1:B2)"] {
+            match Formula::new(formula).unwrap().parse().unwrap() {
+                Expr::Number(number) => assert!(number == 0.0 && number.is_sign_positive(), "{formula} is {number}"),
+                value => panic!("{formula} is {value:?}"),
+            }
+        }
+
+        let arrays: [(&str, &[&[f64]]); 6] = [
+            ("=MMULT({1, 3; 7, 2}, {2, 0; 0, 2})", &[&[2.0, 6.0], &[14.0, 4.0]]),
+            ("=MMULT({1, 2, 3}, {4; 5; 6})", &[&[32.0]]),
+            ("=MINVERSE({4, -1; 2, 0})", &[&[0.0, 0.5], &[-1.0, 2.0]]),
+            (
+                "=MINVERSE({1, 2, 1; 3, 4, -1; 0, 2, 0})",
+                &[&[0.25, 0.25, -0.75], &[0.0, 0.0, 0.5], &[0.75, -0.25, -0.25]],
+            ),
+            ("=MINVERSE({1e-13, 0; 0, 1})", &[&[1e13, 0.0], &[0.0, 1.0]]),
+            ("=MUNIT(3)", &[&[1.0, 0.0, 0.0], &[0.0, 1.0, 0.0], &[0.0, 0.0, 1.0]]),
+        ];
+        for (formula, expected) in arrays {
+            let formula = Formula::new(formula).unwrap();
+            let rows = Evaluator::to_rows(formula.parse().unwrap());
+            assert_eq!(rows.len(), expected.len(), "{rows:?}");
+            for (row, expected) in rows.into_iter().zip(expected) {
+                assert_eq!(row.len(), expected.len(), "{row:?}");
+                for (item, expected) in row.into_iter().zip(*expected) {
+                    match item {
+                        Expr::Number(number) => {
+                            assert!(
+                                (number - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+                                "{number} != {expected}"
+                            );
+                        }
+                        item => panic!("{item:?} is not {expected}"),
+                    }
+                }
+            }
+        }
+
+        let errors = [
+            ("=MROUND(5, -2)", ErrorKind::Num),
+            ("=CEILING(2.5, -2)", ErrorKind::Num),
+            ("=FLOOR(2.5, -2)", ErrorKind::Num),
+            ("=FLOOR(2.5, 0)", ErrorKind::DivZero),
+            ("=QUOTIENT(1, 0)", ErrorKind::DivZero),
+            ("=GCD(-1, 2)", ErrorKind::Num),
+            ("=GCD(12, \"a\")", ErrorKind::Value),
+            ("=SUM(1, \"a\")", ErrorKind::Value),
+            ("=SUMSQ(\"a\")", ErrorKind::Value),
+            ("=GCD(2^53 + 2, 2)", ErrorKind::Num),
+            ("=LCM(2^40, 3^20)", ErrorKind::Num),
+            ("=FACT(-1)", ErrorKind::Num),
+            ("=FACT(171)", ErrorKind::Num),
+            ("=FACTDOUBLE(302)", ErrorKind::Num),
+            ("=COMBIN(2, 3)", ErrorKind::Num),
+            ("=COMBIN(1030, 515)", ErrorKind::Num),
+            ("=COMBINA(-1, 2)", ErrorKind::Num),
+            ("=MULTINOMIAL(1, -2)", ErrorKind::Num),
+            ("=MULTINOMIAL(100, 100, 100, 100, 100)", ErrorKind::Num),
+            ("=MULTINOMIAL(1, \"a\")", ErrorKind::Value),
+            ("=SERIESSUM(2, 1, 2, {1, 'a'})", ErrorKind::Value),
+            ("=SERIESSUM(10, 1, 400, {1, 1})", ErrorKind::Num),
+            ("=MMULT({1, 2}, {1, 2})", ErrorKind::Value),
+            ("=MMULT({1, 'a'}, {1; 2})", ErrorKind::Value),
+            ("=MDETERM({1, 2})", ErrorKind::Value),
+            ("=MINVERSE({1, 2; 2, 4})", ErrorKind::Num),
+            ("=MINVERSE({1, 2, 3; 4, 5, 6; 7, 8, 9})", ErrorKind::Num),
+            ("=MUNIT(0)", ErrorKind::Value),
+            ("=MUNIT(16385)", ErrorKind::Value),
+            ("=MUNIT(1e9)", ErrorKind::Value),
+            ("=SUMPRODUCT({1, 2}, {1, 2, 3})", ErrorKind::Value),
+            ("=SUMPRODUCT({1, 2}, {1; 2})", ErrorKind::Value),
+            ("=SUMX2MY2({1, 2}, {1})", ErrorKind::NA),
+        ];
+        for (formula, kind) in errors {
+            let formula = Formula::new(formula).unwrap();
+            assert_eq!(formula.parse().unwrap(), Expr::Error(kind));
+        }
+
+        // The numbers which overflow are errors in the arrays too
+        let formula = Formula::new("=MMULT({1e200}, {1e200})").unwrap();
+        assert_eq!(formula.parse().unwrap(), Expr::Array(vec![Expr::Error(ErrorKind::Num)]));
     }
 }
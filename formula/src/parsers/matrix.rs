@@ -0,0 +1,302 @@
+use std::ops::{Index, IndexMut};
+
+/// The relative size of the part of a column, which is not a combination of the previous columns,
+/// under which the column is dependent on them
+const TOLERANCE: f64 = 1e-12;
+/// The size of a pivot of the LU decomposition, relative to the largest number of its row,
+/// under which the row is a combination of the previous rows, so the matrix is singular
+const PIVOT_TOLERANCE: f64 = 1e-12;
+
+/// A dense matrix of numbers, which is stored row by row
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Matrix {
+    rows: usize,
+    columns: usize,
+    values: Vec<f64>,
+}
+
+impl Matrix {
+    pub(crate) fn zeros(rows: usize, columns: usize) -> Self {
+        Self {
+            rows,
+            columns,
+            values: vec![0.0; rows * columns],
+        }
+    }
+
+    /// Create a matrix from its rows, which must have the same length
+    pub(crate) fn from_rows(rows: &[Vec<f64>]) -> Self {
+        let columns = rows.first().map_or(0, Vec::len);
+        Self {
+            rows: rows.len(),
+            columns,
+            values: rows.concat(),
+        }
+    }
+
+    pub(crate) fn identity(size: usize) -> Self {
+        let mut identity = Self::zeros(size, size);
+        for i in 0..size {
+            identity[(i, i)] = 1.0;
+        }
+        identity
+    }
+
+    pub(crate) fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub(crate) fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub(crate) fn row(&self, row: usize) -> &[f64] {
+        &self.values[row * self.columns..(row + 1) * self.columns]
+    }
+
+    pub(crate) fn column(&self, column: usize) -> Vec<f64> {
+        (0..self.rows).map(|row| self[(row, column)]).collect()
+    }
+
+    /// Return the product of two matrices, or `None` if the columns of the first one aren't the rows of the second one
+    pub(crate) fn multiply(&self, other: &Self) -> Option<Self> {
+        if self.columns != other.rows {
+            return None;
+        }
+
+        let mut product = Self::zeros(self.rows, other.columns);
+        for i in 0..self.rows {
+            for j in 0..other.columns {
+                product[(i, j)] = (0..self.columns).map(|k| self[(i, k)] * other[(k, j)]).sum();
+            }
+        }
+        Some(product)
+    }
+
+    /// Return the LU decomposition of a square matrix, by the Gaussian elimination with the partial pivoting
+    pub(crate) fn lu(&self) -> Lu {
+        let size = self.rows;
+        let mut factors = self.clone();
+        let mut permutation = (0..size).collect::<Vec<_>>();
+        let mut sign = 1.0;
+        let mut singular = false;
+        for k in 0..size {
+            let pivot = (k..size)
+                .max_by(|a, b| factors[(*a, k)].abs().total_cmp(&factors[(*b, k)].abs()))
+                .unwrap_or(k);
+            if pivot != k {
+                for j in 0..size {
+                    let value = factors[(k, j)];
+                    factors[(k, j)] = factors[(pivot, j)];
+                    factors[(pivot, j)] = value;
+                }
+                permutation.swap(k, pivot);
+                sign = -sign;
+            }
+
+            let scale = self
+                .row(permutation[k])
+                .iter()
+                .fold(0.0, |scale: f64, value| scale.max(value.abs()));
+            if factors[(k, k)].abs() <= scale * PIVOT_TOLERANCE {
+                singular = true;
+                continue;
+            }
+            for i in k + 1..size {
+                let factor = factors[(i, k)] / factors[(k, k)];
+                factors[(i, k)] = factor;
+                for j in k + 1..size {
+                    factors[(i, j)] -= factor * factors[(k, j)];
+                }
+            }
+        }
+
+        Lu {
+            factors,
+            permutation,
+            sign,
+            singular,
+        }
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = f64;
+
+    fn index(&self, (row, column): (usize, usize)) -> &f64 {
+        &self.values[row * self.columns + column]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut f64 {
+        &mut self.values[row * self.columns + column]
+    }
+}
+
+/// The LU decomposition `P A = L U` of a square matrix, where `P` permutes the rows, `L` is lower triangular
+/// with ones on its diagonal, and `U` is upper triangular
+pub(crate) struct Lu {
+    /// `L` below the diagonal, and `U` on and above it
+    factors: Matrix,
+    /// The row of the matrix of each row of the factors
+    permutation: Vec<usize>,
+    /// The sign of the permutation, which is the sign of the determinant of `P`
+    sign: f64,
+    /// Whether a row is a combination of the previous rows, by the rounding errors too
+    singular: bool,
+}
+
+impl Lu {
+    /// Return the determinant, which is zero if the matrix is singular, like an exact calculation
+    pub(crate) fn determinant(&self) -> f64 {
+        if self.singular {
+            return 0.0;
+        }
+        (0..self.factors.rows).fold(self.sign, |determinant, i| determinant * self.factors[(i, i)])
+    }
+
+    /// Return the inverse, whose columns are the solutions of `L U x = P e`, or `None` if the matrix is singular
+    pub(crate) fn inverse(&self) -> Option<Matrix> {
+        if self.singular {
+            return None;
+        }
+
+        let size = self.factors.rows;
+        let mut inverse = Matrix::zeros(size, size);
+        for column in 0..size {
+            let mut x = self
+                .permutation
+                .iter()
+                .map(|row| if *row == column { 1.0 } else { 0.0 })
+                .collect::<Vec<_>>();
+            for i in 0..size {
+                x[i] -= (0..i).map(|j| self.factors[(i, j)] * x[j]).sum::<f64>();
+            }
+            for i in (0..size).rev() {
+                let sum = (i + 1..size).map(|j| self.factors[(i, j)] * x[j]).sum::<f64>();
+                x[i] = (x[i] - sum) / self.factors[(i, i)];
+            }
+            for (row, value) in x.into_iter().enumerate() {
+                inverse[(row, column)] = value;
+            }
+        }
+        Some(inverse)
+    }
+}
+
+/// The least squares solution of `y = X b + a`, where `a` is the intercept, if it's fitted
+#[derive(Debug)]
+pub(crate) struct LeastSquares {
+    /// The coefficients of the columns, which are zero for the columns that are combinations of the previous ones,
+    /// like Excel
+    pub(crate) coefficients: Vec<f64>,
+    /// The intercept, which is zero if it's not fitted
+    pub(crate) intercept: f64,
+    /// The variances of the coefficients, divided by the variance of the residuals
+    pub(crate) variances: Vec<f64>,
+    /// The variance of the intercept, divided by the variance of the residuals
+    pub(crate) intercept_variance: f64,
+    /// The number of the columns which are not combinations of the previous ones
+    pub(crate) rank: usize,
+}
+
+impl LeastSquares {
+    /// Return the fitted value of an observation
+    pub(crate) fn predict(&self, x: &[f64]) -> f64 {
+        self.intercept + dot(x, &self.coefficients)
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Return the least squares solution of `y = X b + a`, where each row of `X` is an observation of `y`.
+///
+/// The columns are centered, if the intercept is fitted, and orthonormalized by the Gram-Schmidt process,
+/// so `X = Q R`, and `b` is the solution of `R b = Q' y`.
+/// The columns which are combinations of the previous ones are skipped, so the solution is found for any data.
+pub(crate) fn least_squares(x: &Matrix, y: &[f64], intercept: bool) -> LeastSquares {
+    let n = y.len() as f64;
+    let mean = |values: &[f64]| if intercept { values.iter().sum::<f64>() / n } else { 0.0 };
+    let mean_y = mean(y);
+    let y = y.iter().map(|y| y - mean_y).collect::<Vec<_>>();
+
+    let mut means = Vec::with_capacity(x.columns());
+    let mut independent = Vec::new();
+    let mut q: Vec<Vec<f64>> = Vec::new();
+    let mut r = Matrix::zeros(x.columns(), x.columns());
+    for j in 0..x.columns() {
+        let column = x.column(j);
+        let norm = dot(&column, &column).sqrt();
+        let column_mean = mean(&column);
+        means.push(column_mean);
+
+        // The projections are removed twice, which keeps the columns orthogonal despite the rounding errors
+        let mut residual = column.iter().map(|value| value - column_mean).collect::<Vec<_>>();
+        let mut projections = vec![0.0; q.len()];
+        for _ in 0..2 {
+            for (projection, q) in projections.iter_mut().zip(&q) {
+                let p = dot(q, &residual);
+                *projection += p;
+                residual.iter_mut().zip(q).for_each(|(value, q)| *value -= p * q);
+            }
+        }
+        let length = dot(&residual, &residual).sqrt();
+        if length <= norm * TOLERANCE {
+            continue;
+        }
+
+        let rank = independent.len();
+        for (i, projection) in projections.into_iter().enumerate() {
+            r[(i, rank)] = projection;
+        }
+        r[(rank, rank)] = length;
+        q.push(residual.into_iter().map(|value| value / length).collect());
+        independent.push(j);
+    }
+
+    // R b = Q' y by the back substitution, and the inverse of R, row by row from the last one
+    let rank = independent.len();
+    let mut solution = q.iter().map(|q| dot(q, &y)).collect::<Vec<_>>();
+    let mut inverse = Matrix::zeros(rank, rank);
+    for i in (0..rank).rev() {
+        let sum = (i + 1..rank).map(|j| r[(i, j)] * solution[j]).sum::<f64>();
+        solution[i] = (solution[i] - sum) / r[(i, i)];
+        inverse[(i, i)] = 1.0 / r[(i, i)];
+        for j in i + 1..rank {
+            let sum = (i + 1..=j).map(|k| r[(i, k)] * inverse[(k, j)]).sum::<f64>();
+            inverse[(i, j)] = -sum / r[(i, i)];
+        }
+    }
+
+    // The covariance of the coefficients is (X' X)^-1 = R^-1 R^-1'
+    let mut coefficients = vec![0.0; x.columns()];
+    let mut variances = vec![0.0; x.columns()];
+    for (i, &column) in independent.iter().enumerate() {
+        coefficients[column] = solution[i];
+        variances[column] = inverse.row(i).iter().map(|value| value * value).sum();
+    }
+    let (intercept, intercept_variance) = if intercept {
+        // The intercept is `mean(y) - mean(X) b`, and its variance is `1 / n + |R^-1' mean(X)'|^2`
+        let intercept = mean_y - dot(&means, &coefficients);
+        let projection = (0..rank)
+            .map(|i| {
+                let sum = (0..=i).map(|k| inverse[(k, i)] * means[independent[k]]).sum::<f64>();
+                sum * sum
+            })
+            .sum::<f64>();
+        (intercept, 1.0 / n + projection)
+    } else {
+        (0.0, 0.0)
+    };
+
+    LeastSquares {
+        coefficients,
+        intercept,
+        variances,
+        intercept_variance,
+        rank,
+    }
+}
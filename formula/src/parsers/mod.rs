@@ -1,11 +1,19 @@
 pub(crate) mod basic;
+pub(crate) mod complex;
+pub(crate) mod criteria;
 pub(crate) mod date_and_time;
+pub(crate) mod day_count;
 pub(crate) mod engineering;
+pub(crate) mod ets;
 pub(crate) mod financial;
-mod helper;
+pub(crate) mod helper;
+pub(crate) mod information;
 pub(crate) mod logical;
+pub(crate) mod lookup_and_reference;
 pub(crate) mod math;
+pub(crate) mod matrix;
 pub(crate) mod operator;
+pub(crate) mod special;
 pub(crate) mod statistical;
 pub(crate) mod text;
 pub(crate) mod web;
@@ -1,84 +1,111 @@
-use crate::{error::Error, Expr, Formula, Result, Rule};
-use pest::iterators::Pair;
+use crate::{ast::Node, error::Error, evaluator::Evaluator, ErrorKind, Expr, Result, Rule};
+use pest::pratt_parser::{Assoc, Op, PrattParser};
+use std::sync::OnceLock;
+
+/// The operators of an expression, from the lowest to the highest precedence, like Excel.
+/// Note that Excel evaluates the negation before the exponentiation, so `-2^2` is `4`.
+pub(crate) fn pratt_parser() -> &'static PrattParser<Rule> {
+    static PRATT_PARSER: OnceLock<PrattParser<Rule>> = OnceLock::new();
+    PRATT_PARSER.get_or_init(|| {
+        PrattParser::new()
+            .op(Op::infix(Rule::eq_op, Assoc::Left)
+                | Op::infix(Rule::ne_op, Assoc::Left)
+                | Op::infix(Rule::lt_op, Assoc::Left)
+                | Op::infix(Rule::gt_op, Assoc::Left)
+                | Op::infix(Rule::lte_op, Assoc::Left)
+                | Op::infix(Rule::gte_op, Assoc::Left))
+            .op(Op::infix(Rule::concat_op, Assoc::Left))
+            .op(Op::infix(Rule::add_op, Assoc::Left) | Op::infix(Rule::sub_op, Assoc::Left))
+            .op(Op::infix(Rule::mul_op, Assoc::Left) | Op::infix(Rule::div_op, Assoc::Left))
+            .op(Op::infix(Rule::pow_op, Assoc::Left))
+            .op(Op::postfix(Rule::percent_op))
+            .op(Op::prefix(Rule::neg_op) | Op::prefix(Rule::pos_op))
+    })
+}
 
-impl Formula<'_> {
-    pub(crate) fn parse_add(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+impl Evaluator<'_> {
+    pub(crate) fn parse_add(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
+        Self::add(operand1, operand2, &rule_name)
+    }
 
-        let res = match (operand1, operand2) {
-            (Expr::Number(operand1), Expr::Number(operand2)) => operand1 + operand2,
-            _ => return Err(Error::Parser(rule_name)),
-        };
-        Ok(Expr::Number(res))
+    pub(crate) fn add(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
+        let operand1 = Self::to_number(operand1, rule_name)?;
+        let operand2 = Self::to_number(operand2, rule_name)?;
+        Ok(Expr::Number(operand1 + operand2))
     }
 
-    pub(crate) fn parse_sub(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_sub(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
+        Self::sub(operand1, operand2, &rule_name)
+    }
 
-        let res = match (operand1, operand2) {
-            (Expr::Number(operand1), Expr::Number(operand2)) => operand1 - operand2,
-            _ => return Err(Error::Parser(rule_name)),
-        };
-        Ok(Expr::Number(res))
+    pub(crate) fn sub(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
+        let operand1 = Self::to_number(operand1, rule_name)?;
+        let operand2 = Self::to_number(operand2, rule_name)?;
+        Ok(Expr::Number(operand1 - operand2))
     }
 
-    pub(crate) fn parse_mul(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_mul(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
+        Self::mul(operand1, operand2, &rule_name)
+    }
 
-        let res = match (operand1, operand2) {
-            (Expr::Number(operand1), Expr::Number(operand2)) => operand1 * operand2,
-            _ => return Err(Error::Parser(rule_name)),
-        };
-        Ok(Expr::Number(res))
+    pub(crate) fn mul(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
+        let operand1 = Self::to_number(operand1, rule_name)?;
+        let operand2 = Self::to_number(operand2, rule_name)?;
+        Ok(Expr::Number(operand1 * operand2))
     }
 
-    pub(crate) fn parse_div(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_div(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
+        Self::div(operand1, operand2, &rule_name)
+    }
 
-        let res = match (operand1, operand2) {
-            (Expr::Number(operand1), Expr::Number(operand2)) => {
-                if operand2 == 0.0 {
-                    Expr::Null
-                } else {
-                    Expr::Number(operand1 / operand2)
-                }
-            }
-            _ => return Err(Error::Parser(rule_name)),
-        };
-        Ok(res)
+    pub(crate) fn div(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
+        let operand1 = Self::to_number(operand1, rule_name)?;
+        let operand2 = Self::to_number(operand2, rule_name)?;
+        if operand2 == 0.0 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        Ok(Expr::Number(operand1 / operand2))
     }
 
-    pub(crate) fn parse_pow(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_pow(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
+        Self::pow(operand1, operand2, &rule_name)
+    }
 
-        let res = match (operand1, operand2) {
-            (Expr::Number(operand1), Expr::Number(operand2)) => operand1.powf(operand2),
-            _ => return Err(Error::Parser(rule_name)),
-        };
-        Ok(Expr::Number(res))
+    pub(crate) fn pow(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
+        let operand1 = Self::to_number(operand1, rule_name)?;
+        let operand2 = Self::to_number(operand2, rule_name)?;
+        Ok(Expr::Number(operand1.powf(operand2)))
     }
 
-    pub(crate) fn parse_eq(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_eq(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
+        Self::eq(operand1, operand2, &rule_name)
+    }
 
+    pub(crate) fn eq(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
         let res = match (operand1, operand2) {
             (Expr::Number(operand1), Expr::Number(operand2)) => operand1 == operand2,
             (Expr::String(operand1), Expr::String(operand2)) => operand1 == operand2,
@@ -86,17 +113,20 @@ impl Formula<'_> {
             (Expr::Datetime(operand1), Expr::Datetime(operand2)) => operand1 == operand2,
             (Expr::Date(operand1), Expr::Date(operand2)) => operand1 == operand2,
             (Expr::Bool(operand1), Expr::Bool(operand2)) => operand1 == operand2,
-            _ => return Err(Error::Parser(rule_name)),
+            _ => return Err(Error::Parser(rule_name.to_owned())),
         };
         Ok(Expr::Bool(res))
     }
 
-    pub(crate) fn parse_ne(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_ne(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
+        Self::ne(operand1, operand2, &rule_name)
+    }
 
+    pub(crate) fn ne(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
         let res = match (operand1, operand2) {
             (Expr::Number(operand1), Expr::Number(operand2)) => (operand1 - operand2).abs() > 0.000_000_1,
             (Expr::String(operand1), Expr::String(operand2)) => operand1 != operand2,
@@ -104,101 +134,122 @@ impl Formula<'_> {
             (Expr::Datetime(operand1), Expr::Datetime(operand2)) => operand1 != operand2,
             (Expr::Date(operand1), Expr::Date(operand2)) => operand1 != operand2,
             (Expr::Bool(operand1), Expr::Bool(operand2)) => operand1 != operand2,
-            _ => return Err(Error::Parser(rule_name)),
+            _ => return Err(Error::Parser(rule_name.to_owned())),
         };
         Ok(Expr::Bool(res))
     }
 
-    pub(crate) fn parse_gt(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_gt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
+        Self::gt(operand1, operand2, &rule_name)
+    }
 
+    pub(crate) fn gt(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
         let res = match (operand1, operand2) {
             (Expr::Number(operand1), Expr::Number(operand2)) => operand1 > operand2,
             (Expr::String(operand1), Expr::String(operand2)) => operand1 > operand2,
             (Expr::Time(operand1), Expr::Time(operand2)) => operand1 > operand2,
             (Expr::Datetime(operand1), Expr::Datetime(operand2)) => operand1 > operand2,
             (Expr::Date(operand1), Expr::Date(operand2)) => operand1 > operand2,
-            _ => return Err(Error::Parser(rule_name)),
+            _ => return Err(Error::Parser(rule_name.to_owned())),
         };
         Ok(Expr::Bool(res))
     }
 
-    pub(crate) fn parse_lt(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_lt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
+        Self::lt(operand1, operand2, &rule_name)
+    }
 
+    pub(crate) fn lt(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
         let res = match (operand1, operand2) {
             (Expr::Number(operand1), Expr::Number(operand2)) => operand1 < operand2,
             (Expr::String(operand1), Expr::String(operand2)) => operand1 < operand2,
             (Expr::Time(operand1), Expr::Time(operand2)) => operand1 < operand2,
             (Expr::Datetime(operand1), Expr::Datetime(operand2)) => operand1 < operand2,
             (Expr::Date(operand1), Expr::Date(operand2)) => operand1 < operand2,
-            _ => return Err(Error::Parser(rule_name)),
+            _ => return Err(Error::Parser(rule_name.to_owned())),
         };
         Ok(Expr::Bool(res))
     }
 
-    pub(crate) fn parse_gte(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_gte(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
+        Self::gte(operand1, operand2, &rule_name)
+    }
 
+    pub(crate) fn gte(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
         let res = match (operand1, operand2) {
             (Expr::Number(operand1), Expr::Number(operand2)) => operand1 >= operand2,
             (Expr::String(operand1), Expr::String(operand2)) => operand1 >= operand2,
             (Expr::Time(operand1), Expr::Time(operand2)) => operand1 >= operand2,
             (Expr::Datetime(operand1), Expr::Datetime(operand2)) => operand1 >= operand2,
             (Expr::Date(operand1), Expr::Date(operand2)) => operand1 >= operand2,
-            _ => return Err(Error::Parser(rule_name)),
+            _ => return Err(Error::Parser(rule_name.to_owned())),
         };
         Ok(Expr::Bool(res))
     }
 
-    pub(crate) fn parse_lte(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand1 = Self::get_formula(&mut args, &rule_name)?;
-        let operand2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_lte(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand1 = self.get_formula(&mut args, &rule_name)?;
+        let operand2 = self.get_formula(&mut args, &rule_name)?;
+        Self::lte(operand1, operand2, &rule_name)
+    }
 
+    pub(crate) fn lte(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
         let res = match (operand1, operand2) {
             (Expr::Number(operand1), Expr::Number(operand2)) => operand1 <= operand2,
             (Expr::String(operand1), Expr::String(operand2)) => operand1 <= operand2,
             (Expr::Time(operand1), Expr::Time(operand2)) => operand1 <= operand2,
             (Expr::Datetime(operand1), Expr::Datetime(operand2)) => operand1 <= operand2,
             (Expr::Date(operand1), Expr::Date(operand2)) => operand1 <= operand2,
-            _ => return Err(Error::Parser(rule_name)),
+            _ => return Err(Error::Parser(rule_name.to_owned())),
         };
         Ok(Expr::Bool(res))
     }
 
-    pub(crate) fn parse_percent(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_percent(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
+        Self::percent(operand, &rule_name)
+    }
 
-        let res = match operand {
-            Expr::Number(operand) => operand / 100.0,
-            _ => return Err(Error::Parser(rule_name)),
-        };
-        Ok(Expr::Number(res))
+    pub(crate) fn percent(operand: Expr, rule_name: &str) -> Result<Expr> {
+        Ok(Expr::Number(Self::to_number(operand, rule_name)? / 100.0))
     }
 
-    pub(crate) fn parse_negate(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let operand = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_negate(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let operand = self.get_formula(&mut args, &rule_name)?;
+        Self::negate(operand, &rule_name)
+    }
+
+    pub(crate) fn negate(operand: Expr, rule_name: &str) -> Result<Expr> {
+        Ok(Expr::Number(-Self::to_number(operand, rule_name)?))
+    }
 
-        let res = match operand {
-            Expr::Number(operand) => -operand,
-            _ => return Err(Error::Parser(rule_name)),
+    pub(crate) fn concat(operand1: Expr, operand2: Expr, rule_name: &str) -> Result<Expr> {
+        let to_text = |operand| match operand {
+            Expr::String(operand) => Ok(operand),
+            Expr::Number(operand) => Ok(operand.to_string()),
+            Expr::Bool(operand) => Ok(operand.to_string().to_uppercase()),
+            Expr::Null => Ok(String::new()),
+            _ => Err(Error::Parser(rule_name.to_owned())),
         };
-        Ok(Expr::Number(res))
+        Ok(Expr::String(to_text(operand1)? + &to_text(operand2)?))
     }
 }
 
@@ -228,7 +279,7 @@ mod tests {
 
         let formula = Formula::new("=F.DIV(10,0)").unwrap();
         let value = formula.parse().unwrap();
-        assert_eq!(value, Expr::Null);
+        assert_eq!(value, Expr::Error(ErrorKind::DivZero));
 
         let formula = Formula::new("=F.MUL(5,6)").unwrap();
         let value = formula.parse().unwrap();
@@ -269,5 +320,86 @@ mod tests {
         let formula = Formula::new("=F.NEGATE(5)").unwrap();
         let value = formula.parse().unwrap();
         assert_eq!(value, Expr::Number(-5.0));
+
+        let formula = Formula::new("=1+2*3").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Number(7.0));
+
+        let formula = Formula::new("=(1+2)*3").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Number(9.0));
+
+        let formula = Formula::new("=10-4-3").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Number(3.0));
+
+        let formula = Formula::new("=2^3^2").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Number(64.0));
+
+        let formula = Formula::new("=-2^2").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Number(4.0));
+
+        let formula = Formula::new("=0-2^2").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Number(-4.0));
+
+        let formula = Formula::new("=+5 - -5").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Number(10.0));
+
+        let formula = Formula::new("=50%*2").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Number(1.0));
+
+        let formula = Formula::new("=1/0").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Error(ErrorKind::DivZero));
+
+        let formula = Formula::new("='a'&1+1&TRUE").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::String("a2TRUE".to_string()));
+
+        let formula = Formula::new("=1+1=2").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Bool(true));
+
+        let formula = Formula::new("=1<>1").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Bool(false));
+
+        let formula = Formula::new("=AND(1>3, 1<3)").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Bool(false));
+
+        let formula = Formula::new("=OR(1>=3, 3<=3)").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Bool(true));
+
+        let formula = Formula::new("=SUM(2-1, (2))*F.ADD(1, 2*(1+1))").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(value, Expr::Number(15.0));
+
+        let formulas = [
+            ("=1+\"2\"", Expr::Number(3.0)),
+            ("=\"3\"*\"4\"", Expr::Number(12.0)),
+            ("=TRUE+1", Expr::Number(2.0)),
+            ("=-\"2\"", Expr::Number(-2.0)),
+            ("=2^\"2\"", Expr::Number(4.0)),
+            ("=\"50\"%", Expr::Number(0.5)),
+            ("=DATE(2020,1,1)+1", Expr::Number(43832.0)),
+            ("=DATE(2020,1,3)-DATE(2020,1,1)", Expr::Number(2.0)),
+            ("=1+\"a\"", Expr::Error(ErrorKind::Value)),
+            ("=1/\"0\"", Expr::Error(ErrorKind::DivZero)),
+            ("=1+#N/A", Expr::Error(ErrorKind::NA)),
+        ];
+        for (formula, expected) in formulas {
+            let value = Formula::new(formula).unwrap().parse().unwrap();
+            assert_eq!(value, expected, "{formula}");
+        }
+
+        assert!(Formula::new("=(1+2").is_err());
+        assert!(Formula::new("=1 2").is_err());
     }
 }
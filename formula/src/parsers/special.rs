@@ -0,0 +1,514 @@
+use std::f64::consts::PI;
+
+/// The Euler-Mascheroni constant
+const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+/// The maximum number of the iterations of the series and the continued fractions
+const MAX_ITERATIONS: usize = 10_000;
+/// A number which replaces zero in the continued fractions, so they don't divide by zero
+const TINY: f64 = 1e-300;
+
+/// The `g` parameter of the Lanczos approximation of the gamma function
+const LANCZOS_G: f64 = 7.0;
+/// The coefficients of the Lanczos approximation, for `g = 7` and `n = 9`, which is accurate to about `1e-15`
+const LANCZOS_COEFFICIENTS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+/// The values of the backward recurrence of the Bessel functions are rescaled by this factor,
+/// so they don't overflow
+const RESCALE: f64 = 1e250;
+/// The natural logarithm of the smallest positive number, where the Bessel functions underflow
+const LN_MIN_POSITIVE: f64 = -745.0;
+
+fn lanczos_sum(x: f64) -> f64 {
+    LANCZOS_COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(LANCZOS_COEFFICIENTS[0], |sum, (i, coefficient)| {
+            sum + coefficient / (x + i as f64 + 1.0)
+        })
+}
+
+/// Return the gamma function, which is exact for the small positive integers, like `GAMMA(5) = 24`.
+/// It's `NaN` for zero and the negative integers, and infinite if it overflows.
+pub(crate) fn gamma(x: f64) -> f64 {
+    if x <= 0.0 && x.fract() == 0.0 {
+        return f64::NAN;
+    }
+    if x.fract() == 0.0 && x <= 171.0 {
+        return (2..x as u32).fold(1.0, |product, k| product * f64::from(k));
+    }
+    if x < 0.5 {
+        // The reflection formula
+        return PI / ((PI * x).sin() * gamma(1.0 - x));
+    }
+    let x = x - 1.0;
+    let t = x + LANCZOS_G + 0.5;
+    // The power is split, so it doesn't overflow before it's multiplied by `e^-t`
+    let power = t.powf((x + 0.5) / 2.0);
+    (2.0 * PI).sqrt() * power * (power * (-t).exp()) * lanczos_sum(x)
+}
+
+/// Return the natural logarithm of the gamma function of a positive number
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        // The reflection formula
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + LANCZOS_G + 0.5;
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + lanczos_sum(x).ln()
+}
+
+/// Return the regularized lower incomplete gamma function `P(a, x)`, for a positive `a`
+pub(crate) fn regularized_gamma_p(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        0.0
+    } else if x < a + 1.0 {
+        gamma_series(a, x)
+    } else {
+        1.0 - gamma_continued_fraction(a, x)
+    }
+}
+
+/// Return the regularized upper incomplete gamma function `Q(a, x) = 1 - P(a, x)`, for a positive `a`,
+/// which is accurate for the small values too
+pub(crate) fn regularized_gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        1.0
+    } else if x < a + 1.0 {
+        1.0 - gamma_series(a, x)
+    } else {
+        gamma_continued_fraction(a, x)
+    }
+}
+
+/// Return `P(a, x)` by its series, which converges fast for `x < a + 1`
+fn gamma_series(a: f64, x: f64) -> f64 {
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    let mut denominator = a;
+    for _ in 0..MAX_ITERATIONS {
+        denominator += 1.0;
+        term *= x / denominator;
+        sum += term;
+        if term.abs() < sum.abs() * f64::EPSILON {
+            break;
+        }
+    }
+    sum * (a * x.ln() - x - ln_gamma(a)).exp()
+}
+
+/// Return `Q(a, x)` by its continued fraction, by the modified Lentz's method, which converges fast for `x >= a + 1`
+fn gamma_continued_fraction(a: f64, x: f64) -> f64 {
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut fraction = d;
+    for i in 1..MAX_ITERATIONS {
+        let i = i as f64;
+        let an = -i * (i - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.0).abs() < f64::EPSILON {
+            break;
+        }
+    }
+    (a * x.ln() - x - ln_gamma(a)).exp() * fraction
+}
+
+/// Return the error function, by `erf(x) = P(1/2, x^2)`
+pub(crate) fn erf(x: f64) -> f64 {
+    let value = regularized_gamma_p(0.5, x * x);
+    if x < 0.0 {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Return the complementary error function, `1 - erf(x)`, which is accurate for the large numbers too
+pub(crate) fn erfc(x: f64) -> f64 {
+    if x < 0.0 {
+        1.0 + regularized_gamma_p(0.5, x * x)
+    } else {
+        regularized_gamma_q(0.5, x * x)
+    }
+}
+
+/// Return `x * ln(y)`, which is zero if `x` is zero, even if `y` is zero too, like the powers of the probabilities
+pub(crate) fn x_ln_y(x: f64, y: f64) -> f64 {
+    if x == 0.0 {
+        0.0
+    } else {
+        x * y.ln()
+    }
+}
+
+/// Return the natural logarithm of the beta function of the positive numbers
+pub(crate) fn ln_beta(a: f64, b: f64) -> f64 {
+    ln_gamma(a) + ln_gamma(b) - ln_gamma(a + b)
+}
+
+/// Return the regularized incomplete beta function `I_x(a, b)`, for the positive `a` and `b`.
+///
+/// `1 - I_x(a, b)` is `I_(1-x)(b, a)`, which is accurate for the small values too, if `1 - x` is exact.
+pub(crate) fn regularized_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front = (a * x.ln() + b * (-x).ln_1p() - ln_beta(a, b)).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Return the continued fraction of `I_x(a, b)`, by the modified Lentz's method, which converges fast
+/// for `x < (a + 1) / (a + b + 2)`
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let clamp = |value: f64| if value.abs() < TINY { TINY } else { value };
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - (a + b) * x / (a + 1.0));
+    let mut fraction = d;
+    for m in 1..MAX_ITERATIONS {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / clamp(1.0 + even * d);
+        c = clamp(1.0 + even / c);
+        fraction *= d * c;
+
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / clamp(1.0 + odd * d);
+        c = clamp(1.0 + odd / c);
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.0).abs() < f64::EPSILON {
+            break;
+        }
+    }
+    fraction
+}
+
+/// Return the inverse of the standard normal cumulative distribution, by the algorithm AS 241 of Wichura,
+/// which is accurate to about `1e-16`
+pub(crate) fn inverse_standard_normal(p: f64) -> f64 {
+    let q = p - 0.5;
+    if q.abs() <= 0.425 {
+        let r = 0.180_625 - q * q;
+        let numerator = polynomial(
+            r,
+            &[
+                3.387_132_872_796_366_5,
+                133.141_667_891_784_38,
+                1_971.590_950_306_551_3,
+                13_731.693_765_509_46,
+                45_921.953_931_549_87,
+                67_265.770_927_008_7,
+                33_430.575_583_588_13,
+                2_509.080_928_730_122_7,
+            ],
+        );
+        let denominator = polynomial(
+            r,
+            &[
+                1.0,
+                42.313_330_701_600_91,
+                687.187_007_492_057_9,
+                5_394.196_021_424_751,
+                21_213.794_301_586_597,
+                39_307.895_800_092_71,
+                28_729.085_735_721_943,
+                5_226.495_278_852_546,
+            ],
+        );
+        return q * numerator / denominator;
+    }
+
+    let r = (-(if q < 0.0 { p } else { 1.0 - p }).ln()).sqrt();
+    let value = if r <= 5.0 {
+        let r = r - 1.6;
+        polynomial(
+            r,
+            &[
+                1.423_437_110_749_683_5,
+                4.630_337_846_156_546,
+                5.769_497_221_460_691,
+                3.647_848_324_763_204_5,
+                1.270_458_252_452_368_4,
+                0.241_780_725_177_450_6,
+                0.022_723_844_989_269_184,
+                7.745_450_142_783_414e-4,
+            ],
+        ) / polynomial(
+            r,
+            &[
+                1.0,
+                2.053_191_626_637_759,
+                1.676_384_830_183_803_8,
+                0.689_767_334_985_1,
+                0.148_103_976_427_480_08,
+                0.015_198_666_563_616_457,
+                5.475_938_084_995_345e-4,
+                1.050_750_071_644_416_9e-9,
+            ],
+        )
+    } else {
+        let r = r - 5.0;
+        polynomial(
+            r,
+            &[
+                6.657_904_643_501_103,
+                5.463_784_911_164_114,
+                1.784_826_539_917_291_3,
+                0.296_560_571_828_504_87,
+                0.026_532_189_526_576_124,
+                0.001_242_660_947_388_078_4,
+                2.711_555_568_743_487_6e-5,
+                2.010_334_399_292_288_1e-7,
+            ],
+        ) / polynomial(
+            r,
+            &[
+                1.0,
+                0.599_832_206_555_888,
+                0.136_929_880_922_735_8,
+                0.014_875_361_290_850_615,
+                7.868_691_311_456_133e-4,
+                1.846_318_317_510_054_8e-5,
+                1.421_511_758_316_446e-7,
+                2.044_263_103_389_939_7e-15,
+            ],
+        )
+    };
+    if q < 0.0 {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Return the value of a polynomial by its coefficients, from the constant one, by the Horner's method
+fn polynomial(x: f64, coefficients: &[f64]) -> f64 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0.0, |sum, coefficient| sum * x + coefficient)
+}
+
+/// Return the number where an increasing function reaches a value, by bisection between the bounds,
+/// to the precision of the floating point numbers.
+/// The upper bound is doubled until the function reaches the value, so the function can be unbounded,
+/// and it's `NaN` if the value is never reached.
+pub(crate) fn invert(function: impl Fn(f64) -> f64, value: f64, mut lower: f64, mut upper: f64) -> f64 {
+    while function(upper) < value {
+        lower = upper;
+        upper *= 2.0;
+        if !upper.is_finite() {
+            return f64::NAN;
+        }
+    }
+    for _ in 0..MAX_ITERATIONS {
+        let middle = lower + (upper - lower) / 2.0;
+        if middle <= lower || middle >= upper {
+            break;
+        }
+        if function(middle) < value {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+    lower + (upper - lower) / 2.0
+}
+
+/// Return whether `(x / 2)^n / n!`, which is about the Bessel functions `J_n(x)` and `I_n(x)` when `n` is much greater
+/// than `x`, underflows
+fn is_bessel_underflow(x: f64, n: u32) -> bool {
+    let n = f64::from(n);
+    n * (x / 2.0).ln() - ln_gamma(n + 1.0) < LN_MIN_POSITIVE
+}
+
+/// Run the Miller's backward recurrence of the Bessel functions of the first kind, `J` if the sign is `-1`,
+/// or of the modified ones, `I` if the sign is `1`, from an order which is large enough, down to zero,
+/// and return the sums of their unnormalized values by the weights of their orders.
+/// So a ratio of two sums is exact, and one of them should be the normalizing sum of the functions.
+fn miller_sums(x: f64, n: u32, sign: f64, weights: &[&dyn Fn(u32) -> f64]) -> Vec<f64> {
+    let order = x.max(f64::from(n));
+    let start = (order + 20.0 + 2.0 * order.sqrt()) as u32;
+    let start = start + start % 2;
+
+    let mut sums = vec![0.0; weights.len()];
+    let mut next = 0.0;
+    let mut current = 1.0;
+    for k in (0..=start).rev() {
+        for (sum, weight) in sums.iter_mut().zip(weights) {
+            *sum += weight(k) * current;
+        }
+        if k == 0 {
+            break;
+        }
+        let previous = 2.0 * f64::from(k) / x * current + sign * next;
+        next = current;
+        current = previous;
+        if current.abs() > RESCALE {
+            current /= RESCALE;
+            next /= RESCALE;
+            sums.iter_mut().for_each(|sum| *sum /= RESCALE);
+        }
+    }
+    sums
+}
+
+/// The normalizing weights of `J_0 + 2 J_2 + 2 J_4 + ... = 1`
+fn bessel_j_norm(k: u32) -> f64 {
+    match k {
+        0 => 1.0,
+        k if k % 2 == 0 => 2.0,
+        _ => 0.0,
+    }
+}
+
+/// Return the Bessel function of the first kind `J_n(x)`
+pub(crate) fn bessel_j(x: f64, n: u32) -> f64 {
+    if x == 0.0 {
+        return if n == 0 { 1.0 } else { 0.0 };
+    }
+    if x < 0.0 {
+        let value = bessel_j(-x, n);
+        return if n % 2 == 0 { value } else { -value };
+    }
+    if is_bessel_underflow(x, n) {
+        return 0.0;
+    }
+    let sums = miller_sums(x, n, -1.0, &[&|k| if k == n { 1.0 } else { 0.0 }, &bessel_j_norm]);
+    sums[0] / sums[1]
+}
+
+/// Return the modified Bessel function of the first kind `I_n(x)`, which is normalized by
+/// `I_0 + 2 I_1 + 2 I_2 + ... = e^x`
+pub(crate) fn bessel_i(x: f64, n: u32) -> f64 {
+    if x == 0.0 {
+        return if n == 0 { 1.0 } else { 0.0 };
+    }
+    if x < 0.0 {
+        let value = bessel_i(-x, n);
+        return if n % 2 == 0 { value } else { -value };
+    }
+    if is_bessel_underflow(x, n) {
+        return 0.0;
+    }
+    let sums = miller_sums(
+        x,
+        n,
+        1.0,
+        &[&|k| if k == n { 1.0 } else { 0.0 }, &|k| if k == 0 { 1.0 } else { 2.0 }],
+    );
+    // The exponent is added as a logarithm, so it doesn't overflow before the ratio is multiplied
+    (x + (sums[0] / sums[1]).ln()).exp()
+}
+
+/// Return the Bessel function of the second kind `Y_n(x)`, for a positive `x`.
+///
+/// `Y_0` and `Y_1` are found by their Neumann series of `J`, like
+/// `Y_0(x) = 2 / PI * (ln(x / 2) + EULER_GAMMA) * J_0(x) - 4 / PI * sum((-1)^k * J_2k(x) / k)`,
+/// and the other orders by the forward recurrence, which is stable.
+pub(crate) fn bessel_y(x: f64, n: u32) -> f64 {
+    let even_weight = |k: u32| match k {
+        k if k >= 2 && k % 2 == 0 => {
+            let half = f64::from(k / 2);
+            if (k / 2) % 2 == 0 {
+                1.0 / half
+            } else {
+                -1.0 / half
+            }
+        }
+        _ => 0.0,
+    };
+    let odd_weight = |k: u32| match k {
+        k if k >= 3 && k % 2 == 1 => {
+            let half = k / 2;
+            let weight = f64::from(k) / (f64::from(half) * f64::from(half + 1));
+            if half % 2 == 0 {
+                weight
+            } else {
+                -weight
+            }
+        }
+        _ => 0.0,
+    };
+    let sums = miller_sums(
+        x,
+        1,
+        -1.0,
+        &[
+            &|k| if k == 0 { 1.0 } else { 0.0 },
+            &|k| if k == 1 { 1.0 } else { 0.0 },
+            &bessel_j_norm,
+            &even_weight,
+            &odd_weight,
+        ],
+    );
+    let [j0, j1, norm, even_sum, odd_sum] = sums[..] else {
+        return f64::NAN;
+    };
+    let (j0, j1, even_sum, odd_sum) = (j0 / norm, j1 / norm, even_sum / norm, odd_sum / norm);
+
+    let ln = (x / 2.0).ln() + EULER_GAMMA;
+    let mut previous = 2.0 / PI * ln * j0 - 4.0 / PI * even_sum;
+    if n == 0 {
+        return previous;
+    }
+    let mut current = -2.0 / (PI * x) * j0 + 2.0 / PI * (ln - 1.0) * j1 - 2.0 / PI * odd_sum;
+    for k in 1..n {
+        let next = 2.0 * f64::from(k) / x * current - previous;
+        previous = current;
+        current = next;
+    }
+    current
+}
+
+/// Return the modified Bessel function of the second kind `K_n(x)`, for a positive `x`, by the trapezoidal rule of
+/// `K_n(x) = integral(e^(-x * cosh(t)) * cosh(n * t), t, 0, infinity)`, which converges exponentially fast,
+/// because the integrand is analytic and decays double exponentially
+pub(crate) fn bessel_k(x: f64, n: u32) -> f64 {
+    let n = f64::from(n);
+    // The step is smaller than the width of the peak of the integrand, which is at `sinh(t) = n / x`
+    let step = (0.25_f64).min((x * x + n * n).powf(-0.25) / 3.0);
+    let peak = (n / x).asinh();
+
+    // The integrand is scaled by `e^x`, so it doesn't underflow for the large numbers
+    let mut sum = 0.0;
+    for i in 0..MAX_ITERATIONS * 10 {
+        let t = i as f64 * step;
+        let exponent = -x * (t.cosh() - 1.0);
+        let value = ((exponent + n * t).exp() + (exponent - n * t).exp()) / 2.0;
+        sum += if i == 0 { value / 2.0 } else { value };
+        if t > peak && value <= sum * f64::EPSILON / 10.0 {
+            break;
+        }
+    }
+    sum * step * (-x).exp()
+}
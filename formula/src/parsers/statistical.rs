@@ -1 +1,2629 @@
+use crate::{
+    ast::Node,
+    error::Error,
+    evaluator::Evaluator,
+    parsers::{
+        criteria::Criteria,
+        ets::{self, Aggregation, Model, Series},
+        matrix::{self, Matrix},
+        special,
+    },
+    ErrorKind, Expr, Result, Rule,
+};
+use std::{
+    f64::consts::{PI, SQRT_2},
+    slice::Iter,
+};
 
+/// The maximum degrees of freedom of the chi-squared and F distributions, like Excel
+const MAX_DEGREES_OF_FREEDOM: f64 = 1e10;
+
+/// Which values of the references and the arrays are used by a statistical function, like Excel.
+/// The values which are typed directly as arguments, like `TRUE` or `"1"`, are always converted to numbers.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Values {
+    /// Only the numbers, like `AVERAGE`
+    Numbers,
+    /// The numbers, the booleans and the texts as `0`, like `AVERAGEA`
+    All,
+}
+
+/// The means of the pairs of numbers, and the sums of the products of their deviations from the means, like `SLOPE`
+struct Deviations {
+    n: f64,
+    mean_x: f64,
+    mean_y: f64,
+    xx: f64,
+    yy: f64,
+    xy: f64,
+}
+
+impl Deviations {
+    fn new(x: &[f64], y: &[f64]) -> Self {
+        let n = x.len() as f64;
+        let mean_x = x.iter().sum::<f64>() / n;
+        let mean_y = y.iter().sum::<f64>() / n;
+        let (mut xx, mut yy, mut xy) = (0.0, 0.0, 0.0);
+        for (x, y) in x.iter().zip(y) {
+            let (dx, dy) = (x - mean_x, y - mean_y);
+            xx += dx * dx;
+            yy += dy * dy;
+            xy += dx * dy;
+        }
+        Self {
+            n,
+            mean_x,
+            mean_y,
+            xx,
+            yy,
+            xy,
+        }
+    }
+
+    /// The slope of the regression line, which is `#DIV/0!` if all the `x` are the same
+    fn slope(&self) -> Result<f64> {
+        if self.xx == 0.0 {
+            return Err(Error::Excel(ErrorKind::DivZero));
+        }
+        Ok(self.xy / self.xx)
+    }
+
+    /// The Pearson correlation coefficient, which is `#DIV/0!` if all the `x` or all the `y` are the same
+    fn correlation(&self) -> Result<f64> {
+        if self.xx == 0.0 || self.yy == 0.0 {
+            return Err(Error::Excel(ErrorKind::DivZero));
+        }
+        Ok(self.xy / (self.xx * self.yy).sqrt())
+    }
+}
+
+/// How the variables of a regression are arranged in the known `x` values, like `LINEST`
+#[derive(Clone, Copy)]
+enum Variables {
+    /// A single variable, which has a value for each known `y` value
+    Single,
+    /// Each column is a variable, if the known `y` values are a column
+    Columns,
+    /// Each row is a variable, if the known `y` values are a row
+    Rows,
+}
+
+/// The known values of a regression, like `LINEST`
+struct Regression {
+    y: Vec<f64>,
+    /// The values of the variables, an observation in each row
+    x: Matrix,
+    variables: Variables,
+    /// The known `x` values as they are given, which are the default new values of `TREND`
+    known_x: Vec<Vec<f64>>,
+}
+
+impl Evaluator<'_> {
+    /// Return the numbers of the arguments of a statistical function, like Excel.
+    ///
+    /// The numbers, the dates, the booleans and the texts of numbers which are typed directly are used,
+    /// and other texts are `#VALUE!`.
+    /// The values of the references and the arrays are used by `values`, and the empty values are ignored.
+    /// The error values are propagated.
+    pub(crate) fn get_stat_numbers(&self, args: &[Node], values: Values) -> Result<Vec<f64>> {
+        let mut numbers = Vec::new();
+        for arg in args {
+            let is_reference = matches!(arg, Node::Reference(_) | Node::Name(_));
+            match self.parse_arg(arg)? {
+                Expr::Array(array) => {
+                    for item in Self::flatten(array) {
+                        numbers.extend(Self::to_stat_number(item, values)?);
+                    }
+                }
+                item if is_reference => numbers.extend(Self::to_stat_number(item, values)?),
+                Expr::Number(number) => numbers.push(number),
+                Expr::Bool(bool) => numbers.push(f64::from(u8::from(bool))),
+                Expr::String(string) => {
+                    numbers.push(string.trim().parse().map_err(|_| Error::Excel(ErrorKind::Value))?)
+                }
+                item => numbers.extend(Self::to_serial(&item)),
+            }
+        }
+        Ok(numbers)
+    }
+
+    fn to_stat_number(item: Expr, values: Values) -> Result<Option<f64>> {
+        let number = match item {
+            Expr::Number(number) => Some(number),
+            Expr::Error(kind) => return Err(Error::Excel(kind)),
+            Expr::Bool(bool) if values == Values::All => Some(f64::from(u8::from(bool))),
+            Expr::String(_) if values == Values::All => Some(0.0),
+            _ => None,
+        };
+        Ok(number)
+    }
+
+    fn mean(numbers: &[f64]) -> f64 {
+        numbers.iter().sum::<f64>() / numbers.len() as f64
+    }
+
+    /// The sum of the squares of the deviations from the mean
+    fn devsq(numbers: &[f64]) -> f64 {
+        let mean = Self::mean(numbers);
+        numbers.iter().map(|number| (number - mean).powi(2)).sum()
+    }
+
+    /// The variance of a sample, or of a population if `population` is true
+    pub(crate) fn variance(numbers: &[f64], population: bool) -> Result<f64> {
+        let n = numbers.len() as f64;
+        let n = if population { n } else { n - 1.0 };
+        if n < 1.0 {
+            return Err(Error::Excel(ErrorKind::DivZero));
+        }
+        Ok(Self::devsq(numbers) / n)
+    }
+
+    pub(crate) fn parse_average(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.average(args, Values::Numbers)
+    }
+
+    pub(crate) fn parse_averagea(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.average(args, Values::All)
+    }
+
+    fn average(&self, args: &[Node], values: Values) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, values)?;
+        if numbers.is_empty() {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        Ok(Expr::Number(Self::mean(&numbers)))
+    }
+
+    pub(crate) fn parse_median(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let mut numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        if numbers.is_empty() {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        numbers.sort_by(f64::total_cmp);
+        let middle = numbers.len() / 2;
+        let median = if numbers.len() % 2 == 0 {
+            (numbers[middle - 1] + numbers[middle]) / 2.0
+        } else {
+            numbers[middle]
+        };
+        Ok(Expr::Number(median))
+    }
+
+    /// Return the most frequent numbers, in the order of their first appearance, like Excel
+    fn modes(&self, args: &[Node]) -> Result<Vec<f64>> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        let mut counts: Vec<(f64, usize)> = Vec::new();
+        for number in numbers {
+            match counts.iter_mut().find(|(n, _)| *n == number) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((number, 1)),
+            }
+        }
+
+        let max = counts.iter().map(|(_, count)| *count).max().unwrap_or_default();
+        if max < 2 {
+            return Err(Error::Excel(ErrorKind::NA));
+        }
+        Ok(counts
+            .into_iter()
+            .filter(|(_, count)| *count == max)
+            .map(|(number, _)| number)
+            .collect())
+    }
+
+    pub(crate) fn parse_mode_sngl(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let modes = self.modes(args)?;
+        Ok(Expr::Number(modes[0]))
+    }
+
+    /// The modes are returned as a vertical array, like Excel
+    pub(crate) fn parse_mode_mult(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let modes = self.modes(args)?;
+        Ok(Expr::from_rows(
+            modes.into_iter().map(|mode| vec![Expr::Number(mode)]).collect(),
+        ))
+    }
+
+    pub(crate) fn parse_min(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.min(args, Values::Numbers)
+    }
+
+    pub(crate) fn parse_mina(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.min(args, Values::All)
+    }
+
+    fn min(&self, args: &[Node], values: Values) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, values)?;
+        let min = numbers.into_iter().reduce(f64::min).unwrap_or_default();
+        Ok(Expr::Number(min))
+    }
+
+    pub(crate) fn parse_max(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.max(args, Values::Numbers)
+    }
+
+    pub(crate) fn parse_maxa(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.max(args, Values::All)
+    }
+
+    fn max(&self, args: &[Node], values: Values) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, values)?;
+        let max = numbers.into_iter().reduce(f64::max).unwrap_or_default();
+        Ok(Expr::Number(max))
+    }
+
+    /// The numbers, the booleans and the texts of numbers which are typed directly are counted,
+    /// but only the numbers of the references and the arrays. The errors are not counted.
+    pub(crate) fn parse_count(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let mut count = 0;
+        for arg in args {
+            let is_reference = matches!(arg, Node::Reference(_) | Node::Name(_));
+            count += match self.parse_arg_or_error(arg)? {
+                Expr::Array(array) => Self::flatten(array).iter().filter(|item| Self::is_number(item)).count(),
+                item if is_reference => usize::from(Self::is_number(&item)),
+                Expr::Bool(_) => 1,
+                Expr::String(string) => usize::from(string.trim().parse::<f64>().is_ok()),
+                item => usize::from(Self::is_number(&item)),
+            };
+        }
+        Ok(Expr::Number(count as f64))
+    }
+
+    fn is_number(item: &Expr) -> bool {
+        matches!(
+            item,
+            Expr::Number(_) | Expr::Date(_) | Expr::Datetime(_) | Expr::Time(_)
+        )
+    }
+
+    /// All the values are counted, including the errors and the empty texts, but not the empty values
+    pub(crate) fn parse_counta(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let mut count = 0;
+        for arg in args {
+            count += match self.parse_arg_or_error(arg)? {
+                Expr::Array(array) => Self::flatten(array)
+                    .iter()
+                    .filter(|item| !matches!(item, Expr::Null))
+                    .count(),
+                Expr::Null => 0,
+                _ => 1,
+            };
+        }
+        Ok(Expr::Number(count as f64))
+    }
+
+    /// The empty values and the empty texts are counted
+    pub(crate) fn parse_countblank(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let values = self.get_formula(&mut args, &rule_name)?;
+
+        let values = match values {
+            Expr::Array(array) => Self::flatten(array),
+            value => vec![value],
+        };
+        let count = values
+            .iter()
+            .filter(|item| matches!(item, Expr::Null) || matches!(item, Expr::String(s) if s.is_empty()))
+            .count();
+        Ok(Expr::Number(count as f64))
+    }
+
+    pub(crate) fn parse_stdev_s(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        Ok(Expr::Number(Self::variance(&numbers, false)?.sqrt()))
+    }
+
+    pub(crate) fn parse_stdev_p(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        Ok(Expr::Number(Self::variance(&numbers, true)?.sqrt()))
+    }
+
+    pub(crate) fn parse_var_s(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        Ok(Expr::Number(Self::variance(&numbers, false)?))
+    }
+
+    pub(crate) fn parse_var_p(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        Ok(Expr::Number(Self::variance(&numbers, true)?))
+    }
+
+    pub(crate) fn parse_avedev(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        if numbers.is_empty() {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let mean = Self::mean(&numbers);
+        let deviations = numbers.iter().map(|number| (number - mean).abs()).collect::<Vec<_>>();
+        Ok(Expr::Number(Self::mean(&deviations)))
+    }
+
+    pub(crate) fn parse_devsq(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        if numbers.is_empty() {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(Self::devsq(&numbers)))
+    }
+
+    pub(crate) fn parse_geomean(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        if numbers.is_empty() || numbers.iter().any(|number| *number <= 0.0) {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        // The mean of the logarithms doesn't overflow, unlike the product of the numbers
+        let logs = numbers.iter().map(|number| number.ln()).collect::<Vec<_>>();
+        Ok(Expr::Number(Self::mean(&logs).exp()))
+    }
+
+    pub(crate) fn parse_harmean(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        if numbers.is_empty() || numbers.iter().any(|number| *number <= 0.0) {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let inverses = numbers.iter().map(|number| 1.0 / number).collect::<Vec<_>>();
+        Ok(Expr::Number(1.0 / Self::mean(&inverses)))
+    }
+
+    pub(crate) fn parse_kurt(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        let n = numbers.len() as f64;
+        if numbers.len() < 4 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        let stdev = Self::variance(&numbers, false)?.sqrt();
+        if stdev == 0.0 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+
+        let mean = Self::mean(&numbers);
+        let sum = numbers
+            .iter()
+            .map(|number| ((number - mean) / stdev).powi(4))
+            .sum::<f64>();
+        let kurt = n * (n + 1.0) / ((n - 1.0) * (n - 2.0) * (n - 3.0)) * sum
+            - 3.0 * (n - 1.0).powi(2) / ((n - 2.0) * (n - 3.0));
+        Ok(Expr::Number(kurt))
+    }
+
+    pub(crate) fn parse_skew(&self, _rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_stat_numbers(args, Values::Numbers)?;
+        let n = numbers.len() as f64;
+        if numbers.len() < 3 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        let stdev = Self::variance(&numbers, false)?.sqrt();
+        if stdev == 0.0 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+
+        let mean = Self::mean(&numbers);
+        let sum = numbers
+            .iter()
+            .map(|number| ((number - mean) / stdev).powi(3))
+            .sum::<f64>();
+        Ok(Expr::Number(n / ((n - 1.0) * (n - 2.0)) * sum))
+    }
+
+    pub(crate) fn parse_countif(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.parse_countifs(rule, args)
+    }
+
+    pub(crate) fn parse_countifs(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let ranges = self.get_criteria_ranges(args, &rule_name)?;
+        let size = ranges.first().map_or(0, |(range, _)| range.len());
+
+        let values = Self::get_matched_values(vec![Expr::Null; size], ranges)?;
+        Ok(Expr::Number(values.len() as f64))
+    }
+
+    pub(crate) fn parse_averageif(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let range = Self::get_range(self.parse_node(args.next().ok_or_else(|| Error::Parser(rule_name.clone()))?)?);
+        let criteria = Criteria::new(self.get_formula(&mut args, &rule_name)?)?;
+        let values = match args.next() {
+            Some(values) => Self::get_range(self.parse_node(values)?),
+            None => range.clone(),
+        };
+
+        let values = Self::get_matched_values(values, vec![(range, criteria)])?;
+        let numbers = Self::get_matched_numbers(values)?;
+        if numbers.is_empty() {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        Ok(Expr::Number(Self::mean(&numbers)))
+    }
+
+    pub(crate) fn parse_averageifs(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_matched_numbers_of_ifs(rule, args)?;
+        if numbers.is_empty() {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        Ok(Expr::Number(Self::mean(&numbers)))
+    }
+
+    pub(crate) fn parse_maxifs(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_matched_numbers_of_ifs(rule, args)?;
+        Ok(Expr::Number(numbers.into_iter().reduce(f64::max).unwrap_or_default()))
+    }
+
+    pub(crate) fn parse_minifs(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let numbers = self.get_matched_numbers_of_ifs(rule, args)?;
+        Ok(Expr::Number(numbers.into_iter().reduce(f64::min).unwrap_or_default()))
+    }
+
+    /// Return the matched numbers of the functions like `AVERAGEIFS(values, range1, criteria1, ...)`
+    fn get_matched_numbers_of_ifs(&self, rule: Rule, args: &[Node]) -> Result<Vec<f64>> {
+        let rule_name = format!("{rule:?}");
+        let (values, ranges) = args.split_first().ok_or_else(|| Error::Parser(rule_name.clone()))?;
+        let values = Self::get_range(self.parse_node(values)?);
+        let ranges = self.get_criteria_ranges(ranges, &rule_name)?;
+
+        Self::get_matched_numbers(Self::get_matched_values(values, ranges)?)
+    }
+
+    /// The number of the excluded values is rounded down to the nearest multiple of 2, like Excel
+    pub(crate) fn parse_trimmean(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let (array, percent) = match args {
+            [array, percent] => (array, percent),
+            _ => return Err(Error::Parser(rule_name)),
+        };
+        let mut numbers = self.get_stat_numbers(std::slice::from_ref(array), Values::Numbers)?;
+        let percent = match self.parse_arg(percent)? {
+            Expr::Number(percent) => percent,
+            _ => return Err(Error::Parser(rule_name)),
+        };
+        if numbers.is_empty() || !(0.0..1.0).contains(&percent) {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        numbers.sort_by(f64::total_cmp);
+        let excluded = (numbers.len() as f64 * percent / 2.0).floor() as usize;
+        let numbers = &numbers[excluded..numbers.len() - excluded];
+        Ok(Expr::Number(Self::mean(numbers)))
+    }
+
+    /// The gamma function of zero and the negative integers is `#NUM!`, like Excel
+    pub(crate) fn parse_gamma(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?;
+        Ok(Expr::Number(special::gamma(number)))
+    }
+
+    /// The logarithm of the gamma function is only defined for the positive numbers, like Excel
+    pub(crate) fn parse_gammaln(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?;
+        if number <= 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(special::ln_gamma(number)))
+    }
+
+    pub(crate) fn parse_gammaln_precise(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.parse_gammaln(rule, args)
+    }
+
+    fn get_cumulative(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<bool> {
+        Ok(self.get_number(args, rule_name)? != 0.0)
+    }
+
+    /// Return the degrees of freedom of the chi-squared and F distributions, which are truncated,
+    /// or `None` if they are not from 1 to 10^10, like Excel
+    fn get_degrees_of_freedom(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<Option<f64>> {
+        let degrees = self.get_number(args, rule_name)?.trunc();
+        Ok((1.0..=MAX_DEGREES_OF_FREEDOM).contains(&degrees).then_some(degrees))
+    }
+
+    fn normal_cdf(z: f64) -> f64 {
+        special::erfc(-z / SQRT_2) / 2.0
+    }
+
+    fn normal_pdf(z: f64) -> f64 {
+        (-z * z / 2.0).exp() / (2.0 * PI).sqrt()
+    }
+
+    /// The probability of the Student's t distribution to be greater than a non-negative number
+    fn t_upper_tail(x: f64, degrees: f64) -> f64 {
+        special::regularized_beta(degrees / 2.0, 0.5, degrees / (degrees + x * x)) / 2.0
+    }
+
+    fn t_cdf(x: f64, degrees: f64) -> f64 {
+        let tail = Self::t_upper_tail(x.abs(), degrees);
+        if x < 0.0 {
+            tail
+        } else {
+            1.0 - tail
+        }
+    }
+
+    fn t_pdf(x: f64, degrees: f64) -> f64 {
+        (special::ln_gamma((degrees + 1.0) / 2.0)
+            - special::ln_gamma(degrees / 2.0)
+            - (degrees * PI).ln() / 2.0
+            - (degrees + 1.0) / 2.0 * (x * x / degrees).ln_1p())
+        .exp()
+    }
+
+    /// Return the non-negative number where the upper tail of the Student's t distribution is the probability
+    fn t_upper_tail_inverse(probability: f64, degrees: f64) -> f64 {
+        special::invert(|x| -Self::t_upper_tail(x, degrees), -probability, 0.0, 1.0)
+    }
+
+    fn gamma_pdf(x: f64, alpha: f64, beta: f64) -> f64 {
+        (special::x_ln_y(alpha - 1.0, x) - x / beta - alpha * beta.ln() - special::ln_gamma(alpha)).exp()
+    }
+
+    fn f_cdf(x: f64, degrees1: f64, degrees2: f64) -> f64 {
+        special::regularized_beta(degrees1 / 2.0, degrees2 / 2.0, degrees1 * x / (degrees1 * x + degrees2))
+    }
+
+    fn f_upper_tail(x: f64, degrees1: f64, degrees2: f64) -> f64 {
+        special::regularized_beta(degrees2 / 2.0, degrees1 / 2.0, degrees2 / (degrees1 * x + degrees2))
+    }
+
+    fn f_pdf(x: f64, degrees1: f64, degrees2: f64) -> f64 {
+        (special::x_ln_y(degrees1 / 2.0, degrees1)
+            + special::x_ln_y(degrees2 / 2.0, degrees2)
+            + special::x_ln_y(degrees1 / 2.0 - 1.0, x)
+            - (degrees1 + degrees2) / 2.0 * (degrees1 * x + degrees2).ln()
+            - special::ln_beta(degrees1 / 2.0, degrees2 / 2.0))
+        .exp()
+    }
+
+    fn ln_binomial(n: f64, k: f64) -> f64 {
+        special::ln_gamma(n + 1.0) - special::ln_gamma(k + 1.0) - special::ln_gamma(n - k + 1.0)
+    }
+
+    fn binomial_pmf(successes: f64, trials: f64, probability: f64) -> f64 {
+        (Self::ln_binomial(trials, successes)
+            + special::x_ln_y(successes, probability)
+            + special::x_ln_y(trials - successes, 1.0 - probability))
+        .exp()
+    }
+
+    fn binomial_cdf(successes: f64, trials: f64, probability: f64) -> f64 {
+        if successes >= trials {
+            return 1.0;
+        }
+        special::regularized_beta(trials - successes, successes + 1.0, 1.0 - probability)
+    }
+
+    pub(crate) fn parse_norm_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let mean = self.get_number(&mut args, &rule_name)?;
+        let standard_dev = self.get_number(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        if standard_dev <= 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let z = (x - mean) / standard_dev;
+        Ok(Expr::Number(if cumulative {
+            Self::normal_cdf(z)
+        } else {
+            Self::normal_pdf(z) / standard_dev
+        }))
+    }
+
+    pub(crate) fn parse_norm_inv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let mean = self.get_number(&mut args, &rule_name)?;
+        let standard_dev = self.get_number(&mut args, &rule_name)?;
+        if probability <= 0.0 || probability >= 1.0 || standard_dev <= 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(
+            mean + standard_dev * special::inverse_standard_normal(probability),
+        ))
+    }
+
+    pub(crate) fn parse_norm_s_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let z = self.get_number(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        Ok(Expr::Number(if cumulative {
+            Self::normal_cdf(z)
+        } else {
+            Self::normal_pdf(z)
+        }))
+    }
+
+    pub(crate) fn parse_norm_s_inv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        if probability <= 0.0 || probability >= 1.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(special::inverse_standard_normal(probability)))
+    }
+
+    pub(crate) fn parse_lognorm_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let mean = self.get_number(&mut args, &rule_name)?;
+        let standard_dev = self.get_number(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        if x <= 0.0 || standard_dev <= 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let z = (x.ln() - mean) / standard_dev;
+        Ok(Expr::Number(if cumulative {
+            Self::normal_cdf(z)
+        } else {
+            Self::normal_pdf(z) / (x * standard_dev)
+        }))
+    }
+
+    pub(crate) fn parse_lognorm_inv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let mean = self.get_number(&mut args, &rule_name)?;
+        let standard_dev = self.get_number(&mut args, &rule_name)?;
+        if probability <= 0.0 || probability >= 1.0 || standard_dev <= 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(
+            (mean + standard_dev * special::inverse_standard_normal(probability)).exp(),
+        ))
+    }
+
+    /// The standard normal probability density
+    pub(crate) fn parse_phi(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        Ok(Expr::Number(Self::normal_pdf(x)))
+    }
+
+    pub(crate) fn parse_t_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_number(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        if degrees < 1.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(if cumulative {
+            Self::t_cdf(x, degrees)
+        } else {
+            Self::t_pdf(x, degrees)
+        }))
+    }
+
+    pub(crate) fn parse_t_dist_2t(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_number(&mut args, &rule_name)?;
+        if x < 0.0 || degrees < 1.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(2.0 * Self::t_upper_tail(x, degrees)))
+    }
+
+    pub(crate) fn parse_t_dist_rt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_number(&mut args, &rule_name)?;
+        if degrees < 1.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(Self::t_cdf(-x, degrees)))
+    }
+
+    /// The inverse is found by the smaller tail, which is exact for the probabilities near 1 too
+    pub(crate) fn parse_t_inv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_number(&mut args, &rule_name)?;
+        if probability <= 0.0 || probability >= 1.0 || degrees < 1.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let x = Self::t_upper_tail_inverse(probability.min(1.0 - probability), degrees);
+        Ok(Expr::Number(if probability < 0.5 { -x } else { x }))
+    }
+
+    pub(crate) fn parse_t_inv_2t(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_number(&mut args, &rule_name)?;
+        if probability <= 0.0 || probability > 1.0 || degrees < 1.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(Self::t_upper_tail_inverse(probability / 2.0, degrees)))
+    }
+
+    pub(crate) fn parse_chisq_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_degrees_of_freedom(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        let Some(degrees) = degrees.filter(|_| x >= 0.0) else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        Ok(Expr::Number(if cumulative {
+            special::regularized_gamma_p(degrees / 2.0, x / 2.0)
+        } else {
+            Self::gamma_pdf(x, degrees / 2.0, 2.0)
+        }))
+    }
+
+    pub(crate) fn parse_chisq_dist_rt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_degrees_of_freedom(&mut args, &rule_name)?;
+        let Some(degrees) = degrees.filter(|_| x >= 0.0) else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        Ok(Expr::Number(special::regularized_gamma_q(degrees / 2.0, x / 2.0)))
+    }
+
+    pub(crate) fn parse_chisq_inv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_degrees_of_freedom(&mut args, &rule_name)?;
+        let Some(degrees) = degrees.filter(|_| (0.0..1.0).contains(&probability)) else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        if probability == 0.0 {
+            return Ok(Expr::Number(0.0));
+        }
+        Ok(Expr::Number(special::invert(
+            |x| special::regularized_gamma_p(degrees / 2.0, x / 2.0),
+            probability,
+            0.0,
+            1.0,
+        )))
+    }
+
+    pub(crate) fn parse_chisq_inv_rt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_degrees_of_freedom(&mut args, &rule_name)?;
+        let Some(degrees) = degrees.filter(|_| probability > 0.0 && probability <= 1.0) else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        if probability == 1.0 {
+            return Ok(Expr::Number(0.0));
+        }
+        Ok(Expr::Number(special::invert(
+            |x| -special::regularized_gamma_q(degrees / 2.0, x / 2.0),
+            -probability,
+            0.0,
+            1.0,
+        )))
+    }
+
+    fn get_f_degrees_of_freedom(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<Option<(f64, f64)>> {
+        let degrees1 = self.get_degrees_of_freedom(args, rule_name)?;
+        let degrees2 = self.get_degrees_of_freedom(args, rule_name)?;
+        Ok(degrees1.zip(degrees2))
+    }
+
+    pub(crate) fn parse_f_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_f_degrees_of_freedom(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        let Some((degrees1, degrees2)) = degrees.filter(|_| x >= 0.0) else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        Ok(Expr::Number(if cumulative {
+            Self::f_cdf(x, degrees1, degrees2)
+        } else {
+            Self::f_pdf(x, degrees1, degrees2)
+        }))
+    }
+
+    pub(crate) fn parse_f_dist_rt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_f_degrees_of_freedom(&mut args, &rule_name)?;
+        let Some((degrees1, degrees2)) = degrees.filter(|_| x >= 0.0) else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        Ok(Expr::Number(Self::f_upper_tail(x, degrees1, degrees2)))
+    }
+
+    pub(crate) fn parse_f_inv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_f_degrees_of_freedom(&mut args, &rule_name)?;
+        let Some((degrees1, degrees2)) = degrees.filter(|_| (0.0..1.0).contains(&probability)) else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        if probability == 0.0 {
+            return Ok(Expr::Number(0.0));
+        }
+        Ok(Expr::Number(special::invert(
+            |x| Self::f_cdf(x, degrees1, degrees2),
+            probability,
+            0.0,
+            1.0,
+        )))
+    }
+
+    pub(crate) fn parse_f_inv_rt(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let degrees = self.get_f_degrees_of_freedom(&mut args, &rule_name)?;
+        let Some((degrees1, degrees2)) = degrees.filter(|_| probability > 0.0 && probability <= 1.0) else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        if probability == 1.0 {
+            return Ok(Expr::Number(0.0));
+        }
+        Ok(Expr::Number(special::invert(
+            |x| -Self::f_upper_tail(x, degrees1, degrees2),
+            -probability,
+            0.0,
+            1.0,
+        )))
+    }
+
+    pub(crate) fn parse_binom_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let successes = self.get_number(&mut args, &rule_name)?.trunc();
+        let trials = self.get_number(&mut args, &rule_name)?.trunc();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        if successes < 0.0 || successes > trials || !(0.0..=1.0).contains(&probability) {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(if cumulative {
+            Self::binomial_cdf(successes, trials, probability)
+        } else {
+            Self::binomial_pmf(successes, trials, probability)
+        }))
+    }
+
+    /// Return the smallest number of successes, where the cumulative binomial distribution reaches the criterion
+    pub(crate) fn parse_binom_inv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let trials = self.get_number(&mut args, &rule_name)?.trunc();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let alpha = self.get_number(&mut args, &rule_name)?;
+        if trials < 0.0 || !(0.0..=1.0).contains(&probability) || !(0.0..=1.0).contains(&alpha) {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        // The binary search of the cumulative distribution, which is increasing
+        let (mut lower, mut upper) = (0.0, trials);
+        while lower < upper {
+            let middle = ((lower + upper) / 2.0).floor();
+            if Self::binomial_cdf(middle, trials, probability) < alpha {
+                lower = middle + 1.0;
+            } else {
+                upper = middle;
+            }
+        }
+        Ok(Expr::Number(lower))
+    }
+
+    /// Return the probability of the successes from the first number to the second one, or of the first number only
+    pub(crate) fn parse_binom_dist_range(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let trials = self.get_number(&mut args, &rule_name)?.trunc();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let successes = self.get_number(&mut args, &rule_name)?.trunc();
+        let successes2 = self.get_opt_number(&mut args, &rule_name, successes)?.trunc();
+        if !(0.0..=1.0).contains(&probability)
+            || successes < 0.0
+            || successes > trials
+            || successes2 < successes
+            || successes2 > trials
+        {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let lower = if successes == 0.0 {
+            0.0
+        } else {
+            Self::binomial_cdf(successes - 1.0, trials, probability)
+        };
+        Ok(Expr::Number(
+            Self::binomial_cdf(successes2, trials, probability) - lower,
+        ))
+    }
+
+    pub(crate) fn parse_negbinom_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let failures = self.get_number(&mut args, &rule_name)?.trunc();
+        let successes = self.get_number(&mut args, &rule_name)?.trunc();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        if failures < 0.0 || successes < 1.0 || !(0.0..=1.0).contains(&probability) {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(if cumulative {
+            special::regularized_beta(successes, failures + 1.0, probability)
+        } else {
+            (Self::ln_binomial(failures + successes - 1.0, successes - 1.0)
+                + special::x_ln_y(successes, probability)
+                + special::x_ln_y(failures, 1.0 - probability))
+            .exp()
+        }))
+    }
+
+    pub(crate) fn parse_hypgeom_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let sample_successes = self.get_number(&mut args, &rule_name)?.trunc();
+        let sample = self.get_number(&mut args, &rule_name)?.trunc();
+        let population_successes = self.get_number(&mut args, &rule_name)?.trunc();
+        let population = self.get_number(&mut args, &rule_name)?.trunc();
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        let minimum = (sample - population + population_successes).max(0.0);
+        if sample_successes < minimum
+            || sample_successes > sample.min(population_successes)
+            || sample <= 0.0
+            || sample > population
+            || population_successes <= 0.0
+            || population_successes > population
+        {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let pmf = |k: f64| {
+            (Self::ln_binomial(population_successes, k)
+                + Self::ln_binomial(population - population_successes, sample - k)
+                - Self::ln_binomial(population, sample))
+            .exp()
+        };
+        Ok(Expr::Number(if cumulative {
+            let count = (sample_successes - minimum) as u64;
+            (0..=count).map(|k| pmf(minimum + k as f64)).sum::<f64>().min(1.0)
+        } else {
+            pmf(sample_successes)
+        }))
+    }
+
+    pub(crate) fn parse_poisson_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?.trunc();
+        let mean = self.get_number(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        if x < 0.0 || mean < 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(if cumulative {
+            special::regularized_gamma_q(x + 1.0, mean)
+        } else {
+            (special::x_ln_y(x, mean) - mean - special::ln_gamma(x + 1.0)).exp()
+        }))
+    }
+
+    pub(crate) fn parse_expon_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let lambda = self.get_number(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        if x < 0.0 || lambda <= 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(if cumulative {
+            -(-lambda * x).exp_m1()
+        } else {
+            lambda * (-lambda * x).exp()
+        }))
+    }
+
+    pub(crate) fn parse_gamma_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let alpha = self.get_number(&mut args, &rule_name)?;
+        let beta = self.get_number(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        if x < 0.0 || alpha <= 0.0 || beta <= 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(if cumulative {
+            special::regularized_gamma_p(alpha, x / beta)
+        } else {
+            Self::gamma_pdf(x, alpha, beta)
+        }))
+    }
+
+    pub(crate) fn parse_gamma_inv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let alpha = self.get_number(&mut args, &rule_name)?;
+        let beta = self.get_number(&mut args, &rule_name)?;
+        if !(0.0..1.0).contains(&probability) || alpha <= 0.0 || beta <= 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        if probability == 0.0 {
+            return Ok(Expr::Number(0.0));
+        }
+        Ok(Expr::Number(special::invert(
+            |x| special::regularized_gamma_p(alpha, x / beta),
+            probability,
+            0.0,
+            beta,
+        )))
+    }
+
+    /// Return the optional bounds of the beta distribution, which are from 0 to 1 by default
+    fn get_beta_bounds(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<(f64, f64)> {
+        let lower = self.get_opt_number(args, rule_name, 0.0)?;
+        let upper = self.get_opt_number(args, rule_name, 1.0)?;
+        Ok((lower, upper))
+    }
+
+    pub(crate) fn parse_beta_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let alpha = self.get_number(&mut args, &rule_name)?;
+        let beta = self.get_number(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        let (lower, upper) = self.get_beta_bounds(&mut args, &rule_name)?;
+        if alpha <= 0.0 || beta <= 0.0 || x < lower || x > upper || lower == upper {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let x = (x - lower) / (upper - lower);
+        Ok(Expr::Number(if cumulative {
+            special::regularized_beta(alpha, beta, x)
+        } else {
+            (special::x_ln_y(alpha - 1.0, x) + special::x_ln_y(beta - 1.0, 1.0 - x) - special::ln_beta(alpha, beta))
+                .exp()
+                / (upper - lower)
+        }))
+    }
+
+    pub(crate) fn parse_beta_inv(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let probability = self.get_number(&mut args, &rule_name)?;
+        let alpha = self.get_number(&mut args, &rule_name)?;
+        let beta = self.get_number(&mut args, &rule_name)?;
+        let (lower, upper) = self.get_beta_bounds(&mut args, &rule_name)?;
+        if probability <= 0.0 || probability > 1.0 || alpha <= 0.0 || beta <= 0.0 || lower == upper {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        // The distribution reaches one, within the precision, before its upper bound
+        if probability == 1.0 {
+            return Ok(Expr::Number(upper));
+        }
+
+        let x = special::invert(|x| special::regularized_beta(alpha, beta, x), probability, 0.0, 1.0);
+        Ok(Expr::Number(lower + x * (upper - lower)))
+    }
+
+    pub(crate) fn parse_weibull_dist(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let alpha = self.get_number(&mut args, &rule_name)?;
+        let beta = self.get_number(&mut args, &rule_name)?;
+        let cumulative = self.get_cumulative(&mut args, &rule_name)?;
+        if x < 0.0 || alpha <= 0.0 || beta <= 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let power = (x / beta).powf(alpha);
+        Ok(Expr::Number(if cumulative {
+            -(-power).exp_m1()
+        } else {
+            alpha / beta * (x / beta).powf(alpha - 1.0) * (-power).exp()
+        }))
+    }
+
+    /// Return the significance level, the standard deviation and the truncated size of the confidence functions
+    fn get_confidence_args(&self, rule: Rule, args: &[Node]) -> Result<Option<(f64, f64, f64)>> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let alpha = self.get_number(&mut args, &rule_name)?;
+        let standard_dev = self.get_number(&mut args, &rule_name)?;
+        let size = self.get_number(&mut args, &rule_name)?.trunc();
+        if alpha <= 0.0 || alpha >= 1.0 || standard_dev <= 0.0 || size < 1.0 {
+            return Ok(None);
+        }
+        Ok(Some((alpha, standard_dev, size)))
+    }
+
+    pub(crate) fn parse_confidence_norm(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let Some((alpha, standard_dev, size)) = self.get_confidence_args(rule, args)? else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        let z = -special::inverse_standard_normal(alpha / 2.0);
+        Ok(Expr::Number(z * standard_dev / size.sqrt()))
+    }
+
+    /// The confidence interval of a single value is `#DIV/0!`, like Excel
+    pub(crate) fn parse_confidence_t(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let Some((alpha, standard_dev, size)) = self.get_confidence_args(rule, args)? else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        if size == 1.0 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        let t = Self::t_upper_tail_inverse(alpha / 2.0, size - 1.0);
+        Ok(Expr::Number(t * standard_dev / size.sqrt()))
+    }
+
+    /// Return the values of an argument, which are the items of an array, like the values of a range
+    fn to_items(value: Expr) -> Vec<Expr> {
+        match value {
+            Expr::Array(array) => Self::flatten(array),
+            value => vec![value],
+        }
+    }
+
+    /// Return the pairs of the numbers of two arguments, which must have the same number of values, like `CORREL`
+    pub(crate) fn get_pairs(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<(Vec<f64>, Vec<f64>)> {
+        let first = self.get_formula(args, rule_name)?;
+        let second = self.get_formula(args, rule_name)?;
+        Self::to_pairs(first, second)
+    }
+
+    /// Return the pairs of the numbers of two values, which must have the same number of items.
+    /// The pairs which have other values are ignored, and the error values are propagated.
+    fn to_pairs(first: Expr, second: Expr) -> Result<(Vec<f64>, Vec<f64>)> {
+        let (first, second) = (Self::to_items(first), Self::to_items(second));
+        if first.len() != second.len() {
+            return Err(Error::Excel(ErrorKind::NA));
+        }
+
+        let mut pairs = (Vec::new(), Vec::new());
+        for pair in first.into_iter().zip(second) {
+            match pair {
+                (Expr::Error(kind), _) | (_, Expr::Error(kind)) => return Err(Error::Excel(kind)),
+                (Expr::Number(first), Expr::Number(second)) => {
+                    pairs.0.push(first);
+                    pairs.1.push(second);
+                }
+                _ => {}
+            }
+        }
+        Ok(pairs)
+    }
+
+    /// Return the deviations of the known `y` values and the known `x` values, which are the arguments in this order
+    fn get_deviations(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<Deviations> {
+        let (y, x) = self.get_pairs(args, rule_name)?;
+        Ok(Deviations::new(&x, &y))
+    }
+
+    pub(crate) fn parse_slope(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let deviations = self.get_deviations(&mut args.iter(), &rule_name)?;
+        Ok(Expr::Number(deviations.slope()?))
+    }
+
+    pub(crate) fn parse_intercept(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let deviations = self.get_deviations(&mut args.iter(), &rule_name)?;
+        Ok(Expr::Number(
+            deviations.mean_y - deviations.slope()? * deviations.mean_x,
+        ))
+    }
+
+    pub(crate) fn parse_rsq(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let deviations = self.get_deviations(&mut args.iter(), &rule_name)?;
+        Ok(Expr::Number(deviations.correlation()?.powi(2)))
+    }
+
+    /// The standard error of the predicted `y` values, which needs at least three pairs, like Excel
+    pub(crate) fn parse_steyx(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let deviations = self.get_deviations(&mut args.iter(), &rule_name)?;
+        if deviations.n < 3.0 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+
+        // The sum of the squares of the residuals isn't negative, despite the rounding errors
+        let residuals = (deviations.yy - deviations.xy * deviations.slope()?).max(0.0);
+        Ok(Expr::Number((residuals / (deviations.n - 2.0)).sqrt()))
+    }
+
+    pub(crate) fn parse_correl(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let deviations = self.get_deviations(&mut args.iter(), &rule_name)?;
+        Ok(Expr::Number(deviations.correlation()?))
+    }
+
+    pub(crate) fn parse_pearson(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.parse_correl(rule, args)
+    }
+
+    pub(crate) fn parse_covariance_s(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let deviations = self.get_deviations(&mut args.iter(), &rule_name)?;
+        if deviations.n < 2.0 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        Ok(Expr::Number(deviations.xy / (deviations.n - 1.0)))
+    }
+
+    pub(crate) fn parse_covariance_p(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let deviations = self.get_deviations(&mut args.iter(), &rule_name)?;
+        if deviations.n < 1.0 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        Ok(Expr::Number(deviations.xy / deviations.n))
+    }
+
+    pub(crate) fn parse_forecast_linear(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let x = self.get_number(&mut args, &rule_name)?;
+        let deviations = self.get_deviations(&mut args, &rule_name)?;
+        Ok(Expr::Number(
+            deviations.mean_y + deviations.slope()? * (x - deviations.mean_x),
+        ))
+    }
+
+    /// Return a number of an array which is returned, which is `#NUM!` if it's not finite, like the numbers which
+    /// are returned directly
+    fn to_array_number(number: f64) -> Expr {
+        if number.is_finite() {
+            Expr::Number(number)
+        } else {
+            Expr::Error(ErrorKind::Num)
+        }
+    }
+
+    /// Return the known `y` values and the optional known `x` values of a regression, like `LINEST`.
+    /// The logarithms of the `y` values are used if `logarithm` is true, like `LOGEST`, so they must be positive.
+    ///
+    /// The `x` values are a single variable if they have as many values as the `y` values, otherwise each of their
+    /// columns, or rows, is a variable, if the `y` values are a column, or a row, like Excel.
+    /// The default `x` values are `1, 2, 3, ...`.
+    fn get_regression(&self, args: &mut Iter<Node>, rule_name: &str, logarithm: bool) -> Result<Regression> {
+        let known_y = Self::to_number_table(self.get_formula(args, rule_name)?)?;
+        let (height, width) = (known_y.len(), known_y[0].len());
+        let mut y = known_y.concat();
+        if logarithm {
+            if y.iter().any(|y| *y <= 0.0) {
+                return Err(Error::Excel(ErrorKind::Num));
+            }
+            y = y.into_iter().map(f64::ln).collect();
+        }
+
+        let known_x = match args.next() {
+            Some(arg) => Self::to_number_table(self.parse_arg(arg)?)?,
+            None => (0..height)
+                .map(|row| (1..=width).map(|column| (row * width + column) as f64).collect())
+                .collect(),
+        };
+        let (x_height, x_width) = (known_x.len(), known_x[0].len());
+        let (variables, x) = if x_height * x_width == y.len()
+            && (x_height == 1 || x_width == 1 || (x_height, x_width) == (height, width))
+        {
+            let x = known_x.concat().into_iter().map(|x| vec![x]).collect::<Vec<_>>();
+            (Variables::Single, Matrix::from_rows(&x))
+        } else if width == 1 && x_height == y.len() {
+            (Variables::Columns, Matrix::from_rows(&known_x))
+        } else if height == 1 && x_width == y.len() {
+            let x = (0..x_width)
+                .map(|column| known_x.iter().map(|row| row[column]).collect())
+                .collect::<Vec<_>>();
+            (Variables::Rows, Matrix::from_rows(&x))
+        } else {
+            return Err(Error::Excel(ErrorKind::Ref));
+        };
+
+        Ok(Regression {
+            y,
+            x,
+            variables,
+            known_x,
+        })
+    }
+
+    pub(crate) fn parse_linest(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.linest(rule, args, false)
+    }
+
+    pub(crate) fn parse_logest(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.linest(rule, args, true)
+    }
+
+    /// Return the coefficients of a regression, from the last variable to the first one, and the intercept,
+    /// and the statistics of the regression below them, if they are asked, like `LINEST`.
+    ///
+    /// The regression of `LOGEST` is of the logarithms of the `y` values, so its coefficients are the exponentials
+    /// of the coefficients of `LINEST`, but the statistics are of the logarithms, like Excel.
+    fn linest(&self, rule: Rule, args: &[Node], logarithm: bool) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let regression = self.get_regression(&mut args, &rule_name, logarithm)?;
+        let constant = self.get_opt_number(&mut args, &rule_name, 1.0)? != 0.0;
+        let stats = self.get_opt_number(&mut args, &rule_name, 0.0)? != 0.0;
+        let fit = matrix::least_squares(&regression.x, &regression.y, constant);
+
+        let exp = |value: f64| if logarithm { value.exp() } else { value };
+        let coefficients = fit
+            .coefficients
+            .iter()
+            .rev()
+            .chain([&fit.intercept])
+            .map(|coefficient| Self::to_array_number(exp(*coefficient)))
+            .collect::<Vec<_>>();
+        if !stats {
+            return Ok(Expr::from_rows(vec![coefficients]));
+        }
+
+        // The sums of the squares are around the mean, or around zero if there's no intercept, like Excel
+        let mean = if constant { Self::mean(&regression.y) } else { 0.0 };
+        let (mut regression_squares, mut residual_squares) = (0.0, 0.0);
+        for (row, y) in regression.y.iter().enumerate() {
+            let fitted = fit.predict(regression.x.row(row));
+            regression_squares += (fitted - mean).powi(2);
+            residual_squares += (y - fitted).powi(2);
+        }
+        let degrees_of_freedom = regression.y.len().saturating_sub(fit.rank + usize::from(constant)) as f64;
+        let variance = residual_squares / degrees_of_freedom;
+        let standard_error = |variance_factor: f64| Self::to_array_number((variance_factor * variance).sqrt());
+
+        let width = coefficients.len();
+        let row = |values: [f64; 2]| {
+            values
+                .into_iter()
+                .map(Self::to_array_number)
+                .chain(std::iter::repeat(Expr::Error(ErrorKind::NA)))
+                .take(width)
+                .collect::<Vec<_>>()
+        };
+        let errors = fit
+            .variances
+            .iter()
+            .rev()
+            .map(|variance_factor| standard_error(*variance_factor))
+            .chain([if constant {
+                standard_error(fit.intercept_variance)
+            } else {
+                Expr::Error(ErrorKind::NA)
+            }])
+            .collect();
+        Ok(Expr::from_rows(vec![
+            coefficients,
+            errors,
+            row([
+                regression_squares / (regression_squares + residual_squares),
+                variance.sqrt(),
+            ]),
+            row([regression_squares / fit.rank as f64 / variance, degrees_of_freedom]),
+            row([regression_squares, residual_squares]),
+        ]))
+    }
+
+    pub(crate) fn parse_trend(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.trend(rule, args, false)
+    }
+
+    pub(crate) fn parse_growth(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.trend(rule, args, true)
+    }
+
+    /// Return the values of a regression at the new `x` values, which are the known ones by default, like `TREND`.
+    /// A single variable can have any shape of new values, otherwise they must have the variables like the known ones.
+    ///
+    /// The regression of `GROWTH` is of the logarithms of the `y` values, so its values are the exponentials.
+    fn trend(&self, rule: Rule, args: &[Node], logarithm: bool) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let regression = self.get_regression(&mut args, &rule_name, logarithm)?;
+        let (new_x, is_array) = match args.next() {
+            Some(arg) => {
+                let value = self.parse_arg(arg)?;
+                let is_array = matches!(value, Expr::Array(_));
+                (Self::to_number_table(value)?, is_array)
+            }
+            None => (regression.known_x, true),
+        };
+        let constant = self.get_opt_number(&mut args, &rule_name, 1.0)? != 0.0;
+        let fit = matrix::least_squares(&regression.x, &regression.y, constant);
+
+        let variables = regression.x.columns();
+        let predict = |x: &[f64]| {
+            let y = fit.predict(x);
+            Self::to_array_number(if logarithm { y.exp() } else { y })
+        };
+        let mut values = match regression.variables {
+            Variables::Single => new_x
+                .iter()
+                .map(|row| row.iter().map(|x| predict(&[*x])).collect())
+                .collect::<Vec<_>>(),
+            Variables::Columns if new_x[0].len() == variables => new_x.iter().map(|row| vec![predict(row)]).collect(),
+            Variables::Rows if new_x.len() == variables => vec![(0..new_x[0].len())
+                .map(|column| predict(&new_x.iter().map(|row| row[column]).collect::<Vec<_>>()))
+                .collect()],
+            _ => return Err(Error::Excel(ErrorKind::Ref)),
+        };
+
+        if !is_array {
+            return Ok(values.remove(0).remove(0));
+        }
+        Ok(Expr::from_rows(values))
+    }
+
+    /// Return the numbers of an argument, which is usually an array, like the arrays of `T.TEST`
+    fn get_sample(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<Vec<f64>> {
+        let arg = args.next().ok_or_else(|| Error::Parser(rule_name.to_owned()))?;
+        self.get_stat_numbers(std::slice::from_ref(arg), Values::Numbers)
+    }
+
+    /// Return the probability of the Student's t-test of two samples, with one or two tails.
+    /// The samples are paired if the type is 1, otherwise they have equal variances if it's 2, or unequal ones if it's 3,
+    /// where the degrees of freedom are of Welch–Satterthwaite, which aren't rounded.
+    pub(crate) fn parse_t_test(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let first = self.get_formula(&mut args, &rule_name)?;
+        let second = self.get_formula(&mut args, &rule_name)?;
+        let tails = self.get_number(&mut args, &rule_name)?.trunc();
+        let kind = self.get_number(&mut args, &rule_name)?.trunc();
+        if tails != 1.0 && tails != 2.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let (t, degrees) = if kind == 1.0 {
+            let (first, second) = Self::to_pairs(first, second)?;
+            let differences = first.iter().zip(&second).map(|(a, b)| a - b).collect::<Vec<_>>();
+            let n = differences.len() as f64;
+            let variance = Self::variance(&differences, false)?;
+            (Self::mean(&differences) / (variance / n).sqrt(), n - 1.0)
+        } else if kind == 2.0 || kind == 3.0 {
+            let first = Self::get_numbers([first], &rule_name)?;
+            let second = Self::get_numbers([second], &rule_name)?;
+            let (n1, n2) = (first.len() as f64, second.len() as f64);
+            let (variance1, variance2) = (Self::variance(&first, false)?, Self::variance(&second, false)?);
+            let difference = Self::mean(&first) - Self::mean(&second);
+            if kind == 2.0 {
+                let pooled = ((n1 - 1.0) * variance1 + (n2 - 1.0) * variance2) / (n1 + n2 - 2.0);
+                (difference / (pooled * (1.0 / n1 + 1.0 / n2)).sqrt(), n1 + n2 - 2.0)
+            } else {
+                let (error1, error2) = (variance1 / n1, variance2 / n2);
+                let degrees = (error1 + error2).powi(2) / (error1.powi(2) / (n1 - 1.0) + error2.powi(2) / (n2 - 1.0));
+                (difference / (error1 + error2).sqrt(), degrees)
+            }
+        } else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+
+        // The samples which don't vary have no t statistic
+        if !t.is_finite() {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+        Ok(Expr::Number(tails * Self::t_upper_tail(t.abs(), degrees)))
+    }
+
+    /// Return the one-tailed probability of the z-test of a sample, whose standard deviation is known,
+    /// or is of the sample by default
+    pub(crate) fn parse_z_test(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let sample = self.get_sample(&mut args, &rule_name)?;
+        let x = self.get_number(&mut args, &rule_name)?;
+        if sample.is_empty() {
+            return Ok(Expr::Error(ErrorKind::NA));
+        }
+        let standard_dev = if args.as_slice().is_empty() {
+            Self::variance(&sample, false)?.sqrt()
+        } else {
+            self.get_number(&mut args, &rule_name)?
+        };
+        if standard_dev < 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        } else if standard_dev == 0.0 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+
+        let z = (Self::mean(&sample) - x) / (standard_dev / (sample.len() as f64).sqrt());
+        Ok(Expr::Number(Self::normal_cdf(-z)))
+    }
+
+    /// Return the probability of the chi-squared test of the observed values, by the expected ones.
+    /// The degrees of freedom are of the table, or of the row, or the column, if the values are in a single one.
+    pub(crate) fn parse_chisq_test(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let observed = Self::to_rows(self.get_formula(&mut args, &rule_name)?);
+        let expected = Self::to_rows(self.get_formula(&mut args, &rule_name)?);
+        let (height, width) = (observed.len(), observed[0].len());
+        if expected.len() != height || observed.iter().chain(&expected).any(|row| row.len() != width) {
+            return Ok(Expr::Error(ErrorKind::NA));
+        }
+        let degrees = match (height, width) {
+            (1, 1) => return Ok(Expr::Error(ErrorKind::NA)),
+            (1, n) | (n, 1) => n - 1,
+            (height, width) => (height - 1) * (width - 1),
+        } as f64;
+
+        let mut statistic = 0.0;
+        for pair in observed.into_iter().flatten().zip(expected.into_iter().flatten()) {
+            match pair {
+                (Expr::Error(kind), _) | (_, Expr::Error(kind)) => return Err(Error::Excel(kind)),
+                (Expr::Number(observed), Expr::Number(expected)) => {
+                    if expected < 0.0 {
+                        return Ok(Expr::Error(ErrorKind::Num));
+                    } else if expected == 0.0 {
+                        return Ok(Expr::Error(ErrorKind::DivZero));
+                    }
+                    statistic += (observed - expected).powi(2) / expected;
+                }
+                _ => {}
+            }
+        }
+        Ok(Expr::Number(special::regularized_gamma_q(
+            degrees / 2.0,
+            statistic / 2.0,
+        )))
+    }
+
+    /// Return the two-tailed probability of the F-test, that the variances of two samples are not different
+    pub(crate) fn parse_f_test(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let first = self.get_sample(&mut args, &rule_name)?;
+        let second = self.get_sample(&mut args, &rule_name)?;
+        let (variance1, variance2) = (Self::variance(&first, false)?, Self::variance(&second, false)?);
+        if variance1 == 0.0 || variance2 == 0.0 {
+            return Ok(Expr::Error(ErrorKind::DivZero));
+        }
+
+        let f = variance1 / variance2;
+        let (degrees1, degrees2) = (first.len() as f64 - 1.0, second.len() as f64 - 1.0);
+        let tail = Self::f_cdf(f, degrees1, degrees2).min(Self::f_upper_tail(f, degrees1, degrees2));
+        Ok(Expr::Number(2.0 * tail))
+    }
+
+    /// Return the sorted numbers of an argument, like the array of `LARGE`
+    fn get_sorted_sample(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<Vec<f64>> {
+        let mut numbers = self.get_sample(args, rule_name)?;
+        numbers.sort_by(f64::total_cmp);
+        Ok(numbers)
+    }
+
+    /// The position `k` is rounded up, like Excel
+    pub(crate) fn parse_large(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let numbers = self.get_sorted_sample(&mut args, &rule_name)?;
+        let k = self.get_number(&mut args, &rule_name)?.ceil();
+        if k < 1.0 || k > numbers.len() as f64 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(numbers[numbers.len() - k as usize]))
+    }
+
+    /// The position `k` is rounded up, like Excel
+    pub(crate) fn parse_small(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let numbers = self.get_sorted_sample(&mut args, &rule_name)?;
+        let k = self.get_number(&mut args, &rule_name)?.ceil();
+        if k < 1.0 || k > numbers.len() as f64 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(numbers[k as usize - 1]))
+    }
+
+    pub(crate) fn parse_rank_eq(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.rank(rule, args, false)
+    }
+
+    pub(crate) fn parse_rank_avg(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.rank(rule, args, true)
+    }
+
+    /// Return the rank of a number in the numbers, from the largest one, or from the smallest one if the order isn't
+    /// zero. The rank of the equal numbers is the top one, or their average rank if `average` is true.
+    fn rank(&self, rule: Rule, args: &[Node], average: bool) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?;
+        let numbers = self.get_sample(&mut args, &rule_name)?;
+        let ascending = self.get_opt_number(&mut args, &rule_name, 0.0)? != 0.0;
+        if !numbers.contains(&number) {
+            return Ok(Expr::Error(ErrorKind::NA));
+        }
+
+        let count = |filter: fn(f64, f64) -> bool| numbers.iter().filter(|n| filter(**n, number)).count() as f64;
+        let before = if ascending {
+            count(|n, number| n < number)
+        } else {
+            count(|n, number| n > number)
+        };
+        let rank = if average {
+            before + (count(|n, number| n == number) + 1.0) / 2.0
+        } else {
+            before + 1.0
+        };
+        Ok(Expr::Number(rank))
+    }
+
+    /// Return the number at a position of the sorted numbers, from zero, which is interpolated between the nearest
+    /// numbers
+    fn interpolate(numbers: &[f64], position: f64) -> f64 {
+        let index = position.floor() as usize;
+        let fraction = position.fract();
+        match numbers.get(index + 1) {
+            Some(next) if fraction > 0.0 => numbers[index] + fraction * (next - numbers[index]),
+            _ => numbers[index],
+        }
+    }
+
+    /// The percentile of the sorted numbers, where `k` is from 0 to 1, inclusive
+    fn percentile_inc(numbers: &[f64], k: f64) -> Option<f64> {
+        if numbers.is_empty() || !(0.0..=1.0).contains(&k) {
+            return None;
+        }
+        Some(Self::interpolate(numbers, k * (numbers.len() - 1) as f64))
+    }
+
+    /// The percentile of the sorted numbers, where `k` is from 0 to 1, exclusive, and is between the first and the last
+    /// positions of the numbers
+    fn percentile_exc(numbers: &[f64], k: f64) -> Option<f64> {
+        let position = k * (numbers.len() + 1) as f64 - 1.0;
+        if numbers.is_empty() || position < 0.0 || position > (numbers.len() - 1) as f64 {
+            return None;
+        }
+        Some(Self::interpolate(numbers, position))
+    }
+
+    pub(crate) fn parse_percentile_inc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let numbers = self.get_sorted_sample(&mut args, &rule_name)?;
+        let k = self.get_number(&mut args, &rule_name)?;
+        Ok(Self::percentile_inc(&numbers, k).map_or(Expr::Error(ErrorKind::Num), Expr::Number))
+    }
+
+    pub(crate) fn parse_percentile_exc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let numbers = self.get_sorted_sample(&mut args, &rule_name)?;
+        let k = self.get_number(&mut args, &rule_name)?;
+        Ok(Self::percentile_exc(&numbers, k).map_or(Expr::Error(ErrorKind::Num), Expr::Number))
+    }
+
+    /// The quartile is truncated, and it's from 0, the minimum, to 4, the maximum
+    pub(crate) fn parse_quartile_inc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let numbers = self.get_sorted_sample(&mut args, &rule_name)?;
+        let quart = self.get_number(&mut args, &rule_name)?.trunc();
+        Ok(Self::percentile_inc(&numbers, quart / 4.0).map_or(Expr::Error(ErrorKind::Num), Expr::Number))
+    }
+
+    /// The quartile is truncated, and it's from 1 to 3
+    pub(crate) fn parse_quartile_exc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let numbers = self.get_sorted_sample(&mut args, &rule_name)?;
+        let quart = self.get_number(&mut args, &rule_name)?.trunc();
+        if !(1.0..=3.0).contains(&quart) {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Self::percentile_exc(&numbers, quart / 4.0).map_or(Expr::Error(ErrorKind::Num), Expr::Number))
+    }
+
+    /// The rank of a single number is 1, like Excel
+    pub(crate) fn parse_percentrank_inc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.percentrank(rule, args, |smaller, n| if n > 1.0 { smaller / (n - 1.0) } else { 1.0 })
+    }
+
+    pub(crate) fn parse_percentrank_exc(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        self.percentrank(rule, args, |smaller, n| (smaller + 1.0) / (n + 1.0))
+    }
+
+    /// Return the rank of a number in the numbers as a percentage, which is truncated to the significant digits,
+    /// 3 by default. The rank of each of the numbers is by the count of the smaller ones, and the number of all of them,
+    /// and the ranks of the other numbers are interpolated between the nearest ones.
+    fn percentrank(&self, rule: Rule, args: &[Node], rank: fn(f64, f64) -> f64) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let numbers = self.get_sorted_sample(&mut args, &rule_name)?;
+        let x = self.get_number(&mut args, &rule_name)?;
+        let significance = self.get_opt_number(&mut args, &rule_name, 3.0)?.trunc();
+        let (Some(first), Some(last)) = (numbers.first(), numbers.last()) else {
+            return Ok(Expr::Error(ErrorKind::Num));
+        };
+        if significance < 1.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        } else if x < *first || x > *last {
+            return Ok(Expr::Error(ErrorKind::NA));
+        }
+
+        let n = numbers.len() as f64;
+        let rank_of = |number: f64| rank(numbers.partition_point(|n| *n < number) as f64, n);
+        let smaller = numbers.partition_point(|n| *n < x);
+        let percentage = if numbers[smaller] == x {
+            rank_of(x)
+        } else {
+            let (lower, upper) = (numbers[smaller - 1], numbers[smaller]);
+            rank_of(lower) + (x - lower) / (upper - lower) * (rank_of(upper) - rank_of(lower))
+        };
+
+        // The tiny number corrects the binary errors, like `0.29 * 1000 = 289.99999999999997`, before the truncation
+        let scale = 10_f64.powf(significance);
+        Ok(Expr::Number((percentage * scale + 1e-9).floor() / scale))
+    }
+
+    /// Return the counts of the numbers in the intervals of the bins, as a vertical array.
+    /// Each bin counts the numbers which are greater than the previous bin, in the sorted order, and less than or equal
+    /// to it, and the last count is of the numbers which are greater than all the bins, like Excel.
+    pub(crate) fn parse_frequency(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let numbers = self.get_sample(&mut args, &rule_name)?;
+        let bins = self.get_sample(&mut args, &rule_name)?;
+
+        // The sort is stable, so the first one of the equal bins counts the numbers
+        let mut order = (0..bins.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| bins[*a].total_cmp(&bins[*b]));
+        let mut counts = vec![0_u32; bins.len() + 1];
+        for number in numbers {
+            let position = order.partition_point(|bin| bins[*bin] < number);
+            counts[order.get(position).copied().unwrap_or(bins.len())] += 1;
+        }
+        Ok(Expr::from_rows(
+            counts
+                .into_iter()
+                .map(|count| vec![Expr::Number(f64::from(count))])
+                .collect(),
+        ))
+    }
+
+    /// Return the probability of the values from the lower limit to the upper one, or of the lower limit only.
+    /// The probabilities must be from 0 to 1, and add up to 1.
+    /// The permutations of `k` items of `n` items are the product of `n` down to `n - k + 1`,
+    /// which is `#NUM!` if it overflows
+    pub(crate) fn parse_permut(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?.trunc();
+        let chosen = self.get_number(&mut args, &rule_name)?.trunc();
+        if number <= 0.0 || chosen < 0.0 || number < chosen {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let mut permutations = 1.0;
+        for i in 0..chosen as u64 {
+            permutations *= number - i as f64;
+            if permutations.is_infinite() {
+                return Ok(Expr::Error(ErrorKind::Num));
+            }
+        }
+        Ok(Expr::Number(permutations))
+    }
+
+    /// The permutations with repetitions of `k` items of `n` items are `n^k`
+    pub(crate) fn parse_permutationa(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_number(&mut args, &rule_name)?.trunc();
+        let chosen = self.get_number(&mut args, &rule_name)?.trunc();
+        if number < 0.0 || chosen < 0.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let permutations = number.powf(chosen);
+        if permutations.is_infinite() {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+        Ok(Expr::Number(permutations))
+    }
+
+    pub(crate) fn parse_prob(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let (values, probabilities) = self.get_pairs(&mut args, &rule_name)?;
+        let lower = self.get_number(&mut args, &rule_name)?;
+        let upper = self.get_opt_number(&mut args, &rule_name, lower)?;
+        if probabilities
+            .iter()
+            .any(|probability| !(0.0..=1.0).contains(probability))
+            || (probabilities.iter().sum::<f64>() - 1.0).abs() > 1e-12
+        {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let probability = values
+            .iter()
+            .zip(&probabilities)
+            .filter(|(value, _)| (lower..=upper).contains(*value))
+            .map(|(_, probability)| probability)
+            .sum();
+        Ok(Expr::Number(probability))
+    }
+
+    /// Return a point of a timeline, which is a number, or a date as its serial number, like Excel
+    fn to_timeline_point(value: Expr) -> Result<f64> {
+        match value {
+            Expr::Number(number) => Ok(number),
+            Expr::Error(kind) => Err(Error::Excel(kind)),
+            Expr::Date(_) | Expr::Datetime(_) => Ok(Self::to_serial(&value).unwrap_or_default()),
+            _ => Err(Error::Excel(ErrorKind::Value)),
+        }
+    }
+
+    /// Return the points of the timeline and their values, which are the arguments of `FORECAST.ETS` in the reverse order,
+    /// and they must have the same number of items
+    fn get_timeline(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<Vec<(f64, f64)>> {
+        let values = Self::to_items(self.get_formula(args, rule_name)?);
+        let timeline = Self::to_items(self.get_formula(args, rule_name)?);
+        if values.len() != timeline.len() {
+            return Err(Error::Excel(ErrorKind::NA));
+        }
+
+        timeline
+            .into_iter()
+            .zip(values)
+            .map(|(point, value)| {
+                let value = match value {
+                    Expr::Number(number) => number,
+                    Expr::Error(kind) => return Err(Error::Excel(kind)),
+                    _ => return Err(Error::Excel(ErrorKind::Value)),
+                };
+                Ok((Self::to_timeline_point(point)?, value))
+            })
+            .collect()
+    }
+
+    /// Return the series of a timeline, by the optional data completion, which is 1 for the interpolation or 0 for
+    /// the zeros, and the optional aggregation, which is `AVERAGE` by default
+    fn get_series(&self, points: Vec<(f64, f64)>, args: &mut Iter<Node>, rule_name: &str) -> Result<Series> {
+        let completion = self.get_opt_number(args, rule_name, 1.0)?;
+        let aggregation = self.get_opt_number(args, rule_name, 1.0)?;
+        let aggregation = Aggregation::new(aggregation).ok_or(Error::Excel(ErrorKind::Num))?;
+        if completion != 0.0 && completion != 1.0 {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+        Series::new(points, completion == 1.0, aggregation).ok_or(Error::Excel(ErrorKind::Num))
+    }
+
+    /// Return the series of a timeline and its fitted model, by the optional seasonality, which is 1 for the detected
+    /// season by default, 0 for no season, or the length of the season, and the optional arguments of the series
+    fn get_ets_model(
+        &self,
+        points: Vec<(f64, f64)>,
+        args: &mut Iter<Node>,
+        rule_name: &str,
+    ) -> Result<(Series, Model)> {
+        let seasonality = self.get_opt_number(args, rule_name, 1.0)?.trunc();
+        if !(0.0..=ets::MAX_SEASONALITY).contains(&seasonality) {
+            return Err(Error::Excel(ErrorKind::Num));
+        }
+
+        let series = self.get_series(points, args, rule_name)?;
+        let season = if seasonality == 1.0 {
+            series.seasonality()
+        } else {
+            seasonality as usize
+        };
+        let model = Model::fit(&series.values, season).ok_or(Error::Excel(ErrorKind::Num))?;
+        Ok((series, model))
+    }
+
+    /// Return the number of the steps of a target after the end of a series, which is `#NUM!` if it's before the end
+    fn get_target_steps(&self, args: &mut Iter<Node>, rule_name: &str) -> Result<impl FnOnce(&Series) -> Result<f64>> {
+        let target = Self::to_timeline_point(self.get_formula(args, rule_name)?)?;
+        Ok(move |series: &Series| {
+            let steps = (target - series.end) / series.step;
+            if steps < 0.0 {
+                return Err(Error::Excel(ErrorKind::Num));
+            }
+            Ok(steps)
+        })
+    }
+
+    pub(crate) fn parse_forecast_ets(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let steps = self.get_target_steps(&mut args, &rule_name)?;
+        let points = self.get_timeline(&mut args, &rule_name)?;
+        let (series, model) = self.get_ets_model(points, &mut args, &rule_name)?;
+        Ok(Expr::Number(model.forecast(steps(&series)?)))
+    }
+
+    pub(crate) fn parse_forecast_ets_confint(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let steps = self.get_target_steps(&mut args, &rule_name)?;
+        let points = self.get_timeline(&mut args, &rule_name)?;
+        let confidence = self.get_opt_number(&mut args, &rule_name, 0.95)?;
+        if confidence <= 0.0 || confidence >= 1.0 {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let (series, model) = self.get_ets_model(points, &mut args, &rule_name)?;
+        let quantile = special::inverse_standard_normal((1.0 + confidence) / 2.0);
+        Ok(Expr::Number(model.interval(steps(&series)?, quantile)))
+    }
+
+    /// The season is detected like `FORECAST.ETS`, and it's 0 if there's no season
+    pub(crate) fn parse_forecast_ets_seasonality(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let points = self.get_timeline(&mut args, &rule_name)?;
+        let series = self.get_series(points, &mut args, &rule_name)?;
+        Ok(Expr::Number(series.seasonality() as f64))
+    }
+
+    /// The statistics are 1 for alpha, 2 for beta, 3 for gamma, 4 for MASE, 5 for SMAPE, 6 for MAE, 7 for RMSE,
+    /// and 8 for the step of the timeline, like Excel
+    pub(crate) fn parse_forecast_ets_stat(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let points = self.get_timeline(&mut args, &rule_name)?;
+        let statistic = self.get_number(&mut args, &rule_name)?.trunc();
+        if !(1.0..=8.0).contains(&statistic) {
+            return Ok(Expr::Error(ErrorKind::Num));
+        }
+
+        let (series, model) = self.get_ets_model(points, &mut args, &rule_name)?;
+        let value = match statistic as u8 {
+            1 => model.alpha,
+            2 => model.beta,
+            3 => model.gamma,
+            4 => model.mase(),
+            5 => model.smape(),
+            6 => model.mae(),
+            7 => model.rmse(),
+            _ => series.step,
+        };
+        Ok(Expr::Number(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::Reference, Formula};
+    use chrono::NaiveDate;
+
+    fn assert_number(value: Expr, expected: f64) {
+        match value {
+            Expr::Number(number) => assert!((number - expected).abs() < 1e-9, "{number} != {expected}"),
+            value => panic!("{value:?} is not {expected}"),
+        }
+    }
+
+    /// Assert an array, row by row, where `NAN` is `#N/A`
+    fn assert_array(value: Expr, expected: &[&[f64]]) {
+        let rows = Evaluator::to_rows(value);
+        assert_eq!(rows.len(), expected.len(), "{rows:?}");
+        for (row, expected) in rows.into_iter().zip(expected) {
+            assert_eq!(row.len(), expected.len(), "{row:?}");
+            for (item, &expected) in row.into_iter().zip(*expected) {
+                match item {
+                    Expr::Number(number) => assert!(
+                        (number - expected).abs() <= expected.abs() * 1e-10,
+                        "{number} != {expected}"
+                    ),
+                    Expr::Error(ErrorKind::NA) => assert!(expected.is_nan()),
+                    item => panic!("{item:?} is not {expected}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_parse_statistical_types() {
+        let numbers = [
+            ("=AVERAGE(1, 2, 3, 4)", 2.5),
+            ("=AVERAGE({1, 2; 3, 4}, 5)", 3.0),
+            ("=AVERAGE({1, TRUE, 'a'}, TRUE, '3')", 5.0 / 3.0),
+            ("=AVERAGE(DATE(2020, 1, 1), 43832)", 43_831.5),
+            ("=AVERAGEA({1, TRUE, 'a'}, 2)", 1.0),
+            ("=MEDIAN(3, 1, 2)", 2.0),
+            ("=MEDIAN({4, 1, 3, 2})", 2.5),
+            ("=MODE.SNGL(1, 2, 2, 3, 3)", 2.0),
+            ("=MIN({4, 'a', -1}, 2)", -1.0),
+            ("=MIN({'a'})", 0.0),
+            ("=MINA({4, TRUE, 3})", 1.0),
+            ("=MINA({4, 'a', 3})", 0.0),
+            ("=MAX(1, {5, 3}, 4)", 5.0),
+            ("=MAXA({-4, TRUE, -3})", 1.0),
+            ("=COUNT(1, 'a', '2', TRUE, {1, 'b', TRUE, #N/A}, #N/A)", 4.0),
+            ("=COUNTA(1, 'a', {1, '', #N/A}, #N/A)", 6.0),
+            ("=COUNTBLANK({1, ''})", 1.0),
+            ("=STDEV.S(2, 4, 4, 4, 5, 5, 7, 9)", 2.138_089_935_299_395),
+            ("=STDEV.P(2, 4, 4, 4, 5, 5, 7, 9)", 2.0),
+            ("=VAR.S({1, 2, 3, 4})", 1.666_666_666_666_667),
+            ("=VAR.P({1, 2, 3, 4})", 1.25),
+            ("=AVEDEV(4, 5, 6, 7, 5, 4, 3)", 1.020_408_163_265_306),
+            ("=DEVSQ(4, 5, 8, 7, 11, 4, 3)", 48.0),
+            ("=GEOMEAN(4, 5, 8, 7, 11, 4, 3)", 5.476_986_969_656_962),
+            ("=HARMEAN(4, 5, 8, 7, 11, 4, 3)", 5.028_375_962_061_728),
+            ("=KURT(3, 4, 5, 2, 3, 4, 5, 6, 4, 7)", -0.151_799_637_208_43),
+            ("=SKEW(3, 4, 5, 2, 3, 4, 5, 6, 4, 7)", 0.359_543_071_407_55),
+            (
+                "=TRIMMEAN({4, 5, 6, 7, 2, 3, 4, 5, 1, 2, 3}, 0.2)",
+                3.777_777_777_777_778,
+            ),
+            ("=GAMMA(2.5)", 1.329_340_388_179_137),
+            ("=GAMMA(-3.75)", 0.267_866_128_861_416_6),
+            ("=GAMMA(0.5)", 1.772_453_850_905_516),
+            ("=GAMMA(5)", 24.0),
+            ("=GAMMALN(4.5)", 2.453_736_570_842_442),
+            ("=GAMMALN(0.1)", 2.252_712_651_734_206),
+            ("=GAMMALN(1000)", 5_905.220_423_209_181),
+            ("=GAMMALN.PRECISE(4.5)", 2.453_736_570_842_442),
+            ("=NORM.S.INV(0.5)", 0.0),
+            ("=T.DIST(0, 5, TRUE)", 0.5),
+            ("=T.INV.2T(1, 5)", 0.0),
+            ("=CHISQ.INV(0, 2)", 0.0),
+            ("=CHISQ.INV.RT(1, 2)", 0.0),
+            ("=BINOM.DIST(0, 10, 0, FALSE)", 1.0),
+            ("=BINOM.DIST(10, 10, 0.3, TRUE)", 1.0),
+            ("=BINOM.INV(6, 0.5, 0.75)", 4.0),
+            ("=BINOM.INV(100, 0.5, 0)", 0.0),
+            ("=BINOM.INV(100, 0.5, 0.5)", 50.0),
+            ("=POISSON.DIST(0, 0, FALSE)", 1.0),
+            ("=GAMMA.DIST(0, 1, 2, FALSE)", 0.5),
+            ("=BETA.INV(1, 8, 10, 1, 3)", 3.0),
+            (
+                "=SLOPE({2, 3, 9, 1, 8, 7, 5}, {6, 5, 11, 7, 5, 4, 4})",
+                0.305_555_555_555_555_6,
+            ),
+            ("=SLOPE({1, 'a', 3, 5}, {1, 2, 2, TRUE})", 2.0),
+            (
+                "=INTERCEPT({2, 3, 9, 1, 8, 7, 5}, {6, 5, 11, 7, 5, 4, 4})",
+                3.166_666_666_666_666_5,
+            ),
+            (
+                "=RSQ({2, 3, 9, 1, 8, 7, 5}, {6, 5, 11, 7, 5, 4, 4})",
+                0.057_950_191_570_881_23,
+            ),
+            (
+                "=STEYX({2, 3, 9, 1, 8, 7, 5}, {6, 5, 11, 7, 5, 4, 4})",
+                3.305_718_950_210_041_5,
+            ),
+            ("=CORREL({3, 2, 4, 5, 6}, {9, 7, 12, 15, 17})", 0.997_054_485_501_581_6),
+            ("=PEARSON({3, 2, 4, 5, 6}, {9, 7, 12, 15, 17})", 0.997_054_485_501_581_6),
+            ("=COVARIANCE.P({3, 2, 4, 5, 6}, {9, 7, 12, 15, 17})", 5.2),
+            ("=COVARIANCE.S({2, 4, 8}, {5, 11, 12})", 9.666_666_666_666_666),
+            (
+                "=FORECAST.LINEAR(30, {6, 7, 9, 15, 21}, {20, 28, 31, 38, 40})",
+                10.607_253_086_419_753,
+            ),
+            ("=TREND({1; 9; 6; 7; 10}, {0; 4; 2; 3; 5}, 6)", 12.351_351_351_351_35),
+            (
+                "=T.TEST({3, 4, 5, 8, 9, 1, 2, 4, 5}, {6, 19, 3, 2, 14, 4, 5, 17, 1}, 1, 1)",
+                0.098_007_892_462_641_05,
+            ),
+            (
+                "=T.TEST({3, 4, 5, 8, 9, 1, 2, 4, 5}, {6, 19, 3, 2, 14, 4, 5, 17, 1}, 1, 2)",
+                0.095_997_943_380_198_11,
+            ),
+            (
+                "=T.TEST({3, 4, 5, 8, 9, 1, 2, 4, 5}, {6, 19, 3, 2, 14, 4, 5, 17, 1}, 1, 3)",
+                0.101_146_961_684_338_94,
+            ),
+            (
+                "=T.TEST({3, 4, 5, 8, 9, 1, 2, 4, 5}, {6, 19, 3, 2, 14, 4, 5, 17, 1}, 2, 1)",
+                0.196_015_784_925_282_1,
+            ),
+            (
+                "=T.TEST({3, 4, 5, 8, 9, 1, 2, 4, 5}, {6, 19, 3, 2, 14, 4, 5, 17, 1}, 2, 2)",
+                0.191_995_886_760_396_22,
+            ),
+            (
+                "=T.TEST({3, 4, 5, 8, 9, 1, 2, 4, 5}, {6, 19, 3, 2, 14, 4, 5, 17, 1}, 2, 3)",
+                0.202_293_923_368_677_88,
+            ),
+            ("=Z.TEST({3, 6, 7, 8, 6, 5, 4, 2, 1, 9}, 4)", 0.090_574_196_851_363_77),
+            ("=Z.TEST({3, 6, 7, 8, 6, 5, 4, 2, 1, 9}, 6)", 0.863_043_389_129_529_9),
+            (
+                "=Z.TEST({3, 6, 7, 8, 6, 5, 4, 2, 1, 9}, 4, 3)",
+                0.123_125_849_846_263_49,
+            ),
+            (
+                "=CHISQ.TEST({58, 35; 11, 25; 10, 23}, {45.35, 47.65; 17.56, 18.44; 16.09, 16.91})",
+                3.081_920_170_083_094_7e-4,
+            ),
+            ("=CHISQ.TEST({10, 20, 30}, {15, 20, 25})", 0.263_597_138_115_726_77),
+            (
+                "=F.TEST({6, 7, 9, 15, 21}, {20, 28, 31, 38, 40})",
+                0.648_317_846_786_174_5,
+            ),
+            ("=LARGE({3, 4; 5, 2; 3, 4; 5, 6; 4, 7}, 3)", 5.0),
+            ("=LARGE({3, 4; 5, 2; 3, 4; 5, 6; 4, 7}, 7)", 4.0),
+            ("=SMALL({3, 4, 5, 2, 3, 4, 6, 4, 7}, 4)", 4.0),
+            ("=SMALL({1, 2, 3}, 1.2)", 2.0),
+            ("=RANK.EQ(7, {7, 3.5, 3.5, 1, 2}, 1)", 5.0),
+            ("=RANK.EQ(2, {7, 3.5, 3.5, 1, 2})", 4.0),
+            ("=RANK.EQ(3.5, {7, 3.5, 3.5, 1, 2}, 1)", 3.0),
+            ("=RANK.AVG(3.5, {7, 3.5, 3.5, 1, 2}, 1)", 3.5),
+            ("=RANK.AVG(94, {89, 88, 92, 101, 94, 97, 95})", 4.0),
+            ("=PERCENTILE.INC({1, 3, 2, 4}, 0.3)", 1.9),
+            ("=PERCENTILE.INC({1, 3, 2, 4}, 1)", 4.0),
+            ("=PERCENTILE.EXC({1, 2, 3, 6, 6, 6, 7, 8, 9}, 0.25)", 2.5),
+            ("=PERCENTILE.EXC({1, 2, 3, 6, 6, 6, 7, 8, 9}, 0.1)", 1.0),
+            ("=QUARTILE.INC({1, 2, 4, 7, 8, 9, 10, 12}, 1)", 3.5),
+            ("=QUARTILE.INC({1, 2, 4, 7, 8, 9, 10, 12}, 4.5)", 12.0),
+            ("=QUARTILE.EXC({6, 7, 15, 36, 39, 40, 41, 42, 43, 47, 49}, 1)", 15.0),
+            ("=QUARTILE.EXC({6, 7, 15, 36, 39, 40, 41, 42, 43, 47, 49}, 3)", 43.0),
+            ("=PERCENTRANK.INC({13, 12, 11, 8, 4, 3, 2, 1, 1, 1}, 2)", 0.333),
+            ("=PERCENTRANK.INC({13, 12, 11, 8, 4, 3, 2, 1, 1, 1}, 4)", 0.555),
+            ("=PERCENTRANK.INC({13, 12, 11, 8, 4, 3, 2, 1, 1, 1}, 8)", 0.666),
+            ("=PERCENTRANK.INC({13, 12, 11, 8, 4, 3, 2, 1, 1, 1}, 5)", 0.583),
+            ("=PERCENTRANK.INC({13, 12, 11, 8, 4, 3, 2, 1, 1, 1}, 5, 5)", 0.583_33),
+            ("=PERCENTRANK.INC({5}, 5)", 1.0),
+            ("=PERCENTRANK.EXC({1, 2, 3, 6, 6, 6, 7, 8, 9}, 7)", 0.7),
+            ("=PERCENTRANK.EXC({1, 2, 3, 6, 6, 6, 7, 8, 9}, 5.43)", 0.381),
+            ("=PERCENTRANK.EXC({1, 2, 3, 6, 6, 6, 7, 8, 9}, 5.43, 1)", 0.3),
+            ("=PROB({0, 1, 2, 3}, {0.2, 0.3, 0.1, 0.4}, 2)", 0.1),
+            ("=PROB({0, 1, 2, 3}, {0.2, 0.3, 0.1, 0.4}, 1, 3)", 0.8),
+            ("=PERMUT(100, 3)", 970_200.0),
+            ("=PERMUT(3, 2)", 6.0),
+            ("=PERMUT(3.9, 2.5)", 6.0),
+            ("=PERMUTATIONA(3, 2)", 9.0),
+            ("=PERMUTATIONA(2, 0)", 1.0),
+        ];
+        for (formula, expected) in numbers {
+            let formula = Formula::new(formula).unwrap();
+            assert_number(formula.parse().unwrap(), expected);
+        }
+
+        // The distributions are accurate to about 1e-12, relative to the reference values
+        let distributions = [
+            ("=NORM.DIST(42, 40, 1.5, TRUE)", 0.908_788_780_274_132_1),
+            ("=NORM.DIST(42, 40, 1.5, FALSE)", 0.109_340_049_783_995_75),
+            ("=NORM.INV(0.908789, 40, 1.5)", 42.000_002_009_566_16),
+            ("=NORM.S.DIST(1.333333, TRUE)", 0.908_788_725_604_095_1),
+            ("=NORM.S.DIST(-10, TRUE)", 7.619_853_024_160_525e-24),
+            ("=NORM.S.DIST(1.333333, FALSE)", 0.164_010_147_569_367_24),
+            ("=NORM.S.INV(0.908789)", 1.333_334_673_044_107_6),
+            ("=NORM.S.INV(1E-20)", -9.262_340_089_798_407),
+            ("=LOGNORM.DIST(4, 3.5, 1.2, TRUE)", 0.039_083_555_706_800_47),
+            ("=LOGNORM.DIST(4, 3.5, 1.2, FALSE)", 0.017_617_596_681_819_225),
+            ("=LOGNORM.INV(0.039084, 3.5, 1.2)", 4.000_025_218_680_635),
+            ("=PHI(0.75)", 0.301_137_432_154_804_4),
+            ("=T.DIST(60, 1, TRUE)", 0.994_695_326_367_376_7),
+            ("=T.DIST(8, 3, FALSE)", 7.369_065_209_469_263e-4),
+            ("=T.DIST(-1.5, 4.5, TRUE)", 0.100_109_542_828_076_76),
+            ("=T.DIST.2T(1.959999998, 60)", 0.054_644_929_975_920_874),
+            ("=T.DIST.RT(1.959999998, 60)", 0.027_322_464_987_960_437),
+            ("=T.INV(0.75, 2)", 0.816_496_580_927_726),
+            ("=T.INV(0.01, 10)", -2.763_769_458_112_696),
+            ("=T.INV.2T(0.546449, 60)", 0.606_533_075_825_755),
+            ("=CHISQ.DIST(0.5, 1, TRUE)", 0.520_499_877_813_046_5),
+            ("=CHISQ.DIST(2, 3, FALSE)", 0.207_553_748_710_297_36),
+            ("=CHISQ.DIST(2, 3.9, FALSE)", 0.207_553_748_710_297_36),
+            ("=CHISQ.DIST.RT(18.307, 10)", 0.050_000_589_091_398_1),
+            ("=CHISQ.INV(0.93, 1)", 3.283_020_286_759_535_7),
+            ("=CHISQ.INV.RT(0.050001, 10)", 18.306_973_456_961_057),
+            ("=F.DIST(15.2069, 6, 4, TRUE)", 0.990_000_043_002_762_7),
+            ("=F.DIST(15.2069, 6, 4, FALSE)", 0.001_223_791_708_783_173_1),
+            ("=F.DIST.RT(15.2069, 6, 4)", 0.009_999_956_997_237_311),
+            ("=F.INV(0.01, 6, 4)", 0.109_309_914_124_578_54),
+            ("=F.INV.RT(0.01, 6, 4)", 15.206_864_861_157_53),
+            ("=BINOM.DIST(6, 10, 0.5, FALSE)", 0.205_078_125),
+            ("=BINOM.DIST(6, 10, 0.5, TRUE)", 0.828_125),
+            ("=BINOM.DIST(3, 40, 0.3, TRUE)", 5.979_284_466_825_944e-4),
+            ("=BINOM.DIST.RANGE(60, 0.75, 48)", 0.083_974_967_429_047_5),
+            ("=BINOM.DIST.RANGE(60, 0.75, 45, 50)", 0.523_629_793_471_887_2),
+            ("=NEGBINOM.DIST(10, 5, 0.25, TRUE)", 0.313_514_058_478_176_6),
+            ("=NEGBINOM.DIST(10, 5, 0.25, FALSE)", 0.055_048_660_375_177_86),
+            ("=HYPGEOM.DIST(1, 4, 8, 20, TRUE)", 0.465_428_276_573_787_44),
+            ("=HYPGEOM.DIST(1, 4, 8, 20, FALSE)", 0.363_261_093_911_248_7),
+            ("=POISSON.DIST(2, 5, TRUE)", 0.124_652_019_483_081_13),
+            ("=POISSON.DIST(2, 5, FALSE)", 0.084_224_337_488_568_33),
+            ("=EXPON.DIST(0.2, 10, TRUE)", 0.864_664_716_763_387_3),
+            ("=EXPON.DIST(0.2, 10, FALSE)", 1.353_352_832_366_126_8),
+            ("=GAMMA.DIST(10.00001131, 9, 2, FALSE)", 0.032_639_130_418_294),
+            ("=GAMMA.DIST(10.00001131, 9, 2, TRUE)", 0.068_094_003_869_787_33),
+            ("=GAMMA.INV(0.068094, 9, 2)", 10.000_011_191_437_178),
+            ("=BETA.DIST(2, 8, 10, TRUE, 1, 3)", 0.685_470_581_054_687_5),
+            ("=BETA.DIST(2, 8, 10, FALSE, 1, 3)", 1.483_764_648_437_5),
+            ("=BETA.DIST(0.3, 2.5, 0.5, TRUE)", 0.018_927_124_071_945_654),
+            ("=BETA.INV(0.685470581, 8, 10, 1, 3)", 1.999_999_999_963_142_6),
+            ("=WEIBULL.DIST(105, 20, 100, TRUE)", 0.929_581_390_069_276_8),
+            ("=WEIBULL.DIST(105, 20, 100, FALSE)", 0.035_588_864_024_504_38),
+            ("=CONFIDENCE.NORM(0.05, 2.5, 50)", 0.692_951_912_174_839),
+            ("=CONFIDENCE.T(0.05, 1, 50)", 0.284_196_855_495_729_9),
+        ];
+        for (formula, expected) in distributions {
+            let formula = Formula::new(formula).unwrap();
+            match formula.parse().unwrap() {
+                Expr::Number(number) => assert!(
+                    (number - expected).abs() <= expected.abs() * 1e-12,
+                    "{formula:?}: {number} != {expected}"
+                ),
+                value => panic!("{formula:?}: {value:?} is not {expected}"),
+            }
+        }
+
+        let errors = [
+            ("=AVERAGE({'a'})", ErrorKind::DivZero),
+            ("=AVERAGE(1, 'a')", ErrorKind::Value),
+            ("=AVERAGE({1, #N/A})", ErrorKind::NA),
+            ("=MAX(1, 1/0)", ErrorKind::DivZero),
+            ("=MEDIAN({'a'})", ErrorKind::Num),
+            ("=MODE.SNGL(1, 2, 3)", ErrorKind::NA),
+            ("=STDEV.S(1)", ErrorKind::DivZero),
+            ("=VAR.P({'a'})", ErrorKind::DivZero),
+            ("=GEOMEAN(1, 0)", ErrorKind::Num),
+            ("=HARMEAN(1, -1)", ErrorKind::Num),
+            ("=KURT(1, 2, 3)", ErrorKind::DivZero),
+            ("=SKEW(1, 1, 1)", ErrorKind::DivZero),
+            ("=TRIMMEAN({1, 2}, 1)", ErrorKind::Num),
+            ("=GAMMA(0)", ErrorKind::Num),
+            ("=GAMMA(-2)", ErrorKind::Num),
+            ("=GAMMA(172)", ErrorKind::Num),
+            ("=GAMMALN(0)", ErrorKind::Num),
+            ("=GAMMALN.PRECISE(-1.5)", ErrorKind::Num),
+            ("=NORM.DIST(1, 0, 0, TRUE)", ErrorKind::Num),
+            ("=NORM.INV(1, 0, 1)", ErrorKind::Num),
+            ("=NORM.S.INV(0)", ErrorKind::Num),
+            ("=NORM.S.DIST('a', TRUE)", ErrorKind::Value),
+            ("=LOGNORM.DIST(0, 3.5, 1.2, TRUE)", ErrorKind::Num),
+            ("=T.DIST(1, 0.5, TRUE)", ErrorKind::Num),
+            ("=T.DIST.2T(-1, 2)", ErrorKind::Num),
+            ("=T.INV(0, 2)", ErrorKind::Num),
+            ("=T.INV.2T(1.5, 2)", ErrorKind::Num),
+            ("=CHISQ.DIST(-1, 2, TRUE)", ErrorKind::Num),
+            ("=CHISQ.DIST(1, 0.5, TRUE)", ErrorKind::Num),
+            ("=CHISQ.INV(1, 2)", ErrorKind::Num),
+            ("=CHISQ.INV.RT(0, 2)", ErrorKind::Num),
+            ("=F.DIST(1, 1, 1E11, TRUE)", ErrorKind::Num),
+            ("=F.INV.RT(-0.1, 6, 4)", ErrorKind::Num),
+            ("=BINOM.DIST(11, 10, 0.5, TRUE)", ErrorKind::Num),
+            ("=BINOM.DIST(1, 10, 1.5, TRUE)", ErrorKind::Num),
+            ("=BINOM.INV(6, 0.5, 2)", ErrorKind::Num),
+            ("=BINOM.DIST.RANGE(60, 0.75, 50, 45)", ErrorKind::Num),
+            ("=NEGBINOM.DIST(10, 0, 0.25, TRUE)", ErrorKind::Num),
+            ("=HYPGEOM.DIST(5, 4, 8, 20, TRUE)", ErrorKind::Num),
+            ("=POISSON.DIST(2, -1, TRUE)", ErrorKind::Num),
+            ("=EXPON.DIST(0.2, 0, TRUE)", ErrorKind::Num),
+            ("=GAMMA.DIST(1, 0, 2, TRUE)", ErrorKind::Num),
+            ("=GAMMA.INV(1, 9, 2)", ErrorKind::Num),
+            ("=BETA.DIST(4, 8, 10, TRUE, 1, 3)", ErrorKind::Num),
+            ("=BETA.INV(0, 8, 10)", ErrorKind::Num),
+            ("=WEIBULL.DIST(-1, 20, 100, TRUE)", ErrorKind::Num),
+            ("=CONFIDENCE.NORM(1, 2.5, 50)", ErrorKind::Num),
+            ("=CONFIDENCE.T(0.05, 1, 1)", ErrorKind::DivZero),
+            ("=SLOPE({1, 2}, {1, 2, 3})", ErrorKind::NA),
+            ("=SLOPE({1, 2}, {3, 3})", ErrorKind::DivZero),
+            ("=STEYX({1, 2}, {3, 4})", ErrorKind::DivZero),
+            ("=CORREL({1, 2, 3}, {1, 1, 1})", ErrorKind::DivZero),
+            ("=CORREL({1, #N/A}, {1, 2})", ErrorKind::NA),
+            ("=COVARIANCE.S({1}, {2})", ErrorKind::DivZero),
+            ("=LINEST({1, 'a'})", ErrorKind::Value),
+            ("=LINEST({1; 2; 3}, {1, 2; 3, 4})", ErrorKind::Ref),
+            ("=LOGEST({1, -2, 3})", ErrorKind::Num),
+            ("=TREND({1; 2; 3}, {1, 2; 3, 4; 5, 6}, {1, 2, 3})", ErrorKind::Ref),
+            ("=T.TEST({1, 2, 3}, {2, 3, 5}, 3, 1)", ErrorKind::Num),
+            ("=T.TEST({1, 2, 3}, {2, 3, 5}, 2, 4)", ErrorKind::Num),
+            ("=T.TEST({1, 2, 3}, {2, 3}, 2, 1)", ErrorKind::NA),
+            ("=T.TEST({1, 2, 3}, {2, 3, 4}, 2, 1)", ErrorKind::DivZero),
+            ("=T.TEST({1}, {2, 3, 4}, 2, 2)", ErrorKind::DivZero),
+            ("=Z.TEST({}, 4)", ErrorKind::NA),
+            ("=Z.TEST({1, 2}, 4, 0)", ErrorKind::DivZero),
+            ("=Z.TEST({1, 2}, 4, -1)", ErrorKind::Num),
+            ("=CHISQ.TEST({1, 2}, {1, 2, 3})", ErrorKind::NA),
+            ("=CHISQ.TEST({1}, {1})", ErrorKind::NA),
+            ("=CHISQ.TEST({1, 2}, {0, 2})", ErrorKind::DivZero),
+            ("=F.TEST({1, 2, 3}, {4, 4, 4})", ErrorKind::DivZero),
+            ("=F.TEST({1}, {4, 5, 6})", ErrorKind::DivZero),
+            ("=LARGE({1, 2}, 3)", ErrorKind::Num),
+            ("=LARGE({'a'}, 1)", ErrorKind::Num),
+            ("=SMALL({1, 2}, 0)", ErrorKind::Num),
+            ("=RANK.EQ(5, {1, 2})", ErrorKind::NA),
+            ("=RANK.AVG(5, {1, 2}, 1)", ErrorKind::NA),
+            ("=PERCENTILE.INC({1, 2}, 1.5)", ErrorKind::Num),
+            ("=PERCENTILE.INC({'a'}, 0.5)", ErrorKind::Num),
+            ("=PERCENTILE.EXC({1, 2, 3}, 0.1)", ErrorKind::Num),
+            ("=PERCENTILE.EXC({1, 2, 3}, 1)", ErrorKind::Num),
+            ("=QUARTILE.INC({1, 2}, 5)", ErrorKind::Num),
+            ("=QUARTILE.EXC({1, 2, 3}, 0)", ErrorKind::Num),
+            ("=QUARTILE.EXC({1, 2}, 1)", ErrorKind::Num),
+            ("=PERCENTRANK.INC({1, 2}, 3)", ErrorKind::NA),
+            ("=PERCENTRANK.INC({1, 2}, 1, 0)", ErrorKind::Num),
+            ("=PERCENTRANK.EXC({1, 2}, 0.5)", ErrorKind::NA),
+            ("=PROB({0, 1}, {0.5, 0.6}, 1)", ErrorKind::Num),
+            ("=PROB({0, 1}, {-0.5, 1.5}, 1)", ErrorKind::Num),
+            ("=PROB({0, 1}, {0.5}, 1)", ErrorKind::NA),
+            ("=PERMUT(0, 0)", ErrorKind::Num),
+            ("=PERMUT(2, 3)", ErrorKind::Num),
+            ("=PERMUT(1000, 500)", ErrorKind::Num),
+            ("=PERMUTATIONA(-2, 2)", ErrorKind::Num),
+            ("=PERMUTATIONA(10, 400)", ErrorKind::Num),
+            ("=FORECAST.ETS(1, {1, 2, 3}, {0, 1, 2})", ErrorKind::Num),
+            ("=FORECAST.ETS(3, {1, 2, 3}, {0, 1})", ErrorKind::NA),
+            ("=FORECAST.ETS(3, {1, 2, 3}, {0, 1.5, 2.5})", ErrorKind::Num),
+            ("=FORECAST.ETS(3, {1, 2, 3}, {0, 1, 2}, 2)", ErrorKind::Num),
+            ("=FORECAST.ETS(3, {1, 2, 3}, {0, 1, 2}, 8761)", ErrorKind::Num),
+            ("=FORECAST.ETS(3, {1, 2, 3}, {0, 1, 2}, 1, 2)", ErrorKind::Num),
+            ("=FORECAST.ETS(3, {1, 2, 3}, {0, 1, 2}, 1, 1, 8)", ErrorKind::Num),
+            ("=FORECAST.ETS(3, {1, 2, 'a'}, {0, 1, 2})", ErrorKind::Value),
+            ("=FORECAST.ETS.CONFINT(3, {1, 2, 3}, {0, 1, 2}, 1)", ErrorKind::Num),
+            ("=FORECAST.ETS.STAT({1, 2, 3}, {0, 1, 2}, 9)", ErrorKind::Num),
+            (
+                "=FORECAST.ETS.SEASONALITY({1, 2, 3, 4, 5}, {0, 1, 2, 3, 1e12})",
+                ErrorKind::Num,
+            ),
+            ("=FORECAST.ETS(3, {1, 2, 3}, {0, 1, 100000})", ErrorKind::Num),
+            (
+                "=FORECAST.ETS(12, {1, 3, 5, 7, 9, 11, 21}, {0, 1, 2, 3, 4, 5, 10})",
+                ErrorKind::Num,
+            ),
+        ];
+        for (formula, kind) in errors {
+            let formula = Formula::new(formula).unwrap();
+            assert_eq!(formula.parse().unwrap(), Expr::Error(kind));
+        }
+
+        let office_x =
+            "{2310, 2, 2, 20; 2333, 2, 2, 12; 2356, 3, 1.5, 33; 2379, 3, 2, 43; 2402, 2, 3, 53; 2425, 4, 2, 23; \
+                        2448, 2, 1.5, 99; 2471, 2, 2, 34; 2494, 3, 3, 23; 2517, 4, 4, 55; 2540, 2, 3, 22}";
+        let office_y = "{142000; 144000; 151000; 150000; 139000; 169000; 126000; 142900; 163000; 169000; 149000}";
+        let arrays: [(String, &[&[f64]]); 11] = [
+            (
+                format!("=LINEST({office_y}, {office_x}, TRUE, TRUE)"),
+                &[
+                    &[
+                        -234.237_164_471_202_42,
+                        2_553.210_660_391_538,
+                        12_529.768_167_086_751,
+                        27.641_387_366_020_286,
+                        52_317.830_507_291_32,
+                    ],
+                    &[
+                        13.268_011_475_500_364,
+                        530.669_151_930_378_3,
+                        400.066_838_193_953_1,
+                        5.429_374_041_545_316,
+                        12_237.361_602_862_353,
+                    ],
+                    &[0.996_747_993_384_510_1, 970.578_462_928_506_3, f64::NAN, f64::NAN, f64::NAN],
+                    &[459.753_674_225_392_44, 6.0, f64::NAN, f64::NAN, f64::NAN],
+                    &[1_732_393_319.229_250_7, 5_652_135.316_203_97, f64::NAN, f64::NAN, f64::NAN],
+                ],
+            ),
+            (
+                "=LINEST({1; 9; 6; 7; 10}, {0; 4; 2; 3; 5}, TRUE, TRUE)".to_string(),
+                &[
+                    &[1.797_297_297_297_297_4, 1.567_567_567_567_567_5],
+                    &[0.177_056_252_315_617_53, 0.581_866_220_043_540_2],
+                    &[0.971_709_514_392_441_2, 0.681_149_002_762_217_9],
+                    &[103.042_718_446_601_94, 3.0],
+                    &[47.808_108_108_108_11, 1.391_891_891_891_891_9],
+                ],
+            ),
+            (
+                "=LINEST({3; 4; 9; 8; 14}, {1, 2; 2, 1; 3, 5; 4, 3; 5, 8}, FALSE, TRUE)".to_string(),
+                &[
+                    &[0.905_448_717_948_718, 1.376_602_564_102_564, 0.0],
+                    &[0.099_199_451_483_694_15, 0.135_752_094_307_673_82, f64::NAN],
+                    &[0.999_084_874_597_169_7, 0.334_133_654_613_388_4, f64::NAN],
+                    &[1_637.619_617_224_880_4, 3.0, f64::NAN],
+                    &[365.665_064_102_564_1, 0.334_935_897_435_897_4, f64::NAN],
+                ],
+            ),
+            // Each row is a variable, if the known `y` values are a row
+            (
+                "=LINEST({3, 4, 9, 8, 14}, {1, 2, 3, 4, 5; 2, 1, 5, 3, 8}, FALSE)".to_string(),
+                &[&[0.905_448_717_948_718, 1.376_602_564_102_564, 0.0]],
+            ),
+            // The second variable is twice the first one, so it's skipped
+            (
+                "=LINEST({3; 5; 8; 9}, {1, 2; 2, 4; 3, 6; 4, 8})".to_string(),
+                &[&[0.0, 2.1, 1.0]],
+            ),
+            (
+                "=LOGEST({33100; 47300; 69000; 102000; 150000; 220000}, {11; 12; 13; 14; 15; 16}, TRUE, TRUE)"
+                    .to_string(),
+                &[
+                    &[1.463_275_628_116_175_8, 495.304_770_158_727_54],
+                    &[0.002_633_402_891_425_061_3, 0.035_834_282_435_717_624],
+                    &[0.999_808_619_775_817_6, 0.011_016_314_665_073_015],
+                    &[20_896.801_099_420_32, 4.0],
+                    &[2.536_018_829_938_566_6, 4.854_367_551_996_111_3e-4],
+                ],
+            ),
+            (
+                "=TREND({2, 4, 7})".to_string(),
+                &[&[1.833_333_333_333_333_3, 4.333_333_333_333_333, 6.833_333_333_333_333]],
+            ),
+            (
+                "=TREND({133890; 135000; 135790; 137300; 138130; 139100; 139900; 141120; 141890; 143230; 144000; 145290}, \
+                  {1; 2; 3; 4; 5; 6; 7; 8; 9; 10; 11; 12}, {13; 14; 15; 16; 17})"
+                    .to_string(),
+                &[
+                    &[146_171.515_151_515_14],
+                    &[147_189.696_969_696_96],
+                    &[148_207.878_787_878_78],
+                    &[149_226.060_606_060_6],
+                    &[150_244.242_424_242_43],
+                ],
+            ),
+            (
+                "=TREND({3; 4; 9; 8; 14}, {1, 2; 2, 1; 3, 5; 4, 3; 5, 8}, {6, 9; 1, 1}, FALSE)".to_string(),
+                &[&[16.408_653_846_153_847], &[2.282_051_282_051_282]],
+            ),
+            (
+                "=GROWTH({33100, 47300, 69000, 102000, 150000, 220000}, {11, 12, 13, 14, 15, 16}, {17, 18})".to_string(),
+                &[&[320_196.718_363_473_2, 468_536.054_184_049_5]],
+            ),
+            (
+                "=TREND({1; 9; 6; 7; 10}, {0; 4; 2; 3; 5}, {6})".to_string(),
+                &[&[12.351_351_351_351_35]],
+            ),
+        ];
+        for (formula, expected) in arrays {
+            let formula = Formula::new(&formula).unwrap();
+            assert_array(formula.parse().unwrap(), expected);
+        }
+
+        // The counts of the bins are in their order, and the last one is of the greater numbers
+        let frequencies = [
+            (
+                "=FREQUENCY({79, 85, 78, 85, 50, 81, 95, 88, 97}, {70, 79, 89})",
+                [1.0, 2.0, 4.0, 2.0].as_slice(),
+            ),
+            ("=FREQUENCY({1, 2, 3, 4, 5}, {4, 2})", &[2.0, 2.0, 1.0]),
+            ("=FREQUENCY({1, 2, 3, 4, 5}, {2, 2})", &[2.0, 0.0, 3.0]),
+            ("=FREQUENCY({1, 2; 3, 'a'}, {})", &[3.0]),
+        ];
+        for (formula, expected) in frequencies {
+            let formula = Formula::new(formula).unwrap();
+            let rows = expected.iter().map(|count| vec![Expr::Number(*count)]).collect();
+            assert_eq!(formula.parse().unwrap(), Expr::from_rows(rows));
+        }
+
+        // A trend with a season of 4 is forecast exactly, and the noisy one is fitted like a grid search of the parameters
+        let seasonal =
+            "{13, 9.5, 7, 13.5, 15, 11.5, 9, 15.5, 17, 13.5, 11, 17.5, 19, 15.5, 13, 19.5, 21, 17.5, 15, 21.5, \
+                        23, 19.5, 17, 23.5}";
+        let noisy =
+            "{112.6, 99.9, 96.1, 107.5, 115.8, 105.1, 102, 114.1, 126.4, 113.3, 113.1, 123.2, 134, 120.9, 117, \
+                     134.1, 142.6, 133.8, 128.4, 140.7, 152.5, 137.4, 137.8, 148.3}";
+        let timeline = "{0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23}";
+        let forecasts = [
+            (format!("=FORECAST.ETS(30, {seasonal}, {timeline})"), 21.0),
+            (format!("=FORECAST.ETS(24.5, {seasonal}, {timeline})"), 23.25),
+            (format!("=FORECAST.ETS(23, {seasonal}, {timeline}, 4)"), 23.5),
+            (format!("=FORECAST.ETS.CONFINT(30, {seasonal}, {timeline})"), 0.0),
+            (format!("=FORECAST.ETS.SEASONALITY({seasonal}, {timeline})"), 4.0),
+            (format!("=FORECAST.ETS.STAT({seasonal}, {timeline}, 6)"), 0.0),
+            (format!("=FORECAST.ETS.SEASONALITY({noisy}, {timeline})"), 4.0),
+            (format!("=FORECAST.ETS.STAT({noisy}, {timeline}, 3)"), 0.0),
+            (format!("=FORECAST.ETS.STAT({noisy}, {timeline}, 7)"), 1.958_219_55),
+            (format!("=FORECAST.ETS.STAT({noisy}, {timeline}, 8)"), 1.0),
+            ("=FORECAST.ETS(10, {5, 7, 9, 11}, {1, 2, 3, 4})".to_string(), 23.0),
+            (
+                "=FORECAST.ETS.SEASONALITY({5, 7, 9, 11}, {1, 2, 3, 4})".to_string(),
+                0.0,
+            ),
+            (
+                "=FORECAST.ETS(7, {5, 7, 11, 13, 13}, {1, 2, 4, 5, 5})".to_string(),
+                17.0,
+            ),
+            (
+                "=FORECAST.ETS(7, {5, 7, 11, 6, 7}, {1, 2, 4, 5, 5}, 0, 1, 7)".to_string(),
+                17.0,
+            ),
+            ("=FORECAST.ETS.STAT({1, 2, 3, 5}, {0, 2, 4, 8}, 8)".to_string(), 2.0),
+            // 3 of the 10 points are missing, which is the most of Excel
+            (
+                "=FORECAST.ETS(12, {1, 3, 5, 7, 9, 11, 19}, {0, 1, 2, 3, 4, 5, 9})".to_string(),
+                25.0,
+            ),
+        ];
+        for (formula, expected) in forecasts {
+            let formula = Formula::new(&formula).unwrap();
+            match formula.parse().unwrap() {
+                Expr::Number(number) => assert!((number - expected).abs() < 1e-6, "{number} != {expected}"),
+                value => panic!("{value:?} is not {expected}"),
+            }
+        }
+
+        // The interval of a distant target is calculated at once, and it's wider than the one of a near target
+        let intervals = ["1e11", "1e5"].map(|target| {
+            let formula = format!("=FORECAST.ETS.CONFINT({target}, {{1, 2, 3, 4, 5, 7}}, {{1, 2, 3, 4, 5, 6}})");
+            match Formula::new(&formula).unwrap().parse().unwrap() {
+                Expr::Number(number) => number,
+                value => panic!("{value:?} is not a number"),
+            }
+        });
+        assert!(intervals[0] > intervals[1] && intervals[1] > 0.0, "{intervals:?}");
+
+        let formula = Formula::new("=MODE.MULT({1, 2, 3, 3, 2, 4})").unwrap();
+        let value = formula.parse().unwrap();
+        assert_eq!(
+            value,
+            Expr::Array(vec![
+                Expr::Array(vec![Expr::Number(2.0)]),
+                Expr::Array(vec![Expr::Number(3.0)]),
+            ])
+        );
+
+        // The texts and the booleans of the references are ignored, but not the ones typed directly
+        let context = |reference: &Reference| match reference {
+            Reference::Cell(cell) if cell.column == 1 => Expr::String("5".to_string()),
+            Reference::Cell(_) => Expr::Bool(true),
+            _ => Expr::from_rows(vec![
+                vec![Expr::Number(1.0), Expr::Null],
+                vec![Expr::String("2".to_string()), Expr::Number(3.0)],
+            ]),
+        };
+        let values = [
+            ("=AVERAGE(A1:B2)", 2.0),
+            ("=AVERAGE(A1:B2, A1, B1)", 2.0),
+            ("=AVERAGE(A1:B2, '5', TRUE)", 2.5),
+            ("=AVERAGEA(A1:B2, B1)", 1.25),
+            ("=COUNT(A1:B2, A1)", 2.0),
+            ("=COUNTA(A1:B2, A1)", 4.0),
+            ("=COUNTBLANK(A1:B2)", 1.0),
+            ("=COUNTBLANK(C3)", 0.0),
+            ("=LARGE(A1:B2, 2)", 1.0),
+            ("=RANK.EQ(3, A1:B2)", 1.0),
+        ];
+        for (formula, expected) in values {
+            let formula = Formula::new(formula).unwrap();
+            assert_number(formula.parse_with_context(&context).unwrap(), expected);
+        }
+
+        // The dates of a timeline are their serial numbers, weekly here
+        let context = |reference: &Reference| {
+            let items = (0..6).map(|week| match reference {
+                Reference::Range(cell, _) if cell.column == 1 => {
+                    let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Days::new(7 * week);
+                    Expr::Date(date)
+                }
+                _ => Expr::Number(10.0 + 3.0 * week as f64),
+            });
+            Expr::from_rows(items.map(|item| vec![item]).collect())
+        };
+        let values = [
+            ("=FORECAST.ETS(DATE(2024, 2, 19), B1:B6, A1:A6)", 31.0),
+            ("=FORECAST.ETS.STAT(B1:B6, A1:A6, 8)", 7.0),
+        ];
+        for (formula, expected) in values {
+            let formula = Formula::new(formula).unwrap();
+            assert_number(formula.parse_with_context(&context).unwrap(), expected);
+        }
+    }
+}
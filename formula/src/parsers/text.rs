@@ -1,12 +1,11 @@
-use crate::{error::Error, Expr, Formula, Result, Rule};
-use pest::iterators::Pair;
+use crate::{ast::Node, error::Error, evaluator::Evaluator, Expr, Result, Rule};
 
-impl Formula<'_> {
-    pub(crate) fn parse_left(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
-        let num_chars = Self::get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
+impl Evaluator<'_> {
+    pub(crate) fn parse_left(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
+        let num_chars = self.get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
 
         let text = match (text, num_chars) {
             (Expr::String(text), Expr::Number(chars)) => {
@@ -20,11 +19,11 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_leftb(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
-        let num_bytes = Self::get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
+    pub(crate) fn parse_leftb(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
+        let num_bytes = self.get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
 
         let text = match (text, num_bytes) {
             (Expr::String(text), Expr::Number(bytes)) => {
@@ -40,11 +39,11 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_right(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
-        let num_chars = Self::get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
+    pub(crate) fn parse_right(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
+        let num_chars = self.get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
 
         let text = match (text, num_chars) {
             (Expr::String(text), Expr::Number(chars)) => {
@@ -62,11 +61,11 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_rightb(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
-        let num_bytes = Self::get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
+    pub(crate) fn parse_rightb(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
+        let num_bytes = self.get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
 
         let text = match (text, num_bytes) {
             (Expr::String(text), Expr::Number(bytes)) => {
@@ -85,12 +84,12 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_mid(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
-        let start = Self::get_formula(&mut args, &rule_name)?;
-        let len = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_mid(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
+        let start = self.get_formula(&mut args, &rule_name)?;
+        let len = self.get_formula(&mut args, &rule_name)?;
 
         let text = match (text, start, len) {
             (Expr::String(text), Expr::Number(start), Expr::Number(len)) => {
@@ -104,12 +103,12 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_midb(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
-        let start = Self::get_formula(&mut args, &rule_name)?;
-        let len = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_midb(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
+        let start = self.get_formula(&mut args, &rule_name)?;
+        let len = self.get_formula(&mut args, &rule_name)?;
 
         let text = match (text, start, len) {
             (Expr::String(text), Expr::Number(start), Expr::Number(len)) => {
@@ -129,10 +128,10 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_char(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let number = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_char(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_formula(&mut args, &rule_name)?;
 
         let char = match number {
             Expr::Number(number) => (number as u8 as char).to_string(),
@@ -141,10 +140,10 @@ impl Formula<'_> {
         Ok(Expr::String(char))
     }
 
-    pub(crate) fn parse_code(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_code(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
 
         let code = match text {
             Expr::String(text) => text
@@ -160,32 +159,24 @@ impl Formula<'_> {
         Ok(Expr::Number(f64::from(code)))
     }
 
-    pub(crate) fn parse_concat(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
-        let texts = args.map(Self::parse_pair).collect::<Result<Vec<_>>>()?;
-
-        let text = match text {
-            Expr::String(mut text) => {
-                for t in texts {
-                    match t {
-                        Expr::String(t) => text.push_str(&t),
-                        _ => return Err(Error::Parser(rule_name)),
-                    }
-                }
-                text
-            }
-            _ => return Err(Error::Parser(rule_name)),
-        };
-        Ok(Expr::String(text))
+    pub(crate) fn parse_concat(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
+        let texts = args.map(|arg| self.parse_arg(arg)).collect::<Result<Vec<_>>>()?;
+
+        texts
+            .into_iter()
+            .try_fold(Self::concat(text, Expr::Null, &rule_name)?, |text, t| {
+                Self::concat(text, t, &rule_name)
+            })
     }
 
-    pub(crate) fn parse_exact(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text1 = Self::get_formula(&mut args, &rule_name)?;
-        let text2 = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_exact(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text1 = self.get_formula(&mut args, &rule_name)?;
+        let text2 = self.get_formula(&mut args, &rule_name)?;
         let exact = match (text1, text2) {
             (Expr::String(text1), Expr::String(text2)) => text1 == text2,
             _ => return Err(Error::Parser(rule_name)),
@@ -193,12 +184,12 @@ impl Formula<'_> {
         Ok(Expr::Bool(exact))
     }
 
-    pub(crate) fn parse_find(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let find_text = Self::get_formula(&mut args, &rule_name)?;
-        let within_text = Self::get_formula(&mut args, &rule_name)?;
-        let start_num = Self::get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
+    pub(crate) fn parse_find(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let find_text = self.get_formula(&mut args, &rule_name)?;
+        let within_text = self.get_formula(&mut args, &rule_name)?;
+        let start_num = self.get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
 
         let index = match (find_text, within_text, start_num) {
             (Expr::String(find_text), Expr::String(within_text), Expr::Number(start_num)) => {
@@ -218,12 +209,12 @@ impl Formula<'_> {
         Ok(Expr::Number(index))
     }
 
-    pub(crate) fn parse_findb(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let find_text = Self::get_formula(&mut args, &rule_name)?;
-        let within_text = Self::get_formula(&mut args, &rule_name)?;
-        let start_num = Self::get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
+    pub(crate) fn parse_findb(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let find_text = self.get_formula(&mut args, &rule_name)?;
+        let within_text = self.get_formula(&mut args, &rule_name)?;
+        let start_num = self.get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
 
         let index = match (find_text, within_text, start_num) {
             (Expr::String(find_text), Expr::String(within_text), Expr::Number(start_num)) => {
@@ -242,12 +233,12 @@ impl Formula<'_> {
         Ok(Expr::Number(index))
     }
 
-    pub(crate) fn parse_search(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let search_text = Self::get_formula(&mut args, &rule_name)?;
-        let within_text = Self::get_formula(&mut args, &rule_name)?;
-        let start_num = Self::get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
+    pub(crate) fn parse_search(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let search_text = self.get_formula(&mut args, &rule_name)?;
+        let within_text = self.get_formula(&mut args, &rule_name)?;
+        let start_num = self.get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
 
         let index = match (search_text, within_text, start_num) {
             (Expr::String(search_text), Expr::String(within_text), Expr::Number(start_num)) => {
@@ -269,12 +260,12 @@ impl Formula<'_> {
         Ok(Expr::Number(index))
     }
 
-    pub(crate) fn parse_searchb(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let search_text = Self::get_formula(&mut args, &rule_name)?;
-        let within_text = Self::get_formula(&mut args, &rule_name)?;
-        let start_num = Self::get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
+    pub(crate) fn parse_searchb(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let search_text = self.get_formula(&mut args, &rule_name)?;
+        let within_text = self.get_formula(&mut args, &rule_name)?;
+        let start_num = self.get_opt_formula_with_default(&mut args, Expr::Number(1.0))?;
 
         let index = match (search_text, within_text, start_num) {
             (Expr::String(search_text), Expr::String(within_text), Expr::Number(start_num)) => {
@@ -295,12 +286,12 @@ impl Formula<'_> {
         Ok(Expr::Number(index))
     }
 
-    pub(crate) fn parse_fixed(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let number = Self::get_formula(&mut args, &rule_name)?;
-        let decimals = Self::get_opt_formula_with_default(&mut args, Expr::Number(2.0))?;
-        let no_commas = Self::get_opt_formula_with_default(&mut args, Expr::Bool(false))?;
+    pub(crate) fn parse_fixed(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let number = self.get_formula(&mut args, &rule_name)?;
+        let decimals = self.get_opt_formula_with_default(&mut args, Expr::Number(2.0))?;
+        let no_commas = self.get_opt_formula_with_default(&mut args, Expr::Bool(false))?;
         let text = match (number, decimals, no_commas) {
             (Expr::Number(number), Expr::Number(mut decimals), Expr::Bool(no_commas)) => {
                 let number = if decimals < 0.0 {
@@ -356,10 +347,10 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_len(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_len(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
         let len = match text {
             Expr::String(text) => text.chars().count() as f64,
             _ => return Err(Error::Parser(rule_name)),
@@ -367,10 +358,10 @@ impl Formula<'_> {
         Ok(Expr::Number(len))
     }
 
-    pub(crate) fn parse_lenb(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_lenb(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
         let len = match text {
             Expr::String(text) => text.len() as f64,
             _ => return Err(Error::Parser(rule_name)),
@@ -378,10 +369,10 @@ impl Formula<'_> {
         Ok(Expr::Number(len))
     }
 
-    pub(crate) fn parse_lower(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_lower(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
         let text = match text {
             Expr::String(text) => text.to_lowercase(),
             _ => return Err(Error::Parser(rule_name)),
@@ -389,10 +380,10 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_upper(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_upper(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
         let text = match text {
             Expr::String(text) => text.to_uppercase(),
             _ => return Err(Error::Parser(rule_name)),
@@ -400,11 +391,11 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_rept(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
-        let rept = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_rept(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
+        let rept = self.get_formula(&mut args, &rule_name)?;
         let text = match (text, rept) {
             (Expr::String(text), Expr::Number(rept)) => {
                 if rept < 0.0 {
@@ -417,13 +408,13 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_replace(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
-        let start = Self::get_formula(&mut args, &rule_name)?;
-        let len = Self::get_formula(&mut args, &rule_name)?;
-        let new_text = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_replace(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
+        let start = self.get_formula(&mut args, &rule_name)?;
+        let len = self.get_formula(&mut args, &rule_name)?;
+        let new_text = self.get_formula(&mut args, &rule_name)?;
 
         let text = match (text, start, len, new_text) {
             (Expr::String(text), Expr::Number(start), Expr::Number(len), Expr::String(new_text)) => {
@@ -439,13 +430,13 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_replaceb(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
-        let start = Self::get_formula(&mut args, &rule_name)?;
-        let len = Self::get_formula(&mut args, &rule_name)?;
-        let new_text = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_replaceb(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
+        let start = self.get_formula(&mut args, &rule_name)?;
+        let len = self.get_formula(&mut args, &rule_name)?;
+        let new_text = self.get_formula(&mut args, &rule_name)?;
 
         let text = match (text, start, len, new_text) {
             (Expr::String(text), Expr::Number(start), Expr::Number(len), Expr::String(new_text)) => {
@@ -466,13 +457,13 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_textjoin(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let delim = Self::get_formula(&mut args, &rule_name)?;
-        let ignore_empty = Self::get_formula(&mut args, &rule_name)?;
-        let text = Self::get_formula(&mut args, &rule_name)?;
-        let texts = args.map(Self::parse_pair).collect::<Result<Vec<_>>>()?;
+    pub(crate) fn parse_textjoin(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let delim = self.get_formula(&mut args, &rule_name)?;
+        let ignore_empty = self.get_formula(&mut args, &rule_name)?;
+        let text = self.get_formula(&mut args, &rule_name)?;
+        let texts = args.map(|arg| self.parse_arg(arg)).collect::<Result<Vec<_>>>()?;
 
         let text = match (delim, ignore_empty, text) {
             (Expr::String(delim), Expr::Bool(ignore_empty), Expr::String(mut text)) => {
@@ -495,10 +486,10 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_trim(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_trim(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
         let text = match text {
             Expr::String(text) => text.trim().to_string(),
             _ => return Err(Error::Parser(rule_name)),
@@ -506,10 +497,10 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_t(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_t(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
         let text = match text {
             Expr::String(text) => text,
             _ => String::new(),
@@ -517,10 +508,10 @@ impl Formula<'_> {
         Ok(Expr::String(text))
     }
 
-    pub(crate) fn parse_proper(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let text = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_proper(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let text = self.get_formula(&mut args, &rule_name)?;
         let text = match text {
             Expr::String(text) => text
                 .split_whitespace()
@@ -1,12 +1,11 @@
-use crate::{error::Error, Expr, Formula, Result, Rule};
-use pest::iterators::Pair;
+use crate::{ast::Node, error::Error, evaluator::Evaluator, Expr, Result, Rule};
 use urlencoding::encode;
 
-impl Formula<'_> {
-    pub(crate) fn parse_encodeurl(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let url = Self::get_formula(&mut args, &rule_name)?;
+impl Evaluator<'_> {
+    pub(crate) fn parse_encodeurl(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let url = self.get_formula(&mut args, &rule_name)?;
 
         let url = match url {
             Expr::String(url) => encode(&url).to_string(),
@@ -15,12 +14,12 @@ impl Formula<'_> {
         Ok(Expr::String(url))
     }
 
-    #[allow(clippy::needless_pass_by_value)]
-    pub(crate) fn parse_filterxml(pair: Pair<Rule>) -> Result<Expr> {
-        // let rule_name = format!("{:?}", &pair.as_rule());
-        // let mut args = pair.into_inner();
-        // let xml = Self::get_formula(&mut args, &rule_name)?;
-        // let xpath = Self::get_formula(&mut args, &rule_name)?;
+    #[allow(clippy::unused_self)]
+    pub(crate) fn parse_filterxml(&self, rule: Rule, _args: &[Node]) -> Result<Expr> {
+        // let rule_name = format!("{rule:?}");
+        // let mut args = args.iter();
+        // let xml = self.get_formula(&mut args, &rule_name)?;
+        // let xpath = self.get_formula(&mut args, &rule_name)?;
         //
         // let body = match (xml, xpath) {
         //     (Expr::String(xml), Expr::String(xpath)) => {
@@ -30,13 +29,13 @@ impl Formula<'_> {
         //     _ => return Err(Error::Parser(rule_name).into()),
         // };
         // Ok(Expr::String(body))
-        todo!("Not implemented: {:?}", pair.as_rule())
+        todo!("Not implemented: {rule:?}")
     }
 
-    pub(crate) fn parse_webservice(pair: Pair<Rule>) -> Result<Expr> {
-        let rule_name = format!("{:?}", &pair.as_rule());
-        let mut args = pair.into_inner();
-        let url = Self::get_formula(&mut args, &rule_name)?;
+    pub(crate) fn parse_webservice(&self, rule: Rule, args: &[Node]) -> Result<Expr> {
+        let rule_name = format!("{rule:?}");
+        let mut args = args.iter();
+        let url = self.get_formula(&mut args, &rule_name)?;
 
         let response = match url {
             Expr::String(url) => Self::web_request(&url).map_err(|_| Error::Parser(rule_name.clone()))?,
@@ -0,0 +1,432 @@
+//! Units of measurement of the `CONVERT` function, which can also be used outside formulas.
+//!
+//! The units have the same case-sensitive names as Excel, like `lbm`, `mi` or `C`, and the metric ones
+//! can have the SI prefixes, like `km` or `mg`. The information units can have the binary prefixes too, like `Mibyte`.
+//!
+//! ```rust
+//! use formula::units::{convert, Category, Unit};
+//!
+//! assert_eq!(convert(1.0, "lbm", "kg"), Some(0.453_592_37));
+//! assert!((convert(68.0, "F", "C").unwrap() - 20.0).abs() < 1e-12);
+//! assert_eq!(convert(1.0, "kibyte", "bit"), Some(8_192.0));
+//! // The units should be of the same category
+//! assert_eq!(convert(2.5, "ft", "sec"), None);
+//!
+//! let unit = Unit::parse("km/h").unwrap();
+//! assert_eq!(unit.category(), Category::Speed);
+//! ```
+
+use crate::parsers::helper::to_decimal;
+
+/// The category of a unit. Only the units of the same category can be converted to each other.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Category {
+    Mass,
+    Distance,
+    Time,
+    Pressure,
+    Force,
+    Energy,
+    Power,
+    Magnetism,
+    Temperature,
+    Volume,
+    Area,
+    Information,
+    Speed,
+}
+
+/// A unit of measurement, like `km` or `lbm`.
+///
+/// It's kept as its factor and offset of the base unit of its category, which is the SI unit, like `m` or `kg`,
+/// or `bit` for the information units.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Unit {
+    category: Category,
+    factor: f64,
+    offset: f64,
+}
+
+/// A unit of the table, with all of its names
+struct Definition {
+    names: &'static [&'static str],
+    category: Category,
+    factor: f64,
+    offset: f64,
+    /// The power of the prefix in the factor of the unit, like `2` for the areas, or `0` if it can't have a prefix
+    prefix_power: i32,
+}
+
+const fn unit(names: &'static [&'static str], category: Category, factor: f64) -> Definition {
+    Definition {
+        names,
+        category,
+        factor,
+        offset: 0.0,
+        prefix_power: 0,
+    }
+}
+
+const fn metric(names: &'static [&'static str], category: Category, factor: f64, prefix_power: i32) -> Definition {
+    Definition {
+        names,
+        category,
+        factor,
+        offset: 0.0,
+        prefix_power,
+    }
+}
+
+const fn temperature(names: &'static [&'static str], factor: f64, offset: f64, prefix_power: i32) -> Definition {
+    Definition {
+        names,
+        category: Category::Temperature,
+        factor,
+        offset,
+        prefix_power,
+    }
+}
+
+const INCH: f64 = 0.0254;
+const FOOT: f64 = 0.3048;
+const YARD: f64 = 0.9144;
+const MILE: f64 = 1_609.344;
+const NAUTICAL_MILE: f64 = 1_852.0;
+const LIGHT_YEAR: f64 = 9.460_730_472_580_8e15;
+const PICA_POINT: f64 = INCH / 72.0;
+const SURVEY_FOOT: f64 = 1_200.0 / 3_937.0;
+const POUND: f64 = 0.453_592_37;
+const POUND_FORCE: f64 = 4.448_221_615_260_5;
+const US_GALLON: f64 = 3.785_411_784e-3;
+
+/// The units of Excel, by their SI factors
+const UNITS: &[Definition] = &[
+    // Mass, by kilogram
+    metric(&["g"], Category::Mass, 1e-3, 1),
+    unit(&["sg"], Category::Mass, POUND_FORCE / FOOT),
+    unit(&["lbm"], Category::Mass, POUND),
+    metric(&["u"], Category::Mass, 1.660_538_782e-27, 1),
+    unit(&["ozm"], Category::Mass, POUND / 16.0),
+    unit(&["grain"], Category::Mass, POUND / 7_000.0),
+    unit(&["cwt", "shweight"], Category::Mass, POUND * 100.0),
+    unit(&["uk_cwt", "lcwt", "hweight"], Category::Mass, POUND * 112.0),
+    unit(&["stone"], Category::Mass, POUND * 14.0),
+    unit(&["ton"], Category::Mass, POUND * 2_000.0),
+    unit(&["uk_ton", "LTON", "brton"], Category::Mass, POUND * 2_240.0),
+    // Distance, by meter
+    metric(&["m"], Category::Distance, 1.0, 1),
+    unit(&["mi"], Category::Distance, MILE),
+    unit(&["Nmi"], Category::Distance, NAUTICAL_MILE),
+    unit(&["in"], Category::Distance, INCH),
+    unit(&["ft"], Category::Distance, FOOT),
+    unit(&["yd"], Category::Distance, YARD),
+    metric(&["ang"], Category::Distance, 1e-10, 1),
+    unit(&["ell"], Category::Distance, INCH * 45.0),
+    metric(&["ly"], Category::Distance, LIGHT_YEAR, 1),
+    metric(&["parsec", "pc"], Category::Distance, 3.085_677_581_281_55e16, 1),
+    unit(&["Picapt", "Pica"], Category::Distance, PICA_POINT),
+    unit(&["pica"], Category::Distance, INCH / 6.0),
+    unit(&["survey_mi"], Category::Distance, SURVEY_FOOT * 5_280.0),
+    // Time, by second
+    unit(&["yr"], Category::Time, 365.25 * 86_400.0),
+    unit(&["day", "d"], Category::Time, 86_400.0),
+    unit(&["hr"], Category::Time, 3_600.0),
+    unit(&["mn", "min"], Category::Time, 60.0),
+    metric(&["sec", "s"], Category::Time, 1.0, 1),
+    // Pressure, by pascal
+    metric(&["Pa", "p"], Category::Pressure, 1.0, 1),
+    metric(&["atm", "at"], Category::Pressure, 101_325.0, 1),
+    metric(&["mmHg"], Category::Pressure, 133.322, 1),
+    unit(&["psi"], Category::Pressure, POUND_FORCE / (INCH * INCH)),
+    unit(&["Torr"], Category::Pressure, 101_325.0 / 760.0),
+    // Force, by newton
+    metric(&["N"], Category::Force, 1.0, 1),
+    metric(&["dyn", "dy"], Category::Force, 1e-5, 1),
+    unit(&["lbf"], Category::Force, POUND_FORCE),
+    metric(&["pond"], Category::Force, 9.806_65e-3, 1),
+    // Energy, by joule
+    metric(&["J"], Category::Energy, 1.0, 1),
+    metric(&["e"], Category::Energy, 1e-7, 1),
+    metric(&["c"], Category::Energy, 4.184, 1),
+    metric(&["cal"], Category::Energy, 4.186_8, 1),
+    metric(&["eV", "ev"], Category::Energy, 1.602_176_487e-19, 1),
+    unit(&["HPh", "hh"], Category::Energy, 550.0 * FOOT * POUND_FORCE * 3_600.0),
+    metric(&["Wh", "wh"], Category::Energy, 3_600.0, 1),
+    unit(&["flb"], Category::Energy, FOOT * POUND_FORCE),
+    unit(&["BTU", "btu"], Category::Energy, 1_055.055_852_62),
+    // Power, by watt
+    unit(&["HP", "h"], Category::Power, 550.0 * FOOT * POUND_FORCE),
+    unit(&["PS"], Category::Power, 735.498_75),
+    metric(&["W", "w"], Category::Power, 1.0, 1),
+    // Magnetism, by tesla
+    metric(&["T"], Category::Magnetism, 1.0, 1),
+    metric(&["ga"], Category::Magnetism, 1e-4, 1),
+    // Temperature, by kelvin
+    temperature(&["C", "cel"], 1.0, 273.15, 0),
+    temperature(&["F", "fah"], 5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0, 0),
+    temperature(&["K", "kel"], 1.0, 0.0, 1),
+    temperature(&["Rank"], 5.0 / 9.0, 0.0, 0),
+    temperature(&["Reau"], 1.25, 273.15, 0),
+    // Volume, by cubic meter
+    unit(&["tsp"], Category::Volume, US_GALLON / 768.0),
+    unit(&["tspm"], Category::Volume, 5e-6),
+    unit(&["tbs"], Category::Volume, US_GALLON / 256.0),
+    unit(&["oz"], Category::Volume, US_GALLON / 128.0),
+    unit(&["cup"], Category::Volume, US_GALLON / 16.0),
+    unit(&["pt", "us_pt"], Category::Volume, US_GALLON / 8.0),
+    unit(&["uk_pt"], Category::Volume, 5.682_612_5e-4),
+    unit(&["qt"], Category::Volume, US_GALLON / 4.0),
+    unit(&["uk_qt"], Category::Volume, 1.136_522_5e-3),
+    unit(&["gal"], Category::Volume, US_GALLON),
+    unit(&["uk_gal"], Category::Volume, 4.546_09e-3),
+    metric(&["l", "L", "lt"], Category::Volume, 1e-3, 1),
+    metric(&["ang3", "ang^3"], Category::Volume, 1e-30, 3),
+    unit(&["barrel"], Category::Volume, US_GALLON * 42.0),
+    unit(&["bushel"], Category::Volume, 0.035_239_070_166_88),
+    unit(&["ft3", "ft^3"], Category::Volume, FOOT * FOOT * FOOT),
+    unit(&["in3", "in^3"], Category::Volume, INCH * INCH * INCH),
+    metric(
+        &["ly3", "ly^3"],
+        Category::Volume,
+        LIGHT_YEAR * LIGHT_YEAR * LIGHT_YEAR,
+        3,
+    ),
+    metric(&["m3", "m^3"], Category::Volume, 1.0, 3),
+    unit(&["mi3", "mi^3"], Category::Volume, MILE * MILE * MILE),
+    unit(&["yd3", "yd^3"], Category::Volume, YARD * YARD * YARD),
+    unit(
+        &["Nmi3", "Nmi^3"],
+        Category::Volume,
+        NAUTICAL_MILE * NAUTICAL_MILE * NAUTICAL_MILE,
+    ),
+    unit(
+        &["Picapt3", "Picapt^3", "Pica3", "Pica^3"],
+        Category::Volume,
+        PICA_POINT * PICA_POINT * PICA_POINT,
+    ),
+    unit(&["GRT", "regton"], Category::Volume, FOOT * FOOT * FOOT * 100.0),
+    unit(&["MTON"], Category::Volume, FOOT * FOOT * FOOT * 40.0),
+    // Area, by square meter
+    unit(&["uk_acre"], Category::Area, YARD * YARD * 4_840.0),
+    unit(&["us_acre"], Category::Area, SURVEY_FOOT * SURVEY_FOOT * 43_560.0),
+    metric(&["ang2", "ang^2"], Category::Area, 1e-20, 2),
+    metric(&["ar"], Category::Area, 100.0, 1),
+    unit(&["ft2", "ft^2"], Category::Area, FOOT * FOOT),
+    unit(&["ha"], Category::Area, 10_000.0),
+    unit(&["in2", "in^2"], Category::Area, INCH * INCH),
+    metric(&["ly2", "ly^2"], Category::Area, LIGHT_YEAR * LIGHT_YEAR, 2),
+    metric(&["m2", "m^2"], Category::Area, 1.0, 2),
+    unit(&["Morgen"], Category::Area, 2_500.0),
+    unit(&["mi2", "mi^2"], Category::Area, MILE * MILE),
+    unit(&["Nmi2", "Nmi^2"], Category::Area, NAUTICAL_MILE * NAUTICAL_MILE),
+    unit(
+        &["Picapt2", "Pica2", "Pica^2", "Picapt^2"],
+        Category::Area,
+        PICA_POINT * PICA_POINT,
+    ),
+    unit(&["yd2", "yd^2"], Category::Area, YARD * YARD),
+    // Information, by bit
+    metric(&["bit"], Category::Information, 1.0, 1),
+    metric(&["byte"], Category::Information, 8.0, 1),
+    // Speed, by meter per second
+    unit(&["admkn"], Category::Speed, FOOT * 6_080.0 / 3_600.0),
+    unit(&["kn"], Category::Speed, NAUTICAL_MILE / 3_600.0),
+    metric(&["m/h", "m/hr"], Category::Speed, 1.0 / 3_600.0, 1),
+    metric(&["m/s", "m/sec"], Category::Speed, 1.0, 1),
+    unit(&["mph"], Category::Speed, MILE / 3_600.0),
+];
+
+/// The SI prefixes, where the longer ones come first, so `da` is not parsed as `d`
+const PREFIXES: &[(&str, f64)] = &[
+    ("da", 1e1),
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("e", 1e1),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+    ("a", 1e-18),
+    ("z", 1e-21),
+    ("y", 1e-24),
+];
+
+/// The binary prefixes of the information units, by their powers of two
+const BINARY_PREFIXES: &[(&str, i32)] = &[
+    ("Yi", 80),
+    ("Zi", 70),
+    ("Ei", 60),
+    ("Pi", 50),
+    ("Ti", 40),
+    ("Gi", 30),
+    ("Mi", 20),
+    ("ki", 10),
+];
+
+impl Definition {
+    fn find(name: &str) -> Option<&'static Self> {
+        UNITS.iter().find(|definition| definition.names.contains(&name))
+    }
+
+    fn to_unit(&self, prefix: f64) -> Unit {
+        Unit {
+            category: self.category,
+            factor: self.factor * prefix.powi(self.prefix_power),
+            offset: self.offset,
+        }
+    }
+}
+
+impl Unit {
+    /// Parse a unit by its case-sensitive name, like `km` or `lbm`, or return `None` if it's not known.
+    ///
+    /// The names of the units are preferred to the prefixed ones, so `mi` is a mile, not a milli-inch.
+    pub fn parse(name: &str) -> Option<Self> {
+        if let Some(definition) = Definition::find(name) {
+            return Some(definition.to_unit(1.0));
+        }
+
+        let binary = BINARY_PREFIXES.iter().find_map(|&(prefix, power)| {
+            let definition = Definition::find(name.strip_prefix(prefix)?)?;
+            (definition.category == Category::Information).then(|| definition.to_unit(2_f64.powi(power)))
+        });
+        binary.or_else(|| {
+            PREFIXES.iter().find_map(|&(prefix, factor)| {
+                let definition = Definition::find(name.strip_prefix(prefix)?)?;
+                (definition.prefix_power != 0).then(|| definition.to_unit(factor))
+            })
+        })
+    }
+
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    /// Convert a number of this unit to another unit, or return `None` if they are not of the same category.
+    ///
+    /// The result is rounded to 15 significant digits, like Excel, so `N` to `dyn` is `100000`, not `99999.99999999999`.
+    pub fn convert(self, number: f64, to: Unit) -> Option<f64> {
+        if self.category != to.category {
+            return None;
+        }
+        if self == to {
+            return Some(number);
+        }
+        Some(to_decimal((number * self.factor + self.offset - to.offset) / to.factor))
+    }
+}
+
+/// Convert a number from a unit to another one, by their names, like `convert(1.0, "mi", "km")`,
+/// or return `None` if a unit is not known, or they are not of the same category
+pub fn convert(number: f64, from: &str, to: &str) -> Option<f64> {
+    Unit::parse(from)?.convert(number, Unit::parse(to)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        let conversions = [
+            (1.0, "lbm", "kg", 0.453_592_37),
+            (1.0, "ozm", "g", 28.349_523_125),
+            (1.0, "ton", "uk_ton", 0.892_857_142_857_142_8),
+            (1.0, "sg", "lbm", 32.174_048_556_430_44),
+            (1.0, "mi", "km", 1.609_344),
+            (1.0, "ft", "in", 12.0),
+            (6.0, "pica", "in", 1.0),
+            (72.0, "Pica", "in", 1.0),
+            (1.0, "Nmi", "m", 1_852.0),
+            (1.0, "survey_mi", "ft", 5_280.010_560_021_119),
+            (1.0, "pc", "ly", 3.261_563_776_945_656_5),
+            (1.0, "nm", "ang", 10.0),
+            (1.0, "dam", "m", 10.0),
+            (1.0, "yr", "day", 365.25),
+            (1.0, "ms", "sec", 0.001),
+            (1.0, "atm", "mmHg", 760.002_100_178_515_2),
+            (1.0, "atm", "Torr", 760.0),
+            (1.0, "psi", "kPa", 6.894_757_293_168_361),
+            (1.0, "lbf", "N", 4.448_221_615_260_5),
+            (1.0, "N", "dyn", 100_000.0),
+            (1.0, "BTU", "J", 1_055.055_852_62),
+            (1.0, "kWh", "J", 3_600_000.0),
+            (1.0, "cal", "c", 1.000_669_216_061_185_4),
+            (1.0, "HPh", "Wh", 745.699_871_582_270_2),
+            (1.0, "HP", "W", 745.699_871_582_270_2),
+            (1.0, "T", "ga", 10_000.0),
+            (68.0, "F", "C", 20.0),
+            (100.0, "C", "F", 212.0),
+            (0.0, "K", "C", -273.15),
+            (100.0, "cel", "Reau", 80.0),
+            (491.67, "Rank", "F", 32.0),
+            (1.0, "mK", "K", 0.001),
+            (1.0, "gal", "l", 3.785_411_784),
+            (1.0, "gal", "tsp", 768.0),
+            (1.0, "uk_gal", "uk_pt", 8.0),
+            (1.0, "barrel", "gal", 42.0),
+            (1.0, "m3", "cm3", 1_000_000.0),
+            (1.0, "ft^3", "in3", 1_728.0),
+            (1.0, "GRT", "ft3", 100.0),
+            (1.0, "mL", "cm3", 1.0),
+            (1.0, "km2", "m2", 1_000_000.0),
+            (1.0, "ha", "ar", 100.0),
+            (1.0, "uk_acre", "yd2", 4_840.0),
+            (1.0, "us_acre", "uk_acre", 1.000_004_000_012),
+            (100.0, "m2", "ft2", 1_076.391_041_670_972),
+            (1.0, "byte", "bit", 8.0),
+            (1.0, "kbyte", "byte", 1_000.0),
+            (1.0, "kibyte", "byte", 1_024.0),
+            (1.0, "Mibit", "kibit", 1_024.0),
+            (1.0, "mph", "km/h", 1.609_344),
+            (1.0, "kn", "m/h", 1_852.0),
+            (1.0, "admkn", "kn", 1.000_639_308_855_291_6),
+            (1.0, "m/s", "m/hr", 3_600.0),
+        ];
+        let exact = [
+            (1.0, "N", "dyn", 100_000.0),
+            (1.0, "kn", "m/h", 1_852.0),
+            (1.0, "mi", "km", 1.609_344),
+        ];
+        for (number, from, to, expected) in exact {
+            assert_eq!(convert(number, from, to), Some(expected), "{from} to {to}");
+        }
+
+        for (number, from, to, expected) in conversions {
+            let value = convert(number, from, to).unwrap();
+            assert!(
+                (value - expected).abs() <= expected.abs() * 1e-14,
+                "{from} to {to}: {value} != {expected}"
+            );
+        }
+
+        let invalid = [
+            ("ft", "sec"),
+            ("C", "J"),
+            ("g", "m"),
+            ("FT", "m"),
+            ("kft", "m"),
+            ("kibyte", "kim"),
+            ("", "m"),
+            ("m", "xyz"),
+        ];
+        for (from, to) in invalid {
+            assert_eq!(convert(1.0, from, to), None, "{from} to {to}");
+        }
+
+        assert_eq!(Unit::parse("mi").unwrap().category(), Category::Distance);
+        assert_eq!(Unit::parse("Pa").unwrap().category(), Category::Pressure);
+        assert_eq!(Unit::parse("pc").unwrap().category(), Category::Distance);
+        assert_eq!(Unit::parse("Gibyte").unwrap().category(), Category::Information);
+    }
+}